@@ -0,0 +1,286 @@
+#[cfg(feature = "cli")]
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+#[cfg(feature = "cli")]
+use std::collections::BTreeMap;
+#[cfg(feature = "cli")]
+use std::path::Path;
+#[cfg(feature = "cli")]
+use std::process::Command;
+
+#[cfg(feature = "cli")]
+use crate::diff::{self, WriteMode};
+#[cfg(feature = "cli")]
+use crate::i18n::tr;
+use crate::metadata::{fields, groups};
+
+/// One TODO to annotate, read from the mapping file
+#[cfg(feature = "cli")]
+struct Annotation {
+    line_number: usize,
+    issue: String,
+}
+
+/// Read a mapping file of "file<TAB>line<TAB>issue" records (the picker format with the
+/// text column replaced by the issue key). Blank lines and lines starting with '#' are skipped.
+#[cfg(feature = "cli")]
+fn read_mapping(mapping: &Path) -> Result<BTreeMap<String, Vec<Annotation>>> {
+    let content = std::fs::read_to_string(mapping)
+        .with_context(|| tr!("Failed to read mapping file: {}", mapping.display()))?;
+
+    let mut by_file: BTreeMap<String, Vec<Annotation>> = BTreeMap::new();
+    for (idx, line) in content.lines().enumerate() {
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut parts = line.splitn(3, '\t');
+        let (file, line_number, issue) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(f), Some(l), Some(i)) => (f, l, i.trim()),
+            _ => anyhow::bail!(tr!(
+                "{}:{}: expected \"file<TAB>line<TAB>issue\"",
+                mapping.display(),
+                idx + 1
+            )),
+        };
+        let line_number = line_number
+            .trim()
+            .parse()
+            .with_context(|| tr!("{}:{}: invalid line number", mapping.display(), idx + 1))?;
+        by_file
+            .entry(file.to_string())
+            .or_default()
+            .push(Annotation {
+                line_number,
+                issue: issue.to_string(),
+            });
+    }
+
+    Ok(by_file)
+}
+
+/// Insert "(ISSUE)" right after the first occurrence of the pattern, or add it to an owner
+/// tag that is already there ("TODO(bob, ISSUE)").
+/// Returns None if the pattern is missing or already carries a reference.
+pub fn annotate_line(line: &str, pattern: &str, issue: &str) -> Option<String> {
+    let idx = line.find(pattern)?;
+    let end = idx + pattern.len();
+    if has_issue_reference(line, pattern) {
+        return None;
+    }
+    if line[end..].starts_with('(') {
+        let close = end + line[end..].find(')')?;
+        return Some(format!("{}, {}{}", &line[..close], issue, &line[close..]));
+    }
+    Some(format!("{}({}){}", &line[..end], issue, &line[end..]))
+}
+
+/// Whether an issue key looks like a tracker reference: "#482", "AB#482", "ENG-42" or a URL
+fn is_issue_key(key: &str) -> bool {
+    // Azure Boards writes work items as "AB#482"
+    if let Some(number) = key.strip_prefix('#').or_else(|| key.strip_prefix("AB#")) {
+        return !number.is_empty() && number.chars().all(|c| c.is_ascii_digit());
+    }
+    if key.starts_with("http://") || key.starts_with("https://") {
+        return true;
+    }
+    match key.split_once('-') {
+        Some((project, number)) => {
+            project.starts_with(|c: char| c.is_ascii_uppercase())
+                && project
+                    .chars()
+                    .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+                && !number.is_empty()
+                && number.chars().all(|c| c.is_ascii_digit())
+        }
+        None => false,
+    }
+}
+
+/// Issue keys in the tag or metadata after the pattern:
+/// "TODO(bob, #482)" -> ["#482"], "TODO[ticket=ENG-42]" -> ["ENG-42"]
+pub fn issue_references<'a>(line: &'a str, pattern: &str) -> Vec<&'a str> {
+    line.match_indices(pattern)
+        .flat_map(|(idx, _)| {
+            let rest = &line[idx + pattern.len()..];
+            let tagged = groups(rest)
+                .0
+                .into_iter()
+                .flat_map(|tag| tag.split([',', ' ']).map(str::trim))
+                .filter(|part| is_issue_key(part));
+            let fields = fields(rest)
+                .into_iter()
+                .filter(|(key, _)| matches!(*key, "ticket" | "issue"))
+                .map(|(_, value)| value);
+            tagged.chain(fields).collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Owner named after the pattern, as in "TODO(bob, #482)" or "TODO[owner=bob]" -> "bob"
+pub fn owner<'a>(line: &'a str, pattern: &str) -> Option<&'a str> {
+    let idx = line.find(pattern)?;
+    let rest = &line[idx + pattern.len()..];
+    if let Some((_, owner)) = fields(rest).into_iter().find(|(key, _)| *key == "owner") {
+        return Some(owner);
+    }
+    groups(rest)
+        .0?
+        .split([',', ' '])
+        .map(str::trim)
+        .find(|part| !part.is_empty() && !is_issue_key(part) && !is_date(part))
+}
+
+fn is_date(s: &str) -> bool {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").is_ok()
+}
+
+/// Deadline written on the line: "due" in the metadata ("TODO[due=2025-12-01]"), a date in
+/// the tag ("TODO(bob, 2025-12-01)"), or a date after "due", "by", "until", "before" or "deadline"
+pub fn deadline(line: &str, pattern: &str) -> Option<NaiveDate> {
+    let idx = line.find(pattern)?;
+    let rest = &line[idx + pattern.len()..];
+
+    let parse =
+        |w: &str| NaiveDate::parse_from_str(w.trim_end_matches(['.', ':']), "%Y-%m-%d").ok();
+
+    if let Some((_, due)) = fields(rest).into_iter().find(|(key, _)| *key == "due") {
+        return parse(due);
+    }
+
+    // Inside the tag any date counts
+    if let Some(tag) = groups(rest).0 {
+        if let Some(date) = tag.split([',', ' ']).find_map(|w| parse(w.trim())) {
+            return Some(date);
+        }
+    }
+
+    let words: Vec<&str> = rest
+        .split(|c: char| c.is_whitespace() || matches!(c, '(' | ')' | ',' | ';'))
+        .filter(|w| !w.is_empty())
+        .collect();
+    words.iter().enumerate().find_map(|(i, word)| {
+        // "due:2025-12-01" and "due: 2025-12-01" both work
+        let (keyword, inline) = word.split_once(':').unwrap_or((word, ""));
+        let keyword = keyword.to_lowercase();
+        if !matches!(
+            keyword.as_str(),
+            "due" | "by" | "until" | "before" | "deadline"
+        ) {
+            return None;
+        }
+        parse(inline).or_else(|| words.get(i + 1).and_then(|next| parse(next)))
+    })
+}
+
+/// Whether the pattern on this line carries an issue reference, as in "TODO(#482)".
+/// An owner tag like "TODO(alice)" does not count.
+pub fn has_issue_reference(line: &str, pattern: &str) -> bool {
+    !issue_references(line, pattern).is_empty()
+}
+
+/// Rewrite the TODO lines listed in the mapping file to include their issue keys. Diffs
+/// name the files relative to `root`, the repository root.
+#[cfg(feature = "cli")]
+pub fn annotate(
+    mapping: &Path,
+    pattern: &str,
+    directory: &Path,
+    root: &Path,
+    mode: WriteMode,
+    commit: bool,
+) -> Result<()> {
+    let by_file = read_mapping(mapping)?;
+
+    let mut annotated = 0;
+    let mut changed_files = Vec::new();
+
+    for (file, annotations) in &by_file {
+        let file_path = directory.join(file);
+        let content = std::fs::read_to_string(&file_path)
+            .with_context(|| tr!("Failed to read file: {}", file_path.display()))?;
+
+        // Keep the original line endings by splitting inclusively
+        let original: Vec<String> = content.split_inclusive('\n').map(String::from).collect();
+        let mut lines = original.clone();
+        let mut changed = false;
+
+        for a in annotations {
+            let Some(line) = lines.get_mut(a.line_number.wrapping_sub(1)) else {
+                eprintln!(
+                    "{}",
+                    tr!("{}:{}: no such line, skipping", file, a.line_number)
+                );
+                continue;
+            };
+            match annotate_line(line, pattern, &a.issue) {
+                Some(new_line) => {
+                    *line = new_line;
+                    changed = true;
+                    annotated += 1;
+                }
+                None => eprintln!(
+                    "{}",
+                    tr!(
+                        "{}:{}: no unannotated '{}' on this line, skipping",
+                        file,
+                        a.line_number,
+                        pattern
+                    )
+                ),
+            }
+        }
+
+        if !changed {
+            continue;
+        }
+        if mode.shows_diff() {
+            let path = diff::repo_path(&file_path, root);
+            print!("{}", diff::unified(&path, &original, &lines));
+        }
+        if mode.writes() {
+            std::fs::write(&file_path, lines.concat())
+                .with_context(|| tr!("Failed to write file: {}", file_path.display()))?;
+        }
+        changed_files.push(file.clone());
+    }
+
+    if mode.writes() {
+        println!(
+            "{}",
+            tr!(
+                "Annotated {} '{}'(s) in {} file(s).",
+                annotated,
+                pattern,
+                changed_files.len()
+            )
+        );
+    } else {
+        // On stderr, so the diff on stdout can go straight to `git apply`
+        eprintln!(
+            "{}",
+            tr!(
+                "Would annotate {} '{}'(s) in {} file(s).",
+                annotated,
+                pattern,
+                changed_files.len()
+            )
+        );
+    }
+
+    if commit && !changed_files.is_empty() {
+        let status = Command::new("git")
+            .arg("commit")
+            .arg("-m")
+            .arg(format!("Annotate {} with issue references", pattern))
+            .arg("--")
+            .args(&changed_files)
+            .current_dir(directory)
+            .status()
+            .with_context(|| tr!("Failed to execute git commit"))?;
+        if !status.success() {
+            anyhow::bail!(tr!("git commit failed"));
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,115 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+
+use crate::i18n::tr;
+
+/// An archive unpacked during this run
+struct Unpacked {
+    archive: PathBuf,
+    /// The temporary directory, removed when fask exits
+    dir: PathBuf,
+    /// What stands in for the archive: `dir` or its only directory
+    target: PathBuf,
+}
+
+/// What was unpacked, so arguments parsed twice (see `profile::apply`) are unpacked once
+static UNPACKED: Mutex<Vec<Unpacked>> = Mutex::new(Vec::new());
+
+/// How to unpack an archive into a directory, by its file name
+fn unpacker(name: &str) -> Option<fn(&Path, &Path) -> Command> {
+    let name = name.to_lowercase();
+    if name.ends_with(".bundle") {
+        // A clone keeps the history, so the history commands work too
+        Some(|archive, into| {
+            let mut cmd = Command::new("git");
+            cmd.args(["clone", "--quiet"]).arg(archive).arg(into);
+            cmd
+        })
+    } else if name.ends_with(".zip") {
+        Some(|archive, into| {
+            let mut cmd = Command::new("unzip");
+            cmd.arg("-q").arg(archive).arg("-d").arg(into);
+            cmd
+        })
+    } else if [".tar", ".tar.gz", ".tgz", ".tar.bz2", ".tar.xz", ".tar.zst"]
+        .iter()
+        .any(|ext| name.ends_with(ext))
+    {
+        // tar works the compression out itself when extracting
+        Some(|archive, into| {
+            let mut cmd = Command::new("tar");
+            cmd.arg("-xf").arg(archive).arg("-C").arg(into);
+            cmd
+        })
+    } else {
+        None
+    }
+}
+
+/// `--directory`, where a git bundle, tarball or zip file is unpacked into a temporary
+/// directory that stands in for it. A single top-level directory in the archive (as in
+/// release tarballs) is the target itself.
+pub fn target(arg: &str) -> Result<PathBuf, String> {
+    let path = PathBuf::from(arg);
+    let name = path.file_name().map(|n| n.to_string_lossy().into_owned());
+    let Some(unpack) = name.as_deref().and_then(unpacker) else {
+        return Ok(path);
+    };
+    if !path.is_file() {
+        return Ok(path);
+    }
+
+    let mut unpacked = UNPACKED.lock().map_err(|e| e.to_string())?;
+    if let Some(done) = unpacked.iter().find(|u| u.archive == path) {
+        return Ok(done.target.clone());
+    }
+    let into = std::env::temp_dir().join(format!("fask-{}-{}", std::process::id(), unpacked.len()));
+    std::fs::create_dir_all(&into).map_err(|e| tr!("can't create {}: {}", into.display(), e))?;
+
+    let mut cmd = unpack(&path, &into);
+    let program = cmd.get_program().to_string_lossy().into_owned();
+    let failure = match cmd.output() {
+        Ok(output) if output.status.success() => None,
+        Ok(output) => Some(format!(
+            "failed to unpack {}: {}",
+            arg,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )),
+        Err(e) => Some(format!(
+            "failed to run {} to unpack {}: {}",
+            program, arg, e
+        )),
+    };
+    if let Some(failure) = failure {
+        let _ = std::fs::remove_dir_all(&into);
+        return Err(failure);
+    }
+    tracing::debug!(archive = arg, into = %into.display(), "unpacked");
+
+    let entries: Vec<PathBuf> = std::fs::read_dir(&into)
+        .map(|dir| dir.flatten().map(|e| e.path()).collect())
+        .unwrap_or_default();
+    let target = match &entries[..] {
+        [only] if only.is_dir() && !only.ends_with(".git") => only.clone(),
+        _ => into.clone(),
+    };
+    unpacked.push(Unpacked {
+        archive: path,
+        dir: into,
+        target: target.clone(),
+    });
+    Ok(target)
+}
+
+/// Remove what was unpacked
+pub fn cleanup() {
+    let Ok(unpacked) = UNPACKED.lock() else {
+        return;
+    };
+    for u in unpacked.iter() {
+        if let Err(e) = std::fs::remove_dir_all(&u.dir) {
+            tracing::debug!(dir = %u.dir.display(), "failed to remove: {}", e);
+        }
+    }
+}
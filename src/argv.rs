@@ -0,0 +1,64 @@
+use clap::{Arg, CommandFactory};
+use std::ffi::OsString;
+
+use crate::Cli;
+
+/// Insert `extra` right after the subcommand in `args`, so it can't end up behind a "--".
+/// Leaves `args` as they are if there is no subcommand.
+pub fn insert_after_subcommand(args: &mut Vec<OsString>, extra: Vec<OsString>) {
+    if let Some(position) = subcommand_position(args) {
+        args.splice(position + 1..position + 1, extra);
+    }
+}
+
+/// Index in `args` of the subcommand: the first word that is neither a top-level option
+/// nor the value of one, as clap reads the command line. A word equal to the subcommand's
+/// name can come first as an option's value, e.g. `--locale current current`.
+fn subcommand_position(args: &[OsString]) -> Option<usize> {
+    let mut command = Cli::command();
+    command.build();
+    let takes_value = |arg: &Arg| arg.get_num_args().is_some_and(|n| n.takes_values());
+    let long = |name: &str| {
+        command.get_arguments().find(|a| {
+            a.get_long() == Some(name)
+                || a.get_all_aliases()
+                    .is_some_and(|aliases| aliases.contains(&name))
+        })
+    };
+    let short = |c: char| {
+        command.get_arguments().find(|a| {
+            a.get_short() == Some(c)
+                || a.get_all_short_aliases()
+                    .is_some_and(|aliases| aliases.contains(&c))
+        })
+    };
+
+    let mut index = 1;
+    while let Some(word) = args.get(index) {
+        let word = word.to_string_lossy();
+        if word == "--" {
+            return None;
+        }
+        if let Some(name) = word.strip_prefix("--") {
+            // `--name value`; `--name=value` is one word
+            if !name.contains('=') && long(name).is_some_and(takes_value) {
+                index += 1;
+            }
+        } else if let Some(shorts) = word.strip_prefix('-').filter(|s| !s.is_empty()) {
+            // In a group like `-vv` or `-lde`, the first option with a value takes the
+            // rest of the word, or the next word if it's last
+            for (at, c) in shorts.char_indices() {
+                if short(c).is_some_and(takes_value) {
+                    if at + c.len_utf8() == shorts.len() {
+                        index += 1;
+                    }
+                    break;
+                }
+            }
+        } else {
+            return Some(index);
+        }
+        index += 1;
+    }
+    None
+}
@@ -0,0 +1,261 @@
+use anyhow::{Context, Result};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::process::Command;
+
+use crate::annotate::owner;
+use crate::blame::{blame_lines, NOT_COMMITTED};
+use crate::i18n::tr;
+use crate::metadata::groups;
+use crate::pr::git;
+use crate::state::repo_root;
+use crate::style::{self, Element};
+use crate::{collect_current_matches, find_todo, CurrentMatch};
+use fask::diff::{self, WriteMode};
+
+/// Lines above and below a TODO whose authors count towards its suggested owner
+const NEIGHBORHOOD: usize = 5;
+
+/// Recent commits to the file whose authors count too
+const RECENT_COMMITS: &str = "20";
+
+/// Suggest an owner for each of the lines of one file: whoever wrote most of the code
+/// around it (two points per line), with the file's recent authors as a tie-breaker (one
+/// point per commit). Lines nobody committed yet don't count. Keyed by line number.
+pub fn suggest_owners(file: &str, line_numbers: &[usize]) -> HashMap<usize, String> {
+    let mut suggestions = HashMap::new();
+    let path = Path::new(file);
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return suggestions;
+    };
+    let line_count = content.lines().count();
+
+    let mut neighbors: Vec<usize> = line_numbers
+        .iter()
+        .flat_map(|&n| n.saturating_sub(NEIGHBORHOOD).max(1)..=(n + NEIGHBORHOOD).min(line_count))
+        .collect();
+    neighbors.sort();
+    neighbors.dedup();
+    let blamed = blame_lines(file, &neighbors);
+
+    let dir = path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let name = path.file_name().map(|n| n.to_string_lossy().into_owned());
+    let recent = match name {
+        Some(name) => git(
+            &["log", "-n", RECENT_COMMITS, "--format=%aN", "--", &name],
+            dir,
+        )
+        .unwrap_or_default(),
+        None => String::new(),
+    };
+
+    for &n in line_numbers {
+        let mut scores: HashMap<&str, usize> = HashMap::new();
+        let window = n.saturating_sub(NEIGHBORHOOD).max(1)..=n + NEIGHBORHOOD;
+        for info in window.filter_map(|i| blamed.get(&i)) {
+            if info.author != NOT_COMMITTED {
+                *scores.entry(info.author.as_str()).or_default() += 2;
+            }
+        }
+        for author in recent.lines().filter(|a| !a.is_empty()) {
+            *scores.entry(author).or_default() += 1;
+        }
+        // Ties go to the name that sorts first, so the suggestion doesn't flip between runs
+        if let Some((author, _)) = scores
+            .into_iter()
+            .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(a.0)))
+        {
+            suggestions.insert(n, author.to_string());
+        }
+    }
+    suggestions
+}
+
+/// Add `name` to the owner tag after `keyword`, the text the pattern matched, or add a tag:
+/// "TODO: x" -> "TODO(alice): x", "TODO(#482)" -> "TODO(alice, #482)".
+/// Returns None if the keyword is missing or already has an owner.
+pub fn assign_line(line: &str, keyword: &str, name: &str) -> Option<String> {
+    if keyword.is_empty() || owner(line, keyword).is_some() {
+        return None;
+    }
+    let idx = line.find(keyword)?;
+    let end = idx + keyword.len();
+    let rest = &line[end..];
+    match groups(rest).0 {
+        Some(tag) => {
+            // The tag is a slice of `rest`, so its offset says where it starts
+            let at = end + (tag.as_ptr() as usize - rest.as_ptr() as usize);
+            let separator = if tag.trim().is_empty() { "" } else { ", " };
+            Some(format!(
+                "{}{}{}{}",
+                &line[..at],
+                name,
+                separator,
+                &line[at..]
+            ))
+        }
+        None => Some(format!("{}({}){}", &line[..end], name, &line[end..])),
+    }
+}
+
+/// Tag TODOs with an owner: the `targets` (IDs or file:line) with `to`, or with `auto`
+/// each unowned target (every unowned TODO if there are none) with its suggested owner
+pub fn assign(
+    targets: &[String],
+    to: Option<&str>,
+    pattern: &str,
+    directory: &Path,
+    mode: WriteMode,
+    commit: bool,
+) -> Result<()> {
+    let root = repo_root(directory);
+    let matches = collect_current_matches(pattern, None, directory)?;
+
+    let mut chosen: Vec<&CurrentMatch> = Vec::new();
+    for target in targets {
+        match find_todo(&matches, target, &root) {
+            Some(m) => chosen.push(m),
+            None => anyhow::bail!(tr!(
+                "No '{}' matches '{}': give an ID as `fask triage` shows it, or file:line",
+                pattern,
+                target
+            )),
+        }
+    }
+    if targets.is_empty() {
+        chosen = matches.iter().collect();
+    }
+    chosen.retain(|m| match owner(&m.line_content, m.keyword()) {
+        Some(existing) => {
+            if !targets.is_empty() {
+                eprintln!(
+                    "{}",
+                    tr!(
+                        "{}:{}: already owned by {}, skipping",
+                        m.file,
+                        m.line_number,
+                        existing
+                    )
+                );
+            }
+            false
+        }
+        None => true,
+    });
+
+    // With the text the pattern matched on each line, where the tag goes
+    let mut by_file: BTreeMap<&str, Vec<(usize, &str)>> = BTreeMap::new();
+    for m in &chosen {
+        by_file
+            .entry(&m.file)
+            .or_default()
+            .push((m.line_number, m.keyword()));
+    }
+
+    let mut assigned = 0;
+    let mut changed_files = Vec::new();
+    for (file, targets) in &by_file {
+        let line_numbers: Vec<usize> = targets.iter().map(|&(n, _)| n).collect();
+        let names: HashMap<usize, String> = match to {
+            Some(name) => line_numbers
+                .iter()
+                .map(|&n| (n, name.to_string()))
+                .collect(),
+            None => suggest_owners(file, &line_numbers),
+        };
+
+        let content =
+            std::fs::read_to_string(file).with_context(|| tr!("Failed to read file: {}", file))?;
+        // Keep the original line endings by splitting inclusively
+        let original: Vec<String> = content.split_inclusive('\n').map(String::from).collect();
+        let mut lines = original.clone();
+        let mut changed = false;
+        for &(n, keyword) in targets {
+            let Some(name) = names.get(&n) else {
+                eprintln!("{}", tr!("{}:{}: no one to suggest, skipping", file, n));
+                continue;
+            };
+            let Some(new_line) = lines
+                .get(n - 1)
+                .and_then(|line| assign_line(line, keyword, name))
+            else {
+                eprintln!(
+                    "{}",
+                    tr!(
+                        "{}:{}: no '{}' to tag on this line, skipping",
+                        file,
+                        n,
+                        keyword
+                    )
+                );
+                continue;
+            };
+            if !mode.shows_diff() {
+                println!(
+                    "{}:{}: {}",
+                    style::paint(Element::Path, file),
+                    style::paint(Element::LineNumber, n),
+                    new_line.trim()
+                );
+            }
+            lines[n - 1] = new_line;
+            changed = true;
+            assigned += 1;
+        }
+
+        if !changed {
+            continue;
+        }
+        if mode.shows_diff() {
+            let path = diff::repo_path(Path::new(file), &root);
+            print!("{}", diff::unified(&path, &original, &lines));
+        }
+        if mode.writes() {
+            std::fs::write(file, lines.concat())
+                .with_context(|| tr!("Failed to write file: {}", file))?;
+        }
+        changed_files.push(file.to_string());
+    }
+
+    if mode.writes() {
+        println!(
+            "{}",
+            tr!(
+                "\nAssigned {} '{}'(s) in {} file(s).",
+                assigned,
+                pattern,
+                changed_files.len()
+            )
+        );
+    } else {
+        // On stderr, so the diff on stdout can go straight to `git apply`
+        eprintln!(
+            "{}",
+            tr!(
+                "\nWould assign {} '{}'(s) in {} file(s).",
+                assigned,
+                pattern,
+                changed_files.len()
+            )
+        );
+    }
+
+    if commit && !changed_files.is_empty() {
+        let status = Command::new("git")
+            .arg("commit")
+            .arg("-m")
+            .arg(format!("Assign owners to {}s", pattern))
+            .arg("--")
+            .args(&changed_files)
+            .status()
+            .with_context(|| tr!("Failed to execute git commit"))?;
+        if !status.success() {
+            anyhow::bail!(tr!("git commit failed"));
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,235 @@
+use anyhow::{Context, Result};
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::process::Command;
+
+use crate::i18n::tr;
+use crate::pr::git;
+use crate::style::{self, Element};
+use crate::unicode;
+
+/// A line containing the pattern in a file at some commit
+pub struct TreeMatch {
+    /// Path relative to the repository root
+    pub file: String,
+    pub line_number: usize,
+    pub column: usize,
+    pub line: String,
+}
+
+/// Full hash of the commit a ref points at
+pub fn resolve_commit(reference: &str, directory: &Path) -> Result<String> {
+    git(
+        &[
+            "rev-parse",
+            "--verify",
+            &format!("{}^{{commit}}", reference),
+        ],
+        directory,
+    )
+    .with_context(|| tr!("Unknown revision '{}'", reference))
+}
+
+/// Local and remote-tracking branches whose short names match the glob, e.g. "release/*" or
+/// "origin/release/*"
+pub fn branches(glob: &str, directory: &Path) -> Result<Vec<String>> {
+    let list = git(
+        &[
+            "for-each-ref",
+            "--format=%(refname:short)",
+            &format!("refs/heads/{}", glob),
+            &format!("refs/remotes/{}", glob),
+        ],
+        directory,
+    )?;
+    let branches: Vec<String> = list
+        .lines()
+        .filter(|name| !name.is_empty() && !name.ends_with("/HEAD"))
+        .map(str::to_string)
+        .collect();
+    if branches.is_empty() {
+        anyhow::bail!(tr!("No branches match '{}'", glob));
+    }
+    Ok(branches)
+}
+
+/// Lines containing the pattern in the tree of `commit`, read straight from the object
+/// database by `git grep`, or in the tracked files of the working tree without a commit.
+/// Only files under `directory` and matching `pathspecs` count.
+pub fn grep_tree(
+    commit: Option<&str>,
+    pattern: &str,
+    pathspecs: &[String],
+    directory: &Path,
+) -> Result<Vec<TreeMatch>> {
+    // Pathspecs are relative to `directory`, but without an including one git would
+    // search the whole tree
+    let scope = (!pathspecs.iter().any(|p| !p.starts_with(":(glob,exclude)"))).then_some(".");
+
+    // Literal like the history searches; -I skips binary blobs
+    let output = Command::new("git")
+        .args(["grep", "-I", "-F", "-n", "--column", "-z", "--full-name"])
+        .args(
+            unicode::spellings(pattern)
+                .into_iter()
+                .flat_map(|v| ["-e".to_string(), v]),
+        )
+        .args(commit)
+        .arg("--")
+        .args(pathspecs)
+        .args(scope)
+        .current_dir(directory)
+        .output()
+        .with_context(|| tr!("Failed to execute git grep"))?;
+    // Exit code 1 just means nothing matched
+    if !output.status.success() && output.status.code() != Some(1) {
+        anyhow::bail!(tr!(
+            "git grep failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    // "<commit>:<file>\0<line>\0<column>\0<text>" per match
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let prefix = commit.map(|c| format!("{}:", c)).unwrap_or_default();
+    Ok(stdout
+        .lines()
+        .filter_map(|record| {
+            let mut fields = record.splitn(4, '\0');
+            let file = fields.next()?;
+            Some(TreeMatch {
+                file: file.strip_prefix(&prefix).unwrap_or(file).to_string(),
+                line_number: fields.next()?.parse().ok()?,
+                column: fields.next()?.parse().ok()?,
+                line: fields.next()?.to_string(),
+            })
+        })
+        .collect())
+}
+
+/// Search the tree of `reference` for lines containing the pattern, without checking it
+/// out. Only files under `directory` and matching `pathspecs` are searched.
+pub fn search_at(
+    reference: &str,
+    pattern: &str,
+    pathspecs: &[String],
+    directory: &Path,
+) -> Result<()> {
+    let commit = resolve_commit(reference, directory)?;
+
+    println!(
+        "{}",
+        tr!(
+            "Searching for '{}' at {} ({})...\n",
+            pattern,
+            reference,
+            &commit[..8.min(commit.len())]
+        )
+    );
+
+    let matches = grep_tree(Some(&commit), pattern, pathspecs, directory)?;
+    tracing::debug!(matches = matches.len(), commit, "searched tree");
+
+    if matches.is_empty() {
+        println!("{}", tr!("No '{}' found at {}.", pattern, reference));
+        return Ok(());
+    }
+
+    for m in &matches {
+        println!(
+            "{}:{}:{}: {}",
+            style::paint(Element::Path, &m.file),
+            style::paint(Element::LineNumber, m.line_number),
+            m.column,
+            m.line.trim()
+        );
+    }
+
+    let files: BTreeSet<&str> = matches.iter().map(|m| m.file.as_str()).collect();
+    println!(
+        "{}",
+        tr!(
+            "\nFound {} '{}'(s) in {} file(s) at {}.",
+            matches.len(),
+            pattern,
+            files.len(),
+            reference
+        )
+    );
+    Ok(())
+}
+
+/// Search the tip of every branch matching the glob, printing the matches with their branch
+/// and then a count per branch, to compare maintenance lines
+pub fn search_branches(
+    glob: &str,
+    pattern: &str,
+    pathspecs: &[String],
+    directory: &Path,
+) -> Result<()> {
+    let branches = branches(glob, directory)?;
+    println!(
+        "{}",
+        tr!(
+            "Searching for '{}' on {} branch(es) matching {}...\n",
+            pattern,
+            branches.len(),
+            glob
+        )
+    );
+
+    let mut counts = Vec::new();
+    for branch in &branches {
+        let commit = resolve_commit(branch, directory)?;
+        let matches = grep_tree(Some(&commit), pattern, pathspecs, directory)?;
+        tracing::debug!(matches = matches.len(), branch, "searched branch");
+        for m in &matches {
+            println!(
+                "{}  {}:{}:{}: {}",
+                style::paint(Element::Label, branch),
+                style::paint(Element::Path, &m.file),
+                style::paint(Element::LineNumber, m.line_number),
+                m.column,
+                m.line.trim()
+            );
+        }
+        let files: BTreeSet<&str> = matches.iter().map(|m| m.file.as_str()).collect();
+        counts.push((branch, matches.len(), files.len()));
+    }
+
+    let (branch_label, todos_label, files_label) = (tr!("BRANCH"), tr!("TODOS"), tr!("FILES"));
+    let width = counts
+        .iter()
+        .map(|(branch, _, _)| branch.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max(branch_label.chars().count());
+    // The counts fit in six columns; a translated header may not
+    let count_width = [&todos_label, &files_label]
+        .iter()
+        .map(|l| l.chars().count())
+        .fold(6, usize::max);
+    println!(
+        "\n{}",
+        style::paint(
+            Element::Heading,
+            format!(
+                "{:<width$}  {:>cw$}  {:>cw$}",
+                branch_label,
+                todos_label,
+                files_label,
+                cw = count_width
+            )
+        )
+    );
+    for (branch, todos, files) in counts {
+        println!(
+            "{:<width$}  {:>cw$}  {:>cw$}",
+            branch,
+            todos,
+            files,
+            cw = count_width
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,175 @@
+use anyhow::{Context, Result};
+use base64::Engine;
+use clap::Args;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::Path;
+
+use crate::file_issues::{IssueTracker, NewIssue};
+use crate::i18n::tr;
+use crate::pr::git;
+
+/// Where and how `--backend azure-devops` creates work items
+#[derive(Args, Clone, Serialize, Deserialize)]
+#[command(next_help_heading = "Azure DevOps")]
+pub struct AzureDevopsArgs {
+    /// Organization (default: from the `origin` remote)
+    #[arg(long)]
+    pub azure_org: Option<String>,
+
+    /// Project (default: from the `origin` remote)
+    #[arg(long)]
+    pub azure_project: Option<String>,
+
+    /// Work item type to create
+    #[arg(long, default_value = "Task")]
+    pub work_item_type: String,
+
+    /// Area path for new work items (default: the project's default area)
+    #[arg(long)]
+    pub area_path: Option<String>,
+}
+
+/// Talks to Azure Boards with a personal access token from $AZURE_DEVOPS_PAT
+pub struct AzureDevops {
+    org: String,
+    project: String,
+    work_item_type: String,
+    area_path: Option<String>,
+    auth: String,
+}
+
+#[derive(Deserialize)]
+struct WorkItem {
+    id: u64,
+}
+
+/// Percent-encode a URL path segment ("My Project" -> "My%20Project")
+fn encode_segment(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// Organization and project from an Azure Repos remote URL:
+/// https://dev.azure.com/ORG/PROJECT/_git/REPO, git@ssh.dev.azure.com:v3/ORG/PROJECT/REPO
+/// or https://ORG.visualstudio.com/PROJECT/_git/REPO
+fn parse_remote(url: &str) -> Option<(String, String)> {
+    let url = url.trim();
+    let decode = |s: &str| s.replace("%20", " ");
+
+    if let Some((_, path)) = url.split_once("ssh.dev.azure.com:v3/") {
+        let mut parts = path.split('/');
+        return Some((decode(parts.next()?), decode(parts.next()?)));
+    }
+    if let Some((_, path)) = url.split_once("dev.azure.com/") {
+        let mut parts = path.split('/');
+        return Some((decode(parts.next()?), decode(parts.next()?)));
+    }
+    let (before, path) = url.split_once(".visualstudio.com/")?;
+    let org = before.rsplit(['/', '@']).next()?;
+    Some((decode(org), decode(path.split('/').next()?)))
+}
+
+impl AzureDevops {
+    pub fn new(args: &AzureDevopsArgs, root: &Path) -> Result<AzureDevops> {
+        let token = std::env::var("AZURE_DEVOPS_PAT")
+            .or_else(|_| std::env::var("AZURE_DEVOPS_EXT_PAT"))
+            .with_context(|| tr!("Set AZURE_DEVOPS_PAT to talk to Azure DevOps"))?;
+
+        let remote = git(&["remote", "get-url", "origin"], root)
+            .ok()
+            .and_then(|url| parse_remote(&url));
+        let (org, project) = match (&args.azure_org, &args.azure_project, remote) {
+            (Some(org), Some(project), _) => (org.clone(), project.clone()),
+            (org, project, Some((remote_org, remote_project))) => (
+                org.clone().unwrap_or(remote_org),
+                project.clone().unwrap_or(remote_project),
+            ),
+            _ => anyhow::bail!(tr!(
+                "Remote 'origin' is not an Azure Repos URL. Pass --azure-org and --azure-project."
+            )),
+        };
+
+        // PATs go in the password half of basic auth with an empty user name
+        let auth = base64::engine::general_purpose::STANDARD.encode(format!(":{}", token));
+
+        Ok(AzureDevops {
+            org,
+            project,
+            work_item_type: args.work_item_type.clone(),
+            area_path: args.area_path.clone(),
+            auth,
+        })
+    }
+
+    /// Base URL of the project's APIs
+    fn project_url(&self) -> String {
+        // $AZURE_DEVOPS_URL points at an on-premises collection, e.g. https://tfs/DefaultCollection
+        let collection = std::env::var("AZURE_DEVOPS_URL")
+            .unwrap_or_else(|_| format!("https://dev.azure.com/{}", encode_segment(&self.org)));
+        format!(
+            "{}/{}",
+            collection.trim_end_matches('/'),
+            encode_segment(&self.project)
+        )
+    }
+}
+
+impl IssueTracker for AzureDevops {
+    /// Create a work item; its key is in Azure Boards' "AB#123" form
+    fn create(&self, issue: &NewIssue) -> Result<String> {
+        let mut fields = vec![
+            json!({ "op": "add", "path": "/fields/System.Title", "value": issue.title }),
+            json!({ "op": "add", "path": "/fields/System.Description", "value": issue.description.replace('\n', "<br>") }),
+        ];
+        if let Some(area) = &self.area_path {
+            fields.push(json!({ "op": "add", "path": "/fields/System.AreaPath", "value": area }));
+        }
+
+        let url = format!(
+            "{}/_apis/wit/workitems/${}?api-version=7.1",
+            self.project_url(),
+            encode_segment(&self.work_item_type)
+        );
+        let item: WorkItem = ureq::post(&url)
+            .set("Authorization", &format!("Basic {}", self.auth))
+            .set("Content-Type", "application/json-patch+json")
+            .set("User-Agent", "fask")
+            .send_string(&serde_json::to_string(&fields)?)
+            .with_context(|| tr!("Failed to create work item"))?
+            .into_json()
+            .with_context(|| tr!("Failed to parse work item"))?;
+
+        Ok(format!("AB#{}", item.id))
+    }
+
+    fn owns(&self, key: &str) -> bool {
+        key.starts_with("AB#")
+    }
+
+    fn status(&self, key: &str) -> Result<Option<String>> {
+        let id = key.trim_start_matches("AB#");
+        let url = format!(
+            "{}/_apis/wit/workitems/{}?fields=System.State&api-version=7.1",
+            self.project_url(),
+            id
+        );
+        let item: Value = match ureq::get(&url)
+            .set("Authorization", &format!("Basic {}", self.auth))
+            .set("User-Agent", "fask")
+            .call()
+        {
+            Ok(response) => response.into_json()?,
+            Err(ureq::Error::Status(404, _)) => return Ok(None),
+            Err(e) => return Err(e).with_context(|| tr!("Failed to fetch work item")),
+        };
+        Ok(item["fields"]["System.State"].as_str().map(String::from))
+    }
+}
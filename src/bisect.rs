@@ -0,0 +1,143 @@
+use anyhow::Result;
+use chrono::NaiveDate;
+use std::path::Path;
+
+use crate::at::{grep_tree, resolve_commit, TreeMatch};
+use crate::dates;
+use crate::i18n::tr;
+use crate::pr::git;
+use crate::style::{self, Element};
+use crate::todo_id;
+
+/// What to look for in each commit's tree
+pub enum Target<'a> {
+    /// Lines containing this text
+    Text(&'a str),
+    /// The TODO with this ID (as shown by `fask triage`), among lines containing the pattern
+    Id { id: &'a str, pattern: &'a str },
+}
+
+impl Target<'_> {
+    /// Lines of the target in the tree of `commit`
+    fn find(&self, commit: &str, directory: &Path) -> Result<Vec<TreeMatch>> {
+        Ok(match self {
+            Target::Text(text) => grep_tree(Some(commit), text, &[], directory)?,
+            Target::Id { id, pattern } => grep_tree(Some(commit), pattern, &[], directory)?
+                .into_iter()
+                .filter(|m| todo_id(&m.file, &m.line) == *id)
+                .collect(),
+        })
+    }
+}
+
+/// "<short hash> (<date>, <author>): <subject>" for a commit
+fn describe(commit: &str, directory: &Path) -> Result<String> {
+    let line = git(
+        &[
+            "log",
+            "-1",
+            "--date=short",
+            "--format=%h%x1f%ad%x1f%aN%x1f%s",
+            commit,
+        ],
+        directory,
+    )?;
+    let fields: Vec<&str> = line.splitn(4, '\x1f').collect();
+    let [hash, date, author, subject] = fields[..] else {
+        return Ok(commit.to_string());
+    };
+    Ok(format!(
+        "{} ({}, {}): {}",
+        style::paint(Element::Commit, hash),
+        style::paint(
+            Element::Date,
+            NaiveDate::parse_from_str(date, "%Y-%m-%d").map_or(date.to_string(), dates::shown)
+        ),
+        author,
+        subject
+    ))
+}
+
+/// Binary-search the first-parent history between `from` (default: the root commit) and
+/// `to` for the commit where the target appeared, or disappeared if it's gone at `to`.
+/// Each step reads one tree, so it takes log2 of the commits instead of every diff.
+/// A TODO that came in on a branch is found at the merge that brought it in.
+pub fn bisect(target: &Target, from: Option<&str>, to: &str, directory: &Path) -> Result<()> {
+    let to = resolve_commit(to, directory)?;
+    let from = from.map(|f| resolve_commit(f, directory)).transpose()?;
+    let range = match &from {
+        Some(from) => format!("{}..{}", from, to),
+        None => to.clone(),
+    };
+    let listed = git(
+        &["rev-list", "--first-parent", "--reverse", &range],
+        directory,
+    )?;
+    // The range leaves out `from` itself
+    let commits: Vec<&str> = from.as_deref().into_iter().chain(listed.lines()).collect();
+    let Some(&last) = commits.last() else {
+        anyhow::bail!(tr!("No commits between --from and --to"));
+    };
+
+    let mut probes = 0;
+    let mut present = |commit: &str| -> Result<bool> {
+        probes += 1;
+        let found = !target.find(commit, directory)?.is_empty();
+        tracing::debug!(commit, found, "probed");
+        Ok(found)
+    };
+
+    let at_end = present(last)?;
+    let at_start = present(commits[0])?;
+    if at_start == at_end {
+        if at_end {
+            println!(
+                "{}",
+                tr!(
+                    "Present throughout the {} commit(s) searched, since {}",
+                    commits.len(),
+                    describe(commits[0], directory)?
+                )
+            );
+        } else {
+            println!(
+                "{}",
+                tr!(
+                    "Not found at either end of the {} commit(s) searched. \
+                 If it was removed, pass --from a commit that has it.",
+                    commits.len()
+                )
+            );
+        }
+        return Ok(());
+    }
+
+    // commits[low] is on the starting side, commits[high] on the ending side
+    let (mut low, mut high) = (0, commits.len() - 1);
+    while high - low > 1 {
+        let middle = low + (high - low) / 2;
+        if present(commits[middle])? == at_end {
+            high = middle;
+        } else {
+            low = middle;
+        }
+    }
+
+    let verb = if at_end { "Introduced" } else { "Removed" };
+    println!("{} in {}", verb, describe(commits[high], directory)?);
+    // Show the lines where they are: after the change when added, before it when removed
+    let shown = if at_end { commits[high] } else { commits[low] };
+    for m in target.find(shown, directory)? {
+        println!(
+            "  {}:{}: {}",
+            style::paint(Element::Path, &m.file),
+            style::paint(Element::LineNumber, m.line_number),
+            m.line.trim()
+        );
+    }
+    println!(
+        "{}",
+        tr!("\nChecked {} of {} commit(s).", probes, commits.len())
+    );
+    Ok(())
+}
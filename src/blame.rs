@@ -0,0 +1,102 @@
+use chrono::{DateTime, Local, NaiveDate};
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Command;
+
+use crate::i18n::tr;
+
+/// Author git blame reports for lines that aren't committed yet
+pub const NOT_COMMITTED: &str = "Not Committed Yet";
+
+/// Who last changed a line, and when
+pub struct BlameInfo {
+    pub author: String,
+    pub date: NaiveDate,
+}
+
+/// Blame one line of a file. Lines that aren't committed yet are attributed to
+/// "Not Committed Yet" today; None means git couldn't blame the file at all.
+pub fn blame_line(file: &str, line_number: usize) -> Option<BlameInfo> {
+    blame_lines(file, &[line_number]).remove(&line_number)
+}
+
+/// Blame several lines of a file with a single git call, keyed by line number.
+/// Empty when git couldn't blame the file.
+pub fn blame_lines(file: &str, line_numbers: &[usize]) -> HashMap<usize, BlameInfo> {
+    let mut blamed = HashMap::new();
+    let path = Path::new(file);
+    let Some(name) = path.file_name() else {
+        return blamed;
+    };
+    if line_numbers.is_empty() {
+        return blamed;
+    }
+    let dir = path.parent().filter(|p| !p.as_os_str().is_empty());
+    let mut cmd = Command::new("git");
+    cmd.args(["blame", "--porcelain"]);
+    for n in line_numbers {
+        cmd.arg("-L").arg(format!("{},{}", n, n));
+    }
+    let output = match cmd
+        .arg("--")
+        .arg(name)
+        .current_dir(dir.unwrap_or(Path::new(".")))
+        .output()
+    {
+        Ok(output) if output.status.success() => output,
+        _ => return blamed,
+    };
+
+    // Each line is "<sha> <orig> <final> [<n>]", then "key value" headers the first time
+    // the commit shows up, then the line itself after a tab
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut commits: HashMap<&str, (Option<&str>, Option<i64>)> = HashMap::new();
+    let mut current: Option<(&str, usize)> = None;
+    for line in stdout.lines() {
+        if line.starts_with('\t') {
+            let Some((sha, final_line)) = current.take() else {
+                continue;
+            };
+            let Some(&(Some(author), Some(time))) = commits.get(sha) else {
+                continue;
+            };
+            let Some(date) = DateTime::from_timestamp(time, 0) else {
+                continue;
+            };
+            blamed.insert(
+                final_line,
+                BlameInfo {
+                    author: author.to_string(),
+                    date: date.with_timezone(&Local).date_naive(),
+                },
+            );
+        } else if let Some((sha, _)) = current {
+            let entry = commits.entry(sha).or_default();
+            if let Some(name) = line.strip_prefix("author ") {
+                entry.0 = Some(name);
+            } else if let Some(seconds) = line.strip_prefix("author-time ") {
+                entry.1 = seconds.parse().ok();
+            }
+        } else {
+            let mut fields = line.split(' ');
+            let sha = fields.next().unwrap_or_default();
+            let final_line = fields.nth(1).and_then(|n| n.parse().ok());
+            current = final_line.map(|n| (sha, n));
+        }
+    }
+    blamed
+}
+
+/// How long ago a date was, roughly: "today", "3 days ago", "5 months ago", ...
+pub fn ago(date: NaiveDate, today: NaiveDate) -> String {
+    let days = (today - date).num_days();
+    // Weeks start at 2, months at 60 / 30 and years at 730 / 365, so only a day is ever one
+    match days {
+        ..=0 => tr!("today"),
+        1 => tr!("1 day ago"),
+        2..=13 => tr!("{} days ago", days),
+        14..=59 => tr!("{} weeks ago", days / 7),
+        60..=729 => tr!("{} months ago", days / 30),
+        _ => tr!("{} years ago", days / 365),
+    }
+}
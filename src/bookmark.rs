@@ -0,0 +1,103 @@
+use anyhow::Result;
+use chrono::Local;
+use std::path::Path;
+
+use crate::i18n::tr;
+use crate::state::{Bookmark, State};
+use crate::style::{self, Element};
+use crate::{collect_current_matches, find_todo};
+
+/// Pin TODOs (by ID or file:line) to work on soon, or unpin them with `remove`
+pub fn bookmark(targets: &[String], remove: bool, pattern: &str, directory: &Path) -> Result<()> {
+    let mut state = State::load(directory)?;
+    let matches = collect_current_matches(pattern, None, directory)?;
+
+    for target in targets {
+        // A bookmark can be removed by ID even once its TODO is gone
+        if remove && state.bookmarks.remove(target).is_some() {
+            println!("{}", tr!("Removed bookmark {}.", target));
+            continue;
+        }
+        let Some(m) = find_todo(&matches, target, &state.root) else {
+            anyhow::bail!(tr!(
+                "No '{}' matches '{}': give an ID as `fask triage` shows it, or file:line",
+                pattern,
+                target
+            ));
+        };
+        let id = m.id(&state.root);
+        if remove {
+            if state.bookmarks.remove(&id).is_none() {
+                anyhow::bail!(tr!(
+                    "{} ({}:{}) is not bookmarked",
+                    id,
+                    m.file,
+                    m.line_number
+                ));
+            }
+            println!(
+                "{}",
+                tr!("Removed bookmark {} ({}:{}).", id, m.file, m.line_number)
+            );
+        } else {
+            state.bookmarks.insert(
+                id.clone(),
+                Bookmark {
+                    file: m.relative_file(&state.root),
+                    text: m.line_content.trim().to_string(),
+                    added_on: Local::now().date_naive(),
+                },
+            );
+            println!(
+                "{}",
+                tr!("Bookmarked {} ({}:{}).", id, m.file, m.line_number)
+            );
+        }
+    }
+
+    state.save()
+}
+
+/// List the bookmarked TODOs where they are now, and the ones that are gone
+pub fn bookmarks(pattern: &str, directory: &Path) -> Result<()> {
+    let state = State::load(directory)?;
+    if state.bookmarks.is_empty() {
+        println!(
+            "{}",
+            tr!("No bookmarks. Pin a TODO with `fask bookmark <id>`.")
+        );
+        return Ok(());
+    }
+
+    let matches = collect_current_matches(pattern, None, directory)?;
+    let mut gone = 0;
+    println!("{}", tr!("Bookmarks ({}):", state.bookmarks.len()));
+    for (id, bookmark) in &state.bookmarks {
+        match find_todo(&matches, id, &state.root) {
+            Some(m) => println!(
+                "  {}  {}:{}: {} {}",
+                id,
+                style::paint(Element::Path, &m.file),
+                style::paint(Element::LineNumber, m.line_number),
+                m.line_content.trim(),
+                style::paint(Element::Context, tr!("(since {})", bookmark.added_on))
+            ),
+            None => {
+                gone += 1;
+                println!(
+                    "  {}  {}",
+                    id,
+                    style::paint(
+                        Element::Context,
+                        tr!("{}: {} (no longer found)", bookmark.file, bookmark.text)
+                    )
+                );
+            }
+        }
+    }
+
+    if gone > 0 {
+        println!("{}", tr!("\n{} bookmark(s) point at TODOs that were resolved or edited (`fask state prune` drops them).", gone));
+    }
+    Ok(())
+}
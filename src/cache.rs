@@ -0,0 +1,112 @@
+use anyhow::Result;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::state::repo_key;
+
+/// A line added by a commit, as stored in the cache
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CachedLine {
+    pub file: String,
+    pub content: String,
+}
+
+/// The parsed diff of one commit: its date and the added lines containing the pattern
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CachedCommit {
+    pub date: NaiveDate,
+    pub lines: Vec<CachedLine>,
+}
+
+/// Bumped whenever diffs are parsed differently, so entries parsed the old way are left
+/// behind instead of served
+const FORMAT_VERSION: u32 = 3;
+
+/// Parsed diffs keyed by commit hash, for one pattern and set of pathspecs.
+/// Commits never change, so entries stay valid for good.
+///
+/// Stored in `$XDG_CACHE_HOME/fask/repos/<name>-<hash>/added-lines-v<version>-<key>.json`.
+#[derive(Default, Serialize, Deserialize)]
+pub struct DiffCache {
+    /// What the entries were parsed for, so a file another pattern's key landed on is
+    /// ignored rather than served
+    #[serde(default)]
+    pattern: String,
+    #[serde(default)]
+    scope: String,
+
+    #[serde(default)]
+    pub commits: HashMap<String, CachedCommit>,
+
+    #[serde(skip)]
+    path: PathBuf,
+}
+
+/// Per-user cache directory, following the XDG base directory spec
+fn cache_home() -> PathBuf {
+    if let Some(dir) = std::env::var_os("XDG_CACHE_HOME").filter(|d| !d.is_empty()) {
+        return PathBuf::from(dir);
+    }
+    let home = std::env::var_os("HOME").unwrap_or_default();
+    PathBuf::from(home).join(".cache")
+}
+
+/// 64-bit FNV-1a of the pattern and scope, as they are, for the cache file's name
+fn cache_key(pattern: &str, scope: &str) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in pattern.bytes().chain([0]).chain(scope.bytes()) {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
+
+/// A repository's directory in the per-user cache, for files that are only worth keeping
+/// on this machine
+pub fn repo_cache_dir(root: &Path) -> PathBuf {
+    cache_home().join("fask").join("repos").join(repo_key(root))
+}
+
+impl DiffCache {
+    /// Load the cache for the repository root, pattern and pathspecs (which are relative to
+    /// `directory`). A missing or unreadable cache is an empty one; it only ever saves work.
+    pub fn load(root: &Path, directory: &Path, pattern: &str, pathspecs: &[String]) -> DiffCache {
+        let scope = if pathspecs.is_empty() {
+            String::new()
+        } else {
+            let directory = directory
+                .canonicalize()
+                .unwrap_or_else(|_| directory.to_path_buf());
+            format!("{}\0{}", directory.display(), pathspecs.join("\0"))
+        };
+        let key = cache_key(pattern, &scope);
+        let path =
+            repo_cache_dir(root).join(format!("added-lines-v{}-{}.json", FORMAT_VERSION, key));
+
+        let cache = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<DiffCache>(&content).ok())
+            .filter(|cache| cache.pattern == pattern && cache.scope == scope);
+        DiffCache {
+            pattern: pattern.to_string(),
+            scope,
+            path,
+            ..cache.unwrap_or_default()
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Write the cache back. Failing to is not worth failing the search over.
+    pub fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+}
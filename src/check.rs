@@ -0,0 +1,72 @@
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::at::{grep_tree, TreeMatch};
+use crate::i18n::tr;
+use crate::pr::git;
+use crate::style::{self, Element};
+
+/// Matches per file
+fn per_file(matches: &[TreeMatch]) -> BTreeMap<&str, usize> {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for m in matches {
+        *counts.entry(m.file.as_str()).or_default() += 1;
+    }
+    counts
+}
+
+/// Compare the number of TODOs in the working tree's tracked files with the merge base of
+/// `against` and HEAD, listing the files whose count changed. Both sides go through the same
+/// tree scanner, so only real changes show up. Returns whether the net count went up.
+pub fn check(against: &str, pattern: &str, pathspecs: &[String], directory: &Path) -> Result<bool> {
+    let base = git(&["merge-base", against, "HEAD"], directory)
+        .with_context(|| tr!("Can't find a merge base with '{}'", against))?;
+
+    let before = grep_tree(Some(&base), pattern, pathspecs, directory)?;
+    let now = grep_tree(None, pattern, pathspecs, directory)?;
+    let before_counts = per_file(&before);
+    let now_counts = per_file(&now);
+
+    let mut files: Vec<&str> = before_counts
+        .keys()
+        .chain(now_counts.keys())
+        .copied()
+        .collect();
+    files.sort();
+    files.dedup();
+    for file in files {
+        let old = before_counts.get(file).copied().unwrap_or(0);
+        let new = now_counts.get(file).copied().unwrap_or(0);
+        if old == new {
+            continue;
+        }
+        let (color, sign) = if new > old {
+            (Element::Error, '+')
+        } else {
+            (Element::Added, '-')
+        };
+        println!(
+            "{}  {} ({} -> {})",
+            style::paint(color, format!("{}{}", sign, old.abs_diff(new))),
+            style::paint(Element::Path, file),
+            old,
+            new
+        );
+    }
+
+    let net = now.len() as i64 - before.len() as i64;
+    println!(
+        "{}",
+        tr!(
+            "\n{} '{}'(s) at {} ({}), {} now: net {}.",
+            before.len(),
+            pattern,
+            against,
+            &base[..8.min(base.len())],
+            now.len(),
+            format!("{:+}", net)
+        )
+    );
+    Ok(net > 0)
+}
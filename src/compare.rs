@@ -0,0 +1,145 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::i18n::tr;
+use crate::style::{self, Element};
+use crate::todo_id;
+
+/// One TODO from `--format json` output; other fields are ignored
+#[derive(Deserialize)]
+struct Record {
+    file: String,
+    line_number: usize,
+    line_content: String,
+}
+
+impl Record {
+    fn location(&self) -> String {
+        format!(
+            "{}:{}",
+            style::paint(Element::Path, &self.file),
+            style::paint(Element::LineNumber, self.line_number)
+        )
+    }
+}
+
+/// Read a `current`/`since --format json` report: one object per line, or a JSON array
+fn read_report(path: &Path) -> Result<Vec<Record>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| tr!("Failed to read report: {}", path.display()))?;
+    if content.trim_start().starts_with('[') {
+        return serde_json::from_str(&content)
+            .with_context(|| tr!("Failed to parse report: {}", path.display()));
+    }
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(idx, line)| {
+            serde_json::from_str(line)
+                .with_context(|| tr!("Failed to parse {}:{}", path.display(), idx + 1))
+        })
+        .collect()
+}
+
+/// Report TODOs that are new in `new`, resolved since `old`, or moved (same text, other
+/// place). Returns whether there are new ones.
+pub fn compare(old: &Path, new: &Path) -> Result<bool> {
+    let old = read_report(old)?;
+    let new = read_report(new)?;
+
+    // The same TODO in both keeps its ID (file and text) even when its line shifts
+    let mut unmatched_old: BTreeMap<String, Vec<&Record>> = BTreeMap::new();
+    for r in &old {
+        unmatched_old
+            .entry(todo_id(&r.file, &r.line_content))
+            .or_default()
+            .push(r);
+    }
+    let mut moved: Vec<(&Record, &Record)> = Vec::new();
+    let mut added: Vec<&Record> = Vec::new();
+    for r in &new {
+        let id = todo_id(&r.file, &r.line_content);
+        match unmatched_old.get_mut(&id).filter(|rs| !rs.is_empty()) {
+            Some(rs) => {
+                let before = rs.remove(0);
+                if before.line_number != r.line_number {
+                    moved.push((before, r));
+                }
+            }
+            None => added.push(r),
+        }
+    }
+    let mut resolved: Vec<&Record> = unmatched_old.into_values().flatten().collect();
+
+    // A TODO that went to another file has a new ID; pair it up by its text
+    added.retain(|r| {
+        let text = r.line_content.trim();
+        match resolved.iter().position(|o| o.line_content.trim() == text) {
+            Some(i) => {
+                moved.push((resolved.remove(i), r));
+                false
+            }
+            None => true,
+        }
+    });
+
+    let by_location =
+        |a: &&Record, b: &&Record| (&a.file, a.line_number).cmp(&(&b.file, b.line_number));
+    added.sort_by(by_location);
+    resolved.sort_by(by_location);
+    moved.sort_by(|a, b| by_location(&a.1, &b.1));
+
+    if !added.is_empty() {
+        println!("{}", tr!("New ({}):", added.len()));
+        for r in &added {
+            println!(
+                "  {} {}: {}",
+                style::paint(Element::Added, "+"),
+                r.location(),
+                r.line_content.trim()
+            );
+        }
+        println!();
+    }
+    if !resolved.is_empty() {
+        println!("{}", tr!("Resolved ({}):", resolved.len()));
+        for r in &resolved {
+            println!(
+                "  {} {}: {}",
+                style::paint(Element::Removed, "-"),
+                r.location(),
+                r.line_content.trim()
+            );
+        }
+        println!();
+    }
+    if !moved.is_empty() {
+        println!("{}", tr!("Moved ({}):", moved.len()));
+        for (before, after) in &moved {
+            println!(
+                "  {} {} -> {}: {}",
+                style::paint(Element::Warning, "~"),
+                before.location(),
+                after.location(),
+                after.line_content.trim()
+            );
+        }
+        println!();
+    }
+
+    println!(
+        "{}",
+        tr!(
+            "{} new, {} resolved, {} moved ({} before, {} after).",
+            added.len(),
+            resolved.len(),
+            moved.len(),
+            old.len(),
+            new.len()
+        )
+    );
+    Ok(!added.is_empty())
+}
@@ -0,0 +1,139 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::i18n::tr;
+use crate::metadata::Filter;
+use crate::score::ScoreConfig;
+use crate::state::repo_root;
+
+/// Repository settings from `<repo>/.fask.toml`. Every section is optional.
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub lint: LintConfig,
+    pub score: ScoreConfig,
+    /// `[profiles.<name>]` sections, picked with --profile
+    pub profiles: BTreeMap<String, Profile>,
+    pub theme: ThemeConfig,
+    /// `[types]`: names for lists of globs, usable as -t values, e.g. `web = ["*.ts", "*.css"]`
+    pub types: BTreeMap<String, Vec<String>>,
+
+    /// Where the config was read from; None when there is no config file
+    #[serde(skip)]
+    pub path: Option<PathBuf>,
+}
+
+/// The `[lint]` section: team policy on top of the built-in rules
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct LintConfig {
+    /// Rules to skip, like --disable
+    pub disable: Vec<String>,
+    /// Keywords banned-keyword flags when --banned isn't given
+    pub banned: Option<Vec<String>>,
+    /// Longest allowed TODO line, in characters (too-long)
+    pub max_length: Option<usize>,
+    /// Metadata keys every TODO must carry (missing-metadata)
+    pub required_keys: Vec<String>,
+    /// Regex-based rules
+    pub rules: Vec<CustomRule>,
+}
+
+/// A `[profiles.<name>]` section: options a command gets with `--profile <name>` unless
+/// they are given on the command line. Commands without an option ignore it.
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Profile {
+    /// --pattern
+    pub pattern: Option<String>,
+    /// -t/--file-type file glob
+    pub r#type: Option<String>,
+    /// --exclude globs, added to any on the command line
+    pub exclude: Vec<String>,
+    /// --where conditions (e.g. "severity=high"), added to any on the command line
+    pub r#where: Vec<String>,
+    /// --format
+    pub format: Option<String>,
+}
+
+/// The `[theme]` section: a built-in theme to start from and colors for elements of the
+/// output (see `style::parse_color`), e.g. `path = "bright-magenta"`
+#[derive(Default, Deserialize)]
+#[serde(default)]
+pub struct ThemeConfig {
+    /// dark, light or truecolor, when --theme isn't given
+    pub name: Option<String>,
+    /// Colors by element key ("path", "line-number", ...)
+    #[serde(flatten)]
+    pub colors: BTreeMap<String, String>,
+}
+
+/// A `[[lint.rules]]` entry: the TODO line must match `require` and must not match `forbid`,
+/// and, when it has the key, its metadata must satisfy `where` (a --where condition such as
+/// "priority<3")
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustomRule {
+    pub id: String,
+    #[serde(default, with = "serde_regex")]
+    pub require: Option<Regex>,
+    #[serde(default, with = "serde_regex")]
+    pub forbid: Option<Regex>,
+    #[serde(default, deserialize_with = "deserialize_filter")]
+    pub r#where: Option<Filter>,
+    pub message: Option<String>,
+}
+
+fn deserialize_filter<'de, D: serde::Deserializer<'de>>(d: D) -> Result<Option<Filter>, D::Error> {
+    Option::<String>::deserialize(d)?
+        .map(|s| Filter::parse(&s).map_err(serde::de::Error::custom))
+        .transpose()
+}
+
+/// Deserialize regexes in place so a bad pattern fails at load time with its location
+mod serde_regex {
+    use regex::Regex;
+    use serde::{Deserialize, Deserializer};
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Option<Regex>, D::Error> {
+        Option::<String>::deserialize(d)?
+            .map(|pattern| Regex::new(&pattern).map_err(serde::de::Error::custom))
+            .transpose()
+    }
+}
+
+impl Config {
+    /// Load `.fask.toml` from the root of the repository containing `directory`,
+    /// or the defaults if there is none
+    pub fn load(directory: &Path) -> Result<Config> {
+        let path = repo_root(directory).join(".fask.toml");
+        if !path.exists() {
+            return Ok(Config::default());
+        }
+
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| tr!("Failed to read config: {}", path.display()))?;
+        let mut config: Config = toml::from_str(&content)
+            .with_context(|| tr!("Failed to parse config: {}", path.display()))?;
+        config.path = Some(path);
+        Ok(config)
+    }
+}
+
+/// The --directory of the (innermost) subcommand on the command line, or the current
+/// directory for commands without one: where its `.fask.toml` is looked up
+pub fn command_directory(matches: &clap::ArgMatches) -> PathBuf {
+    let mut matches = matches;
+    while let Some((_, subcommand)) = matches.subcommand() {
+        matches = subcommand;
+    }
+    matches
+        .try_get_one::<PathBuf>("directory")
+        .ok()
+        .flatten()
+        .cloned()
+        .unwrap_or_else(|| PathBuf::from("."))
+}
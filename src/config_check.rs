@@ -0,0 +1,539 @@
+use anyhow::{Context, Result};
+use clap::{CommandFactory, ValueEnum};
+use regex::Regex;
+use std::collections::BTreeSet;
+use std::ops::Range;
+use std::path::Path;
+use toml_edit::{ImDocument, Item, TableLike, Value};
+
+use crate::config::Config;
+use crate::i18n::tr;
+use crate::lint::{BANNED_KEYWORD, BUILTIN_RULES, MISSING_METADATA, TOO_LONG};
+use crate::metadata::Filter;
+use crate::state::repo_root;
+use crate::style::{self, Element, Theme};
+use crate::Cli;
+
+/// The keys each section of `.fask.toml` knows, mirroring the structs in config.rs
+const TOP_KEYS: &[&str] = &["lint", "score", "profiles", "theme", "types"];
+const LINT_KEYS: &[&str] = &["disable", "banned", "max-length", "required-keys", "rules"];
+const RULE_KEYS: &[&str] = &["id", "require", "forbid", "where", "message"];
+const SCORE_KEYS: &[&str] = &["age", "severity", "churn", "unowned", "churn-days"];
+const PROFILE_KEYS: &[&str] = &["pattern", "type", "exclude", "where", "format"];
+
+#[derive(PartialEq)]
+enum Level {
+    Error,
+    Warning,
+}
+
+/// Something wrong in the config, at a byte range of the file if it has one
+struct Problem {
+    level: Level,
+    span: Option<Range<usize>>,
+    message: String,
+}
+
+#[derive(Default)]
+struct Problems(Vec<Problem>);
+
+impl Problems {
+    fn error(&mut self, span: Option<Range<usize>>, message: String) {
+        self.0.push(Problem {
+            level: Level::Error,
+            span,
+            message,
+        });
+    }
+
+    fn warning(&mut self, span: Option<Range<usize>>, message: String) {
+        self.0.push(Problem {
+            level: Level::Warning,
+            span,
+            message,
+        });
+    }
+}
+
+/// Edit distance between two keys, for "did you mean"
+fn distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = (above + 1)
+                .min(row[j] + 1)
+                .min(diagonal + usize::from(ca != *cb));
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
+/// Flag the keys of `table` that `known` doesn't list, suggesting the closest known one
+fn unknown_keys(table: &dyn TableLike, known: &[&str], section: &str, problems: &mut Problems) {
+    for (name, _) in table.iter() {
+        if known.contains(&name) {
+            continue;
+        }
+        let span = table.get_key_value(name).and_then(|(key, _)| key.span());
+        let hint = known
+            .iter()
+            .filter(|k| distance(name, k) <= 2)
+            .min_by_key(|k| distance(name, k))
+            .map_or(tr!(" (known: {})", known.join(", ")), |k| {
+                tr!("; did you mean '{}'?", k)
+            });
+        problems.error(span, tr!("unknown key '{}' in {}{}", name, section, hint));
+    }
+}
+
+/// The tables of an array of tables, written either as `[[...]]` or as inline tables
+fn tables(item: &Item) -> Vec<&dyn TableLike> {
+    if let Some(array) = item.as_array_of_tables() {
+        return array.iter().map(|t| t as &dyn TableLike).collect();
+    }
+    item.as_array()
+        .map(|array| {
+            array
+                .iter()
+                .filter_map(|v| v.as_inline_table().map(|t| t as &dyn TableLike))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The strings of a string or array-of-strings value, with their spans
+fn strings(item: &Item) -> Vec<(&str, Option<Range<usize>>)> {
+    fn one(v: &Value) -> Option<(&str, Option<Range<usize>>)> {
+        v.as_str().map(|s| (s, v.span()))
+    }
+    match item.as_value() {
+        Some(Value::Array(array)) => array.iter().filter_map(one).collect(),
+        Some(value) => one(value).into_iter().collect(),
+        None => Vec::new(),
+    }
+}
+
+/// Why ripgrep would reject a glob, if it would
+fn glob_error(glob: &str) -> Option<&'static str> {
+    if glob.is_empty() {
+        return Some("empty glob");
+    }
+    for (idx, _) in glob.match_indices("**") {
+        let before = glob[..idx].chars().last();
+        let after = glob[idx + 2..].chars().next();
+        if before.is_some_and(|c| c != '/') || after.is_some_and(|c| c != '/' && c != '*') {
+            return Some("'**' must be a whole path component, as in 'src/**/*.rs'");
+        }
+    }
+    let mut chars = glob.chars();
+    let mut in_alternatives = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '[' => {
+                // A ']' right after the '[' (or the '[!') is a literal
+                let mut class_len = 0;
+                let mut closed = false;
+                for c in chars.by_ref() {
+                    if c == ']' && class_len > 0 {
+                        closed = true;
+                        break;
+                    }
+                    if !(class_len == 0 && (c == '!' || c == '^')) {
+                        class_len += 1;
+                    }
+                }
+                if !closed {
+                    return Some("unclosed character class '['");
+                }
+            }
+            '{' if in_alternatives => return Some("nested alternatives '{' aren't allowed"),
+            '{' => in_alternatives = true,
+            '}' if !in_alternatives => return Some("'}' without a '{'"),
+            '}' => in_alternatives = false,
+            _ => {}
+        }
+    }
+    in_alternatives.then_some("unclosed alternatives '{'")
+}
+
+/// Flag a known numeric setting that holds something else. The loader would too, but it
+/// stops at the first problem.
+fn numeric(item: &Item, integer: bool, key: &str, problems: &mut Problems) {
+    let Some(value) = item.as_value() else {
+        return;
+    };
+    let ok = match value {
+        Value::Integer(i) => !integer || *i.value() >= 0,
+        Value::Float(_) => !integer,
+        _ => false,
+    };
+    if !ok {
+        let expected = if integer {
+            "a whole number"
+        } else {
+            "a number"
+        };
+        problems.error(value.span(), tr!("{} must be {}", key, expected));
+    }
+}
+
+/// Every value some command's --format accepts, for checking profiles
+fn format_values() -> BTreeSet<String> {
+    Cli::command()
+        .get_subcommands()
+        .flat_map(|sub| {
+            sub.get_arguments()
+                .filter(|a| a.get_id() == "format")
+                .flat_map(|a| a.get_possible_values())
+                .map(|v| v.get_name().to_string())
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// `[theme]`: a built-in theme name and colors for known elements
+fn check_theme(theme: &dyn TableLike, problems: &mut Problems) {
+    let known: Vec<&str> = std::iter::once("name")
+        .chain(style::ELEMENTS.iter().map(|(_, key)| *key))
+        .collect();
+    unknown_keys(theme, &known, "[theme]", problems);
+
+    for (key, item) in theme.iter() {
+        let value = item.as_value();
+        let span = value.and_then(|v| v.span());
+        let Some(text) = value.and_then(|v| v.as_str()) else {
+            problems.error(span, tr!("{} must be a string", key));
+            continue;
+        };
+        if key == "name" {
+            if Theme::from_str(text, true).is_err() {
+                problems.error(
+                    span,
+                    format!(
+                        "unknown theme '{}' (built in: dark, light, truecolor)",
+                        text
+                    ),
+                );
+            }
+        } else if known.contains(&key) {
+            if let Err(e) = style::parse_color(text) {
+                problems.error(span, e);
+            }
+        }
+    }
+}
+
+/// `[types]`: each name needs a list of valid globs, and a name that looks like a glob
+/// would shadow it as a -t value
+fn check_types(types: &dyn TableLike, problems: &mut Problems) {
+    for (name, item) in types.iter() {
+        let key_span = types.get_key_value(name).and_then(|(key, _)| key.span());
+        if name.contains(['*', '?', '[', '{', '/']) || name.starts_with('!') {
+            problems.warning(
+                key_span.clone(),
+                format!(
+                    "type name '{}' looks like a glob, so -t {} means the type",
+                    name, name
+                ),
+            );
+        }
+        let span = item.as_value().and_then(|v| v.span());
+        let Some(array) = item.as_array() else {
+            problems.error(span, tr!("type '{}' must be a list of globs", name));
+            continue;
+        };
+        if array.is_empty() {
+            problems.error(span, tr!("type '{}' has no globs", name));
+        }
+        let several = array.len() > 1;
+        for (glob, span) in strings(item) {
+            if let Some(why) = glob_error(glob) {
+                problems.error(span, tr!("invalid glob '{}': {}", glob, why));
+            } else if several && glob.contains(['{', '}']) {
+                // Several globs are joined into one "{a,b}", which can't nest
+                problems.error(
+                    span,
+                    format!(
+                        "glob '{}' in a type can't use {{a,b}}; list them instead",
+                        glob
+                    ),
+                );
+            }
+        }
+    }
+}
+
+fn check_lint(lint: &dyn TableLike, problems: &mut Problems) {
+    unknown_keys(lint, LINT_KEYS, "[lint]", problems);
+
+    let rules = lint.get("rules").map(tables).unwrap_or_default();
+    let mut ids: Vec<&str> = Vec::new();
+    for rule in &rules {
+        unknown_keys(*rule, RULE_KEYS, "[[lint.rules]]", problems);
+        let id = rule.get("id").and_then(|i| i.as_value());
+        let id_span = id.and_then(|i| i.span());
+        match id.and_then(|i| i.as_str()) {
+            Some(id) if BUILTIN_RULES.contains(&id) => problems.error(
+                id_span.clone(),
+                tr!("rule id '{}' is taken by a built-in rule", id),
+            ),
+            Some(id) if ids.contains(&id) => problems.error(
+                id_span.clone(),
+                tr!("rule id '{}' is used by an earlier rule", id),
+            ),
+            Some(id) => ids.push(id),
+            None => problems.error(None, tr!("a [[lint.rules]] entry has no id")),
+        }
+
+        for key in ["require", "forbid"] {
+            for (pattern, span) in rule.get(key).map(strings).unwrap_or_default() {
+                if let Err(e) = Regex::new(pattern) {
+                    // The regex crate draws its own pointer above the reason
+                    let e = e.to_string();
+                    let reason = e.lines().last().unwrap_or_default();
+                    let reason = reason.strip_prefix("error: ").unwrap_or(reason);
+                    problems.error(span, tr!("invalid regex in {}: {}", key, reason));
+                }
+            }
+        }
+        for (filter, span) in rule.get("where").map(strings).unwrap_or_default() {
+            if let Err(e) = Filter::parse(filter) {
+                problems.error(span, tr!("invalid where condition: {}", e));
+            }
+        }
+        if !["require", "forbid", "where"]
+            .iter()
+            .any(|key| rule.contains_key(key))
+        {
+            problems.warning(
+                id_span,
+                tr!("rule has no require, forbid or where, so it never flags anything"),
+            );
+        }
+    }
+
+    if let Some(max_length) = lint.get("max-length") {
+        numeric(max_length, true, "max-length", problems);
+    }
+
+    let disabled = lint.get("disable").map(strings).unwrap_or_default();
+    for (id, span) in &disabled {
+        if !BUILTIN_RULES.contains(id) && !ids.contains(id) {
+            problems.error(
+                span.clone(),
+                format!(
+                    "unknown lint rule '{}' (known: {})",
+                    id,
+                    BUILTIN_RULES
+                        .iter()
+                        .chain(&ids)
+                        .copied()
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            );
+        }
+    }
+    // Settings for a rule that's switched off
+    for (key, rule) in [
+        ("max-length", TOO_LONG),
+        ("required-keys", MISSING_METADATA),
+        ("banned", BANNED_KEYWORD),
+    ] {
+        if lint.contains_key(key) && disabled.iter().any(|(id, _)| *id == rule) {
+            let span = lint.get_key_value(key).and_then(|(k, _)| k.span());
+            problems.warning(
+                span,
+                tr!("{} has no effect while {} is disabled", key, rule),
+            );
+        }
+    }
+}
+
+fn check_profile(
+    name: &str,
+    profile: &dyn TableLike,
+    formats: &BTreeSet<String>,
+    problems: &mut Problems,
+) {
+    unknown_keys(
+        profile,
+        PROFILE_KEYS,
+        &format!("[profiles.{}]", name),
+        problems,
+    );
+
+    let types = profile.get("type").map(strings).unwrap_or_default();
+    let excludes = profile.get("exclude").map(strings).unwrap_or_default();
+    for (glob, span) in types.iter().chain(&excludes) {
+        if let Some(why) = glob_error(glob) {
+            problems.error(span.clone(), tr!("invalid glob '{}': {}", glob, why));
+        }
+    }
+    for (glob, span) in &excludes {
+        if types.iter().any(|(t, _)| t == glob) {
+            problems.warning(
+                span.clone(),
+                tr!("exclude '{}' drops every file type '{}' picks", glob, glob),
+            );
+        }
+    }
+    for (filter, span) in profile.get("where").map(strings).unwrap_or_default() {
+        if let Err(e) = Filter::parse(filter) {
+            problems.error(span, tr!("invalid where condition: {}", e));
+        }
+    }
+    for (format, span) in profile.get("format").map(strings).unwrap_or_default() {
+        if !formats.contains(format) {
+            problems.error(
+                span,
+                format!(
+                    "no command has --format {} (known: {})",
+                    format,
+                    formats.iter().cloned().collect::<Vec<_>>().join(", ")
+                ),
+            );
+        }
+    }
+}
+
+/// 1-based line and column of a byte offset
+fn line_column(raw: &str, offset: usize) -> (usize, usize) {
+    let before = &raw[..offset.min(raw.len())];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    (
+        before.matches('\n').count() + 1,
+        before[line_start..].chars().count() + 1,
+    )
+}
+
+/// Print a problem as "path:line:column: level: message" with the line and a pointer under it
+fn report(path: &str, raw: &str, problem: &Problem) {
+    let (label, color) = match problem.level {
+        Level::Error => ("error", Element::Error),
+        Level::Warning => ("warning", Element::Warning),
+    };
+    let Some(span) = &problem.span else {
+        println!(
+            "{}: {}: {}",
+            style::paint(Element::Path, path),
+            style::paint(color, label),
+            problem.message
+        );
+        return;
+    };
+    let (line, column) = line_column(raw, span.start);
+    println!(
+        "{}:{}:{}: {}: {}",
+        style::paint(Element::Path, path),
+        style::paint(Element::LineNumber, line),
+        column,
+        style::paint(color, label),
+        problem.message
+    );
+    let text = raw.lines().nth(line - 1).unwrap_or("");
+    // Tabs stay tabs so the pointer lines up under them
+    let indent: String = text
+        .chars()
+        .take(column - 1)
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect();
+    let width = raw[span.start..span.end.min(raw.len())]
+        .lines()
+        .next()
+        .map_or(1, |s| s.chars().count().max(1));
+    println!("    {}", text);
+    println!("    {}{}", indent, style::paint(color, "^".repeat(width)));
+}
+
+/// Validate `.fask.toml`: syntax, unknown keys, globs, regexes, where conditions and
+/// options that cancel each other out. Returns whether there were errors.
+pub fn check(directory: &Path) -> Result<bool> {
+    let path = repo_root(directory).join(".fask.toml");
+    if !path.exists() {
+        println!(
+            "{}",
+            tr!("No config at {}; the defaults apply.", path.display())
+        );
+        return Ok(false);
+    }
+    let raw = std::fs::read_to_string(&path)
+        .with_context(|| tr!("Failed to read config: {}", path.display()))?;
+    let shown = path.display().to_string();
+
+    let mut problems = Problems::default();
+    match ImDocument::parse(raw.as_str()) {
+        Err(e) => problems.error(
+            e.span(),
+            e.message().trim().lines().collect::<Vec<_>>().join(": "),
+        ),
+        Ok(document) => {
+            let root = document.as_table();
+            unknown_keys(root, TOP_KEYS, "the top level", &mut problems);
+            if let Some(lint) = root.get("lint").and_then(Item::as_table_like) {
+                check_lint(lint, &mut problems);
+            }
+            if let Some(score) = root.get("score").and_then(Item::as_table_like) {
+                unknown_keys(score, SCORE_KEYS, "[score]", &mut problems);
+                for (key, value) in score.iter() {
+                    let integer = key == "churn-days";
+                    numeric(value, integer, key, &mut problems);
+                }
+            }
+            if let Some(theme) = root.get("theme").and_then(Item::as_table_like) {
+                check_theme(theme, &mut problems);
+            }
+            if let Some(types) = root.get("types").and_then(Item::as_table_like) {
+                check_types(types, &mut problems);
+            }
+            if let Some(profiles) = root.get("profiles").and_then(Item::as_table_like) {
+                let formats = format_values();
+                for (name, profile) in profiles.iter() {
+                    if let Some(profile) = profile.as_table_like() {
+                        check_profile(name, profile, &formats, &mut problems);
+                    }
+                }
+            }
+
+            // Wrong types and anything else the loader trips over that isn't reported yet
+            if let Err(e) = toml::from_str::<Config>(&raw) {
+                let reported = e.span().is_some_and(|span| {
+                    problems
+                        .0
+                        .iter()
+                        .any(|p| p.span.as_ref().is_some_and(|s| s.start == span.start))
+                });
+                if !reported {
+                    problems.error(e.span(), e.message().to_string());
+                }
+            }
+        }
+    }
+
+    problems
+        .0
+        .sort_by_key(|p| p.span.as_ref().map_or(0, |s| s.start));
+    for problem in &problems.0 {
+        report(&shown, &raw, problem);
+    }
+    let errors = problems
+        .0
+        .iter()
+        .filter(|p| p.level == Level::Error)
+        .count();
+    let warnings = problems.0.len() - errors;
+    if problems.0.is_empty() {
+        println!("{}", tr!("{} is valid.", shown));
+    } else {
+        eprintln!("{}", tr!("\n{} error(s), {} warning(s).", errors, warnings));
+    }
+    Ok(errors > 0)
+}
@@ -0,0 +1,400 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use notify::{EventKind, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use crate::i18n::tr;
+use crate::search::{self, Settings};
+use crate::server::{parse_listen_addr, read_request, write_response, ServeOptions};
+use crate::state::{repo_root, state_dir};
+use crate::{search_matches, CurrentMatch};
+
+/// How long the tree has to stay quiet after a change before the index is refreshed, so a
+/// checkout or a formatter run costs one search instead of hundreds
+const QUIET: Duration = Duration::from_millis(200);
+
+/// How long a command waits for a daemon to take the connection before searching itself
+const CONNECT_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// How long a command waits for a daemon's whole answer before searching itself
+const TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Where a running daemon says what it indexes, next to the state
+const ADVERT_FILE: &str = "daemon.json";
+
+/// Whether commands ask a running daemon before searching (not with --no-daemon)
+static ENABLED: AtomicBool = AtomicBool::new(true);
+
+pub fn init(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// The matches as of the last change to the working tree
+struct Index {
+    matches: Vec<CurrentMatch>,
+    /// Of the tree when `matches` were found; None outside git
+    fingerprint: Option<String>,
+    refreshed: DateTime<Local>,
+    refreshes: usize,
+}
+
+#[derive(Serialize)]
+struct Status {
+    todos: usize,
+    refreshed: String,
+    refreshes: usize,
+}
+
+/// What a daemon indexes and where to ask for it, in `daemon.json`
+#[derive(Serialize, Deserialize, PartialEq)]
+struct Advert {
+    /// Address to connect to
+    addr: String,
+    pattern: String,
+    file_type: Option<String>,
+    /// Canonical, so any way of naming the directory finds it
+    directory: PathBuf,
+    settings: Settings,
+}
+
+/// A match with its file relative to the searched directory and the spans `/api/todos`
+/// leaves out, for commands to take in place of their own search
+#[derive(Serialize, Deserialize)]
+struct Indexed {
+    #[serde(flatten)]
+    m: CurrentMatch,
+    spans: Vec<(usize, usize)>,
+}
+
+/// `/api/index`: what was searched for, and the matches
+#[derive(Serialize, Deserialize)]
+struct IndexReply {
+    pattern: String,
+    file_type: Option<String>,
+    directory: PathBuf,
+    settings: Settings,
+    matches: Vec<Indexed>,
+}
+
+/// What the working tree looks like to git: HEAD, the status and the modification time
+/// of every changed file, to tell changes to the search from changes to ignored files.
+/// None outside a git repository.
+fn fingerprint(directory: &Path) -> Option<String> {
+    let git = |args: &[&str]| {
+        Command::new("git")
+            .args(args)
+            .current_dir(directory)
+            .output()
+            .ok()
+            .filter(|o| o.status.success())
+            .map(|o| String::from_utf8_lossy(&o.stdout).into_owned())
+    };
+    let status = git(&["status", "--porcelain", "-z", "--untracked-files=all"])?;
+    let head = git(&["rev-parse", "--verify", "--quiet", "HEAD"]).unwrap_or_default();
+
+    // Status paths are relative to the top of the repository
+    let root = repo_root(directory);
+    let mut print = head;
+    for entry in status.split('\0') {
+        print.push_str(entry);
+        // "XY path", or the bare old path after a rename
+        let Some(path) = entry.get(3..).filter(|p| !p.is_empty()) else {
+            continue;
+        };
+        let modified = std::fs::metadata(root.join(path))
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok());
+        if let Some(modified) = modified {
+            print.push_str(&modified.as_nanos().to_string());
+        }
+    }
+    Some(print)
+}
+
+/// Keep the matches in memory, searching again when files under the directory change, and
+/// answer `/api/todos` like `serve` does without searching. Other fask commands with the
+/// same pattern, file type, directory and search settings take the matches from
+/// `/api/index` instead of searching themselves. Nothing is kept on disk: a started daemon
+/// searches once, and commits need no hook since they don't change the files.
+pub fn daemon(listen: &str, options: ServeOptions) -> Result<()> {
+    let addr = parse_listen_addr(listen);
+    let listener = TcpListener::bind(&addr).with_context(|| tr!("Failed to listen on {}", addr))?;
+    let directory = options
+        .directory
+        .canonicalize()
+        .with_context(|| tr!("Failed to resolve {}", options.directory.display()))?;
+    let state = state_dir(&repo_root(&directory));
+
+    // Set on every change under the directory, outside .git and fask's own files; commits
+    // don't touch the files, and checkouts, merges and stash pops come up as edits
+    let dirty = Arc::new(AtomicBool::new(false));
+    let flag = Arc::clone(&dirty);
+    let ignored = state.clone();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let Ok(event) = event else {
+            return;
+        };
+        let relevant = !matches!(event.kind, EventKind::Access(_))
+            && event.paths.iter().any(|path| {
+                !path.starts_with(&ignored) && !path.components().any(|c| c.as_os_str() == ".git")
+            });
+        if relevant {
+            flag.store(true, Ordering::Relaxed);
+        }
+    })
+    .with_context(|| tr!("Failed to start watching for changes"))?;
+    watcher
+        .watch(&directory, RecursiveMode::Recursive)
+        .with_context(|| tr!("Failed to watch {}", directory.display()))?;
+
+    let index = Arc::new(RwLock::new(Index {
+        fingerprint: fingerprint(&options.directory),
+        matches: search_matches(
+            &options.pattern,
+            options.file_type.as_deref(),
+            std::slice::from_ref(&options.directory),
+        )?,
+        refreshed: Local::now(),
+        refreshes: 1,
+    }));
+    let advert = advertise(&state, &addr, &options, &directory)?;
+    // Stopping with Ctrl-C or a kill takes the advert along, so no stale file is left in
+    // the state directory
+    let removed = advert.clone();
+    ctrlc::set_handler(move || {
+        let _ = std::fs::remove_file(&removed);
+        std::process::exit(130);
+    })
+    .with_context(|| tr!("Failed to set up the shutdown handler"))?;
+    println!(
+        "{}",
+        tr!(
+            "Watching {} and serving TODO data on http://{}/api/todos",
+            options.directory.display(),
+            addr
+        )
+    );
+
+    // Set while a refresh searches; until it is done, the matches are behind the files
+    let refreshing = Arc::new(AtomicBool::new(false));
+    let options = Arc::new(options);
+    let (watched, refresher) = (Arc::clone(&index), Arc::clone(&options));
+    let (pending, busy) = (Arc::clone(&dirty), Arc::clone(&refreshing));
+    thread::spawn(move || loop {
+        thread::sleep(QUIET);
+        // Busy before the change is taken, so there's no moment the index looks current
+        if pending.load(Ordering::SeqCst) {
+            busy.store(true, Ordering::SeqCst);
+            pending.store(false, Ordering::SeqCst);
+            refresh(&watched, &refresher);
+            busy.store(false, Ordering::SeqCst);
+        }
+    });
+
+    let directory = Arc::new(directory);
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else {
+            continue;
+        };
+        // Each request on its own thread, so a slow client holds up no other
+        let (index, options, directory) = (
+            Arc::clone(&index),
+            Arc::clone(&options),
+            Arc::clone(&directory),
+        );
+        let stale = dirty.load(Ordering::SeqCst) || refreshing.load(Ordering::SeqCst);
+        thread::spawn(move || {
+            if let Err(e) = handle_connection(stream, &index, &options, &directory, stale) {
+                eprintln!("{}", tr!("Request failed: {}", format!("{:#}", e)));
+            }
+        });
+    }
+    let _ = std::fs::remove_file(&advert);
+    Ok(())
+}
+
+/// Search again, unless git says nothing that is searched has changed. The lock is only
+/// taken to swap the matches in, so requests go on with the old ones meanwhile.
+fn refresh(index: &RwLock<Index>, options: &ServeOptions) {
+    let current = fingerprint(&options.directory);
+    if current.is_some() && current == index.read().unwrap_or_else(|e| e.into_inner()).fingerprint {
+        return;
+    }
+    match search_matches(
+        &options.pattern,
+        options.file_type.as_deref(),
+        std::slice::from_ref(&options.directory),
+    ) {
+        Ok(matches) => {
+            tracing::debug!(todos = matches.len(), "index refreshed");
+            let mut index = index.write().unwrap_or_else(|e| e.into_inner());
+            index.matches = matches;
+            index.fingerprint = current;
+            index.refreshed = Local::now();
+            index.refreshes += 1;
+        }
+        Err(e) => eprintln!("{}", tr!("Refresh failed: {}", format!("{:#}", e))),
+    }
+}
+
+/// Write `daemon.json` for other commands to find the daemon by, returning its path
+fn advertise(
+    state: &Path,
+    addr: &str,
+    options: &ServeOptions,
+    directory: &Path,
+) -> Result<PathBuf> {
+    // A daemon listening on every interface is reached on the loopback one
+    let addr = match addr.strip_prefix("0.0.0.0:") {
+        Some(port) => format!("127.0.0.1:{}", port),
+        None => addr.to_string(),
+    };
+    let advert = Advert {
+        addr,
+        pattern: options.pattern.clone(),
+        file_type: options.file_type.clone(),
+        directory: directory.to_path_buf(),
+        settings: search::settings(),
+    };
+    std::fs::create_dir_all(state).with_context(|| tr!("Failed to create {}", state.display()))?;
+    let path = state.join(ADVERT_FILE);
+    std::fs::write(&path, serde_json::to_string_pretty(&advert)?)
+        .with_context(|| tr!("Failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+/// The matches a running daemon holds for this search, with files named from `directory`
+/// the way a search would name them. None when no daemon indexes the same pattern, file
+/// type and directory with the same search settings, it is catching up with a change, or
+/// it doesn't answer in time.
+pub fn indexed(
+    pattern: &str,
+    file_type: Option<&str>,
+    directory: &Path,
+) -> Option<Vec<CurrentMatch>> {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return None;
+    }
+    let path = state_dir(&repo_root(directory)).join(ADVERT_FILE);
+    let advert: Advert = serde_json::from_str(&std::fs::read_to_string(path).ok()?).ok()?;
+    let canonical = directory.canonicalize().ok()?;
+    let settings = search::settings();
+    if advert.pattern != pattern
+        || advert.file_type.as_deref() != file_type
+        || advert.directory != canonical
+        || advert.settings != settings
+    {
+        return None;
+    }
+
+    // A daemon that has stopped leaves its file behind; the connection fails right away.
+    // One that hangs is given up on, and the command searches itself.
+    let agent = ureq::AgentBuilder::new()
+        .timeout_connect(CONNECT_TIMEOUT)
+        .timeout_read(TIMEOUT)
+        .timeout(TIMEOUT)
+        .build();
+    let reply: IndexReply = match agent
+        .get(&format!("http://{}/api/index", advert.addr))
+        .call()
+    {
+        Ok(response) => response.into_json().ok()?,
+        Err(e) => {
+            tracing::debug!(addr = advert.addr, error = %e, "no daemon");
+            return None;
+        }
+    };
+    if reply.pattern != pattern
+        || reply.file_type.as_deref() != file_type
+        || reply.directory != canonical
+        || reply.settings != settings
+    {
+        return None;
+    }
+    tracing::debug!(
+        addr = advert.addr,
+        matches = reply.matches.len(),
+        "matches from the daemon"
+    );
+    Some(
+        reply
+            .matches
+            .into_iter()
+            .map(|Indexed { m, spans }| CurrentMatch {
+                file: directory.join(&m.file).to_string_lossy().into_owned(),
+                spans,
+                ..m
+            })
+            .collect(),
+    )
+}
+
+fn handle_connection(
+    mut stream: TcpStream,
+    index: &RwLock<Index>,
+    options: &ServeOptions,
+    directory: &Path,
+    stale: bool,
+) -> Result<()> {
+    let (method, path) = read_request(&stream)?;
+
+    if method != "GET" {
+        return write_response(&mut stream, "405 Method Not Allowed", "text/plain", "");
+    }
+
+    let index = index.read().unwrap_or_else(|e| e.into_inner());
+    match path.as_str() {
+        "/api/todos" => {
+            let body = serde_json::to_string(&index.matches)?;
+            write_response(&mut stream, "200 OK", "application/json", &body)
+        }
+        // Commands would rather search than take matches from before a change
+        "/api/index" if stale => write_response(
+            &mut stream,
+            "503 Service Unavailable",
+            "text/plain",
+            "Refreshing",
+        ),
+        "/api/index" => {
+            let matches = index
+                .matches
+                .iter()
+                .map(|m| {
+                    // Relative to the directory, for the asking command to name its own way
+                    let file = Path::new(&m.file)
+                        .strip_prefix(&options.directory)
+                        .map_or_else(|_| m.file.clone(), |f| f.to_string_lossy().into_owned());
+                    Indexed {
+                        spans: m.spans.clone(),
+                        m: CurrentMatch { file, ..m.clone() },
+                    }
+                })
+                .collect();
+            let body = serde_json::to_string(&IndexReply {
+                pattern: options.pattern.clone(),
+                file_type: options.file_type.clone(),
+                directory: directory.to_path_buf(),
+                settings: search::settings(),
+                matches,
+            })?;
+            write_response(&mut stream, "200 OK", "application/json", &body)
+        }
+        "/api/status" => {
+            let body = serde_json::to_string(&Status {
+                todos: index.matches.len(),
+                refreshed: index.refreshed.to_rfc3339(),
+                refreshes: index.refreshes,
+            })?;
+            write_response(&mut stream, "200 OK", "application/json", &body)
+        }
+        _ => write_response(&mut stream, "404 Not Found", "text/plain", "Not found"),
+    }
+}
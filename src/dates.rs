@@ -0,0 +1,79 @@
+use chrono::{Duration, Local, Months, NaiveDate, Weekday};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::blame::ago;
+use crate::i18n::tr;
+
+/// Whether commit dates are printed as dates (--absolute-dates) instead of "3 months ago"
+static ABSOLUTE: AtomicBool = AtomicBool::new(false);
+
+pub fn init(absolute: bool) {
+    ABSOLUTE.store(absolute, Ordering::Relaxed);
+}
+
+pub fn absolute() -> bool {
+    ABSOLUTE.load(Ordering::Relaxed)
+}
+
+/// How long ago a commit date was, from today: "3 months ago"
+pub fn relative(date: NaiveDate) -> String {
+    ago(date, Local::now().date_naive())
+}
+
+/// A commit date the way people reason about staleness, "3 months ago", or as the date
+/// itself with --absolute-dates
+pub fn shown(date: NaiveDate) -> String {
+    if absolute() {
+        date.to_string()
+    } else {
+        relative(date)
+    }
+}
+
+/// The span a date input names: a single day, an ISO week or a month
+fn parse_period(input: &str) -> Option<(NaiveDate, NaiveDate)> {
+    let input = input.trim();
+    for format in ["%Y-%m-%d", "%Y/%m/%d"] {
+        if let Ok(day) = NaiveDate::parse_from_str(input, format) {
+            return Some((day, day));
+        }
+    }
+
+    // "2025-W14" (also "2025W14" and lowercase)
+    let upper = input.to_uppercase();
+    if let Some((year, week)) = upper.split_once('W') {
+        let year: i32 = year.trim_end_matches('-').parse().ok()?;
+        let week: u32 = week.parse().ok()?;
+        let monday = NaiveDate::from_isoywd_opt(year, week, Weekday::Mon)?;
+        return Some((monday, monday + Duration::days(6)));
+    }
+
+    // "2025-03" or "2025/03"
+    let (year, month) = input.split_once(['-', '/'])?;
+    let first = NaiveDate::from_ymd_opt(year.parse().ok()?, month.parse().ok()?, 1)?;
+    let last = first.checked_add_months(Months::new(1))? - Duration::days(1);
+    Some((first, last))
+}
+
+fn invalid(input: &str) -> String {
+    tr!(
+        "invalid date '{}'. Use YYYY-MM-DD, YYYY/MM/DD, a week (2025-W14) or a month (2025-03)",
+        input
+    )
+}
+
+/// Parse a date input as the first day it names, for "since" bounds:
+/// "2025-03" is March 1st, "2025-W14" that week's Monday
+pub fn parse_start(input: &str) -> Result<NaiveDate, String> {
+    parse_period(input)
+        .map(|(first, _)| first)
+        .ok_or_else(|| invalid(input))
+}
+
+/// Parse a date input as the last day it names, for "until" bounds:
+/// "2025-03" is March 31st, "2025-W14" that week's Sunday
+pub fn parse_end(input: &str) -> Result<NaiveDate, String> {
+    parse_period(input)
+        .map(|(_, last)| last)
+        .ok_or_else(|| invalid(input))
+}
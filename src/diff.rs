@@ -0,0 +1,95 @@
+//! Unified diffs of files rewritten line by line, for previewing write-back commands
+
+/// Unchanged lines shown around each change, as `diff -u` and git show them
+const CONTEXT: usize = 3;
+
+/// A unified diff of `path` (relative to the repository root, see `repo_path`) from `old` to
+/// `new`, its lines (each with its ending) before and after they were rewritten in place, none
+/// added or removed. Paths get the `a/` and `b/` prefixes, so the diff applies from the root
+/// with `git apply` or `patch -p1`. Empty if nothing changed.
+pub fn unified(path: &str, old: &[String], new: &[String]) -> String {
+    debug_assert_eq!(old.len(), new.len());
+    let changed: Vec<usize> = (0..old.len()).filter(|&i| old[i] != new[i]).collect();
+    if changed.is_empty() {
+        return String::new();
+    }
+
+    let mut out = format!("--- a/{}\n+++ b/{}\n", path, path);
+    let mut first = 0;
+    while first < changed.len() {
+        // Changes with no more than twice the context between them share a hunk
+        let mut last = first;
+        while last + 1 < changed.len() && changed[last + 1] - changed[last] <= 2 * CONTEXT {
+            last += 1;
+        }
+        let start = changed[first].saturating_sub(CONTEXT);
+        let end = (changed[last] + CONTEXT + 1).min(old.len());
+        // Line counts match, since lines are only replaced
+        out.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            start + 1,
+            end - start,
+            start + 1,
+            end - start
+        ));
+
+        let mut i = start;
+        while i < end {
+            if old[i] == new[i] {
+                push_line(&mut out, ' ', &old[i]);
+                i += 1;
+                continue;
+            }
+            // A run of changed lines: all the removals, then all the additions
+            let run_end = (i..end).find(|&j| old[j] == new[j]).unwrap_or(end);
+            for line in &old[i..run_end] {
+                push_line(&mut out, '-', line);
+            }
+            for line in &new[i..run_end] {
+                push_line(&mut out, '+', line);
+            }
+            i = run_end;
+        }
+        first = last + 1;
+    }
+    out
+}
+
+/// `file` relative to the repository `root`, with '/' separators, for the paths of a diff
+#[cfg(feature = "cli")]
+pub fn repo_path(file: &std::path::Path, root: &std::path::Path) -> String {
+    let canonical = file.canonicalize().unwrap_or_else(|_| file.to_path_buf());
+    let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+    let relative = canonical.strip_prefix(&root).unwrap_or(file);
+    let relative = crate::slash_path(&relative.to_string_lossy());
+    relative.strip_prefix("./").unwrap_or(&relative).to_string()
+}
+
+fn push_line(out: &mut String, prefix: char, line: &str) {
+    out.push(prefix);
+    out.push_str(line);
+    if !line.ends_with('\n') {
+        out.push_str("\n\\ No newline at end of file\n");
+    }
+}
+
+/// What a write-back command like `fask assign` does with the lines it rewrites
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WriteMode {
+    /// Write the files, listing the rewritten lines
+    Write,
+    /// Print the diff and leave the files alone
+    DryRun,
+    /// Print the diff and write the files
+    WriteWithDiff,
+}
+
+impl WriteMode {
+    pub fn writes(self) -> bool {
+        self != WriteMode::DryRun
+    }
+
+    pub fn shows_diff(self) -> bool {
+        self != WriteMode::Write
+    }
+}
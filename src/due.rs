@@ -0,0 +1,100 @@
+use anyhow::{Context, Result};
+use chrono::{Local, NaiveDate};
+use std::path::Path;
+
+use crate::annotate::deadline;
+use crate::i18n::tr;
+use crate::style::{self, Element};
+use crate::{collect_current_matches, CurrentMatch};
+
+/// How many overdue TODOs a notification lists before summarizing the rest
+const NOTIFY_LINES: usize = 5;
+
+/// Raise a desktop notification summarizing the overdue TODOs
+fn notify(overdue: &[(NaiveDate, &CurrentMatch)], pattern: &str, today: NaiveDate) -> Result<()> {
+    let mut body: Vec<String> = overdue
+        .iter()
+        .take(NOTIFY_LINES)
+        .map(|(due, m)| {
+            format!(
+                "{} ({} day(s) late): {}",
+                m.file.trim_start_matches("./"),
+                (today - *due).num_days(),
+                m.line_content.trim()
+            )
+        })
+        .collect();
+    if overdue.len() > NOTIFY_LINES {
+        body.push(tr!("and {} more", overdue.len() - NOTIFY_LINES));
+    }
+
+    notify_rust::Notification::new()
+        .summary(&tr!("{} overdue '{}'(s)", overdue.len(), pattern))
+        .body(&body.join("\n"))
+        .appname("fask")
+        .show()
+        .with_context(|| tr!("Failed to show desktop notification"))?;
+    Ok(())
+}
+
+/// List TODOs with deadlines, soonest first, and optionally notify about overdue ones
+pub fn due(
+    pattern: &str,
+    file_type: Option<&str>,
+    directory: &Path,
+    within: Option<i64>,
+    notify_overdue: bool,
+) -> Result<()> {
+    let today = Local::now().date_naive();
+    let matches = collect_current_matches(pattern, file_type, directory)?;
+
+    let mut dated: Vec<(NaiveDate, &CurrentMatch)> = matches
+        .iter()
+        .filter_map(|m| Some((deadline(&m.line_content, pattern)?, m)))
+        .filter(|(due, _)| within.is_none_or(|days| (*due - today).num_days() <= days))
+        .collect();
+    dated.sort_by(|a, b| (a.0, &a.1.file, a.1.line_number).cmp(&(b.0, &b.1.file, b.1.line_number)));
+
+    if dated.is_empty() {
+        println!("{}", tr!("No '{}'s with deadlines.", pattern));
+        return Ok(());
+    }
+
+    for (due, m) in &dated {
+        let days = (*due - today).num_days();
+        let when = match days {
+            d if d < 0 => style::paint(Element::Error, tr!("{} day(s) overdue", -d)),
+            0 => style::paint(Element::Warning, tr!("due today")),
+            d => tr!("due in {} day(s)", d),
+        };
+        println!(
+            "{} ({})  {}:{}: {}",
+            due,
+            when,
+            style::paint(Element::Path, &m.file),
+            style::paint(Element::LineNumber, m.line_number),
+            m.line_content.trim()
+        );
+    }
+
+    let overdue: Vec<(NaiveDate, &CurrentMatch)> = dated
+        .iter()
+        .filter(|(due, _)| *due < today)
+        .copied()
+        .collect();
+    println!(
+        "{}",
+        tr!(
+            "\n{} '{}'(s) with deadlines, {} overdue.",
+            dated.len(),
+            pattern,
+            overdue.len()
+        )
+    );
+
+    if notify_overdue && !overdue.is_empty() {
+        notify(&overdue, pattern, today)?;
+    }
+
+    Ok(())
+}
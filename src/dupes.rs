@@ -0,0 +1,149 @@
+use anyhow::Result;
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+
+use crate::i18n::tr;
+use crate::style::{self, Element};
+use crate::{collect_current_matches, CurrentMatch};
+
+/// The TODO's text after the keyword, lowercased, with owner tags, punctuation and
+/// repeated whitespace removed, so trivially different copies compare equal
+fn normalize(m: &CurrentMatch) -> String {
+    let after = match m.spans.first() {
+        Some(&(_, end)) => &m.line_content[end..],
+        None => m.line_content.as_str(),
+    };
+
+    // Drop a leading "(owner)" tag
+    let after = after.trim_start();
+    let after = match after
+        .strip_prefix('(')
+        .and_then(|rest| rest.split_once(')'))
+    {
+        Some((_, rest)) => rest,
+        None => after,
+    };
+
+    let cleaned: String = after
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() {
+                c.to_lowercase().next().unwrap_or(c)
+            } else {
+                ' '
+            }
+        })
+        .collect();
+    cleaned.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Jaccard similarity of the word sets of two normalized texts
+fn similarity(a: &BTreeSet<&str>, b: &BTreeSet<&str>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f64 / union as f64
+}
+
+/// Find the representative of a set in the union-find forest
+fn find(parent: &mut [usize], mut i: usize) -> usize {
+    while parent[i] != i {
+        parent[i] = parent[parent[i]];
+        i = parent[i];
+    }
+    i
+}
+
+/// Group TODOs whose normalized text is identical or at least `threshold` similar,
+/// biggest groups first. TODOs without a copy get a group of their own.
+pub fn clusters(matches: &[CurrentMatch], threshold: f64) -> Vec<Vec<&CurrentMatch>> {
+    // A bare "TODO" says nothing, so it can't be a duplicate of anything
+    let entries: Vec<(String, &CurrentMatch)> = matches
+        .iter()
+        .map(|m| (normalize(m), m))
+        .filter(|(text, _)| !text.is_empty())
+        .collect();
+
+    let words: Vec<BTreeSet<&str>> = entries
+        .iter()
+        .map(|(text, _)| text.split(' ').collect())
+        .collect();
+
+    // Cluster entries: exact matches always, near matches when above the threshold
+    let mut parent: Vec<usize> = (0..entries.len()).collect();
+    for i in 0..entries.len() {
+        for j in (i + 1)..entries.len() {
+            if entries[i].0 == entries[j].0 || similarity(&words[i], &words[j]) >= threshold {
+                let (a, b) = (find(&mut parent, i), find(&mut parent, j));
+                parent[b] = a;
+            }
+        }
+    }
+
+    let mut groups: HashMap<usize, Vec<&CurrentMatch>> = HashMap::new();
+    for (i, (_, m)) in entries.iter().enumerate() {
+        let root = find(&mut parent, i);
+        groups.entry(root).or_default().push(m);
+    }
+    let mut groups: Vec<Vec<&CurrentMatch>> = groups.into_values().collect();
+
+    // Biggest clusters first, then by first location for a stable order
+    for group in &mut groups {
+        group.sort_by(|a, b| (&a.file, a.line_number).cmp(&(&b.file, b.line_number)));
+    }
+    groups.sort_by(|a, b| {
+        b.len()
+            .cmp(&a.len())
+            .then_with(|| (&a[0].file, a[0].line_number).cmp(&(&b[0].file, b[0].line_number)))
+    });
+    groups
+}
+
+/// Report TODOs whose normalized text is identical or at least `threshold` similar
+pub fn find_dupes(
+    pattern: &str,
+    file_type: Option<&str>,
+    directory: &Path,
+    threshold: f64,
+) -> Result<()> {
+    let matches = collect_current_matches(pattern, file_type, directory)?;
+    let groups: Vec<Vec<&CurrentMatch>> = clusters(&matches, threshold)
+        .into_iter()
+        .filter(|g| g.len() > 1)
+        .collect();
+
+    if groups.is_empty() {
+        println!("{}", tr!("No duplicate '{}'s found.", pattern));
+        return Ok(());
+    }
+
+    for (idx, group) in groups.iter().enumerate() {
+        if idx > 0 {
+            println!();
+        }
+        let files: BTreeSet<&str> = group.iter().map(|m| m.file.as_str()).collect();
+        println!(
+            "{}",
+            style::paint(
+                Element::Heading,
+                tr!("{} occurrences in {} file(s)", group.len(), files.len())
+            )
+        );
+        for m in group {
+            println!(
+                "  {}:{}: {}",
+                style::paint(Element::Path, &m.file),
+                style::paint(Element::LineNumber, m.line_number),
+                m.line_content.trim()
+            );
+        }
+    }
+
+    println!(
+        "{}",
+        tr!("\n{} group(s) of duplicate '{}'s.", groups.len(), pattern)
+    );
+
+    Ok(())
+}
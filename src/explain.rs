@@ -0,0 +1,191 @@
+use anyhow::Result;
+use chrono::{Local, NaiveDate};
+use std::path::Path;
+
+use crate::annotate::owner;
+use crate::assign::suggest_owners;
+use crate::blame::{blame_line, NOT_COMMITTED};
+use crate::dates;
+use crate::i18n::tr;
+use crate::odb::ObjectReader;
+use crate::pr::git;
+use crate::state::State;
+use crate::style::{self, Element};
+use crate::{collect_current_matches, find_line_in, find_todo};
+
+/// A commit that touched the TODO's line, with the line before and after it
+struct Change {
+    hash: String,
+    date: Option<NaiveDate>,
+    author: String,
+    subject: String,
+    before: Option<String>,
+    after: Option<String>,
+}
+
+/// Commits that touched line `line` of `file` at HEAD, oldest first, from `git log -L`
+fn line_history(file: &str, line: usize, root: &Path) -> Result<Vec<Change>> {
+    let log = git(
+        &[
+            "log",
+            "-L",
+            &format!("{},{}:{}", line, line, file),
+            "--format=commit %H%x1f%ad%x1f%aN%x1f%s",
+            "--date=short",
+        ],
+        root,
+    )?;
+
+    let mut changes: Vec<Change> = Vec::new();
+    for line in log.lines() {
+        if let Some(header) = line.strip_prefix("commit ") {
+            let fields: Vec<&str> = header.splitn(4, '\x1f').collect();
+            if let [hash, date, author, subject] = fields[..] {
+                changes.push(Change {
+                    hash: hash.to_string(),
+                    date: NaiveDate::parse_from_str(date, "%Y-%m-%d").ok(),
+                    author: author.to_string(),
+                    subject: subject.to_string(),
+                    before: None,
+                    after: None,
+                });
+            }
+        } else if let Some(change) = changes.last_mut() {
+            if let Some(after) = line.strip_prefix('+').filter(|_| !line.starts_with("+++")) {
+                change.after = Some(after.trim().to_string());
+            } else if let Some(before) = line.strip_prefix('-').filter(|_| !line.starts_with("---"))
+            {
+                change.before = Some(before.trim().to_string());
+            }
+        }
+    }
+    changes.reverse();
+    Ok(changes)
+}
+
+/// Tell the story of one TODO: where it is, who owns it, how old it is, any triage
+/// decision, and every commit that touched its line since it was added
+pub fn explain(target: &str, pattern: &str, directory: &Path) -> Result<()> {
+    let state = State::load(directory)?;
+    let root = &state.root;
+    let matches = collect_current_matches(pattern, None, directory)?;
+    let Some(m) = find_todo(&matches, target, root) else {
+        anyhow::bail!(tr!(
+            "No '{}' matches '{}': give an ID as `fask triage` shows it, or file:line",
+            pattern,
+            target
+        ));
+    };
+    let id = m.id(root);
+    let file = m.relative_file(root);
+
+    println!(
+        "{}  {}:{}: {}",
+        style::paint(Element::Heading, &id),
+        style::paint(Element::Path, &m.file),
+        style::paint(Element::LineNumber, m.line_number),
+        m.line_content.trim()
+    );
+
+    let blame = blame_line(&m.file, m.line_number);
+    let tag = owner(&m.line_content, m.keyword());
+    match tag {
+        Some(tag) => println!("{}", tr!("  owner:    {} (tag)", tag)),
+        None => match suggest_owners(&m.file, &[m.line_number]).remove(&m.line_number) {
+            Some(suggested) => println!(
+                "{}",
+                tr!(
+                    "  owner:    none, suggested {} (fask assign --auto)",
+                    suggested
+                )
+            ),
+            None => println!("{}", tr!("  owner:    none")),
+        },
+    }
+    let today = Local::now().date_naive();
+    if let Some(b) = &blame {
+        if b.author == NOT_COMMITTED {
+            println!("{}", tr!("  touched:  not committed yet"));
+        } else {
+            println!(
+                "{}",
+                tr!("  touched:  {} by {}", dates::shown(b.date), b.author)
+            );
+        }
+    }
+    if !m.metadata.is_empty() {
+        let fields: Vec<String> = m
+            .metadata
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect();
+        println!("{}", tr!("  metadata: {}", fields.join(", ")));
+    }
+    if let Some(entry) = state.triage.get(&id) {
+        println!(
+            "{}",
+            tr!("  triage:   {} on {}", entry.describe(), entry.decided_on)
+        );
+    }
+
+    // git log -L follows HEAD's line numbers, which uncommitted edits may have shifted
+    let mut objects = ObjectReader::new(root)?;
+    let head_line = objects
+        .read_file("HEAD", &file)?
+        .and_then(|contents| find_line_in(&contents, &m.line_content, pattern))
+        .map(|(line, _)| line);
+    let Some(head_line) = head_line else {
+        println!("{}", tr!("\nNot committed yet, so there is no history."));
+        return Ok(());
+    };
+    let changes = line_history(&file, head_line, root)?;
+
+    if let Some(added) = changes.first() {
+        // The relative date already says how old it is
+        let age = added
+            .date
+            .filter(|_| dates::absolute())
+            .map_or(String::new(), |d| tr!("{} days, ", (today - d).num_days()));
+        println!(
+            "{}",
+            tr!(
+                "  age:      {}added {} in {}",
+                age,
+                added.date.map_or("?".to_string(), dates::shown),
+                style::paint(Element::Commit, &added.hash[..8.min(added.hash.len())])
+            )
+        );
+    }
+
+    println!(
+        "{}",
+        tr!("\nHistory ({} commit(s), oldest first):", changes.len())
+    );
+    for (idx, change) in changes.iter().enumerate() {
+        println!(
+            "  {} {} {}: {}",
+            style::paint(Element::Commit, &change.hash[..8.min(change.hash.len())]),
+            style::paint(
+                Element::Date,
+                change.date.map_or("?".to_string(), dates::shown)
+            ),
+            change.author,
+            change.subject
+        );
+        if idx > 0 {
+            if let Some(before) = &change.before {
+                println!(
+                    "      {}",
+                    style::paint(Element::Removed, format!("- {}", before))
+                );
+            }
+        }
+        if let Some(after) = &change.after {
+            println!(
+                "      {}",
+                style::paint(Element::Added, format!("+ {}", after))
+            );
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,206 @@
+use anyhow::Result;
+use chrono::{Local, NaiveDate};
+use clap::ValueEnum;
+use rayon::prelude::*;
+use regex::Regex;
+use std::path::Path;
+
+use crate::annotate::{deadline, owner};
+use crate::assign::suggest_owners;
+use crate::blame::blame_line;
+use crate::config::Config;
+use crate::graph;
+use crate::ical::{self, IcalArgs};
+use crate::metadata::{MetaValue, Metadata};
+use crate::notion::{self, NotionArgs};
+use crate::obsidian::{self, ObsidianArgs};
+use crate::score::{file_churn, ScoreInputs};
+use crate::state::repo_root;
+use crate::xlsx::{self, XlsxArgs};
+use crate::{collect_current_matches, short_hash};
+
+/// Where `export` sends the TODOs
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    /// Upsert into a Notion database through the API
+    Notion,
+    /// Markdown notes with task checkboxes and backlinks, for an Obsidian vault
+    Obsidian,
+    /// An .ics calendar of the TODOs that have deadlines
+    Ical,
+    /// Graphviz digraph of the directory hierarchy with counts, on stdout
+    Dot,
+    /// Nested JSON of counts per directory and file for d3 treemaps, on stdout
+    TreemapJson,
+    /// An Excel workbook: the matches, and a summary by directory and by owner
+    Xlsx,
+}
+
+/// What `--redact` takes out of an export; counts, ages, scores and deadlines stay
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Redact {
+    /// Email addresses, in owners and text, become stable pseudonyms
+    Emails,
+    /// The TODO text becomes just the pattern
+    Snippets,
+    /// Each directory and file name becomes a hash (extensions are kept)
+    Paths,
+}
+
+/// One TODO with the details exports show
+pub struct ExportRow {
+    pub id: String,
+    /// Path relative to the repository root
+    pub file: String,
+    pub line_number: usize,
+    pub text: String,
+    /// Owner tag, or the suggested owner from blame
+    pub owner: Option<String>,
+    pub severity: &'static str,
+    /// Days since the line was last changed
+    pub age_days: i64,
+    /// Due date written on the line, e.g. "TODO(2025-12-01)" or "due: 2025-12-01"
+    pub deadline: Option<NaiveDate>,
+    /// Priority score from 0 to 100; higher should be fixed sooner
+    pub score: f64,
+}
+
+/// Severity from a `severity=` metadata field, or roughly from the markers on the line
+pub fn severity(line: &str, metadata: &Metadata) -> &'static str {
+    if let Some(MetaValue::Text(severity)) = metadata.get("severity") {
+        match severity.as_str() {
+            "high" => return "high",
+            "medium" => return "medium",
+            "low" => return "low",
+            _ => {}
+        }
+    }
+    if ["FIXME", "BUG", "XXX"].iter().any(|k| line.contains(k)) {
+        "high"
+    } else if line.contains("HACK") {
+        "medium"
+    } else {
+        "low"
+    }
+}
+
+/// Collect the current TODOs with owner, severity, age and score, highest score first
+pub fn export_rows(
+    pattern: &str,
+    file_type: Option<&str>,
+    directory: &Path,
+) -> Result<Vec<ExportRow>> {
+    let root = repo_root(directory);
+    let today = Local::now().date_naive();
+    let weights = Config::load(directory)?.score;
+    let churn = file_churn(&root, weights.churn_days);
+    let max_churn = churn.values().copied().max().unwrap_or(0);
+    let matches = collect_current_matches(pattern, file_type, directory)?;
+
+    // One git blame per TODO, so spread them out
+    let mut rows: Vec<ExportRow> = matches
+        .par_iter()
+        .map(|m| {
+            let blame = blame_line(&m.file, m.line_number);
+            let age_days = blame.as_ref().map_or(0, |b| (today - b.date).num_days());
+            let tagged = owner(&m.line_content, m.keyword());
+            // Without an owner tag, whoever wrote most of the code around it
+            let owner = tagged
+                .map(String::from)
+                .or_else(|| suggest_owners(&m.file, &[m.line_number]).remove(&m.line_number));
+            let file = m.relative_file(&root);
+            let severity = severity(&m.line_content, &m.metadata);
+            let score = weights.score(&ScoreInputs {
+                age_days,
+                severity,
+                churn: churn.get(&file).copied().unwrap_or(0),
+                max_churn,
+                owned: tagged.is_some(),
+            });
+            ExportRow {
+                id: m.id(&root),
+                line_number: m.line_number,
+                text: m.line_content.trim().to_string(),
+                owner,
+                severity,
+                age_days,
+                deadline: deadline(&m.line_content, pattern),
+                score,
+                file,
+            }
+        })
+        .collect();
+    rows.sort_by(|a, b| b.score.total_cmp(&a.score));
+    Ok(rows)
+}
+
+/// A hash of `text`, the same for the same text, so redacted reports still group by it
+fn pseudonym(text: &str) -> String {
+    short_hash(text.bytes())
+}
+
+/// Anonymize the rows as `redact` asks
+fn redact_rows(rows: &mut [ExportRow], redact: &[Redact], pattern: &str) {
+    let emails = Regex::new(r"[\w.+-]+@[\w-]+(\.[\w-]+)+").expect("valid regex");
+    let hide_emails = |text: &str| -> String {
+        emails
+            .replace_all(text, |c: &regex::Captures| {
+                format!("{}@redacted", pseudonym(&c[0]))
+            })
+            .into_owned()
+    };
+    for row in rows {
+        if redact.contains(&Redact::Emails) {
+            row.text = hide_emails(&row.text);
+            row.owner = row.owner.as_deref().map(hide_emails);
+        }
+        if redact.contains(&Redact::Snippets) {
+            row.text = pattern.to_string();
+        }
+        if redact.contains(&Redact::Paths) {
+            let parts: Vec<String> = row
+                .file
+                .split('/')
+                .map(|part| match part.rsplit_once('.') {
+                    Some((name, extension)) if !name.is_empty() => {
+                        format!("{}.{}", pseudonym(name), extension)
+                    }
+                    _ => pseudonym(part),
+                })
+                .collect();
+            row.file = parts.join("/");
+        }
+    }
+}
+
+/// Export settings from the command line, for whichever format is picked
+pub struct ExportArgs<'a> {
+    pub format: ExportFormat,
+    pub notion: &'a NotionArgs,
+    pub obsidian: &'a ObsidianArgs,
+    pub ical: &'a IcalArgs,
+    pub xlsx: &'a XlsxArgs,
+}
+
+/// Export the current TODOs in the given format
+pub fn export(
+    pattern: &str,
+    file_type: Option<&str>,
+    directory: &Path,
+    redact: &[Redact],
+    args: &ExportArgs,
+) -> Result<()> {
+    let mut rows = export_rows(pattern, file_type, directory)?;
+    redact_rows(&mut rows, redact, pattern);
+    match args.format {
+        ExportFormat::Notion => notion::upsert(&rows, args.notion),
+        ExportFormat::Obsidian => obsidian::write_vault(&rows, args.obsidian),
+        ExportFormat::Ical => ical::write_calendar(&rows, args.ical),
+        ExportFormat::Xlsx => xlsx::write_workbook(&rows, args.xlsx),
+        ExportFormat::Dot => {
+            graph::print_dot(&rows);
+            Ok(())
+        }
+        ExportFormat::TreemapJson => graph::print_treemap(&rows),
+    }
+}
@@ -0,0 +1,442 @@
+use anyhow::{Context, Result};
+use chrono::Local;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+use crate::annotate::{annotate_line, has_issue_reference, issue_references};
+use crate::azure_devops::{AzureDevops, AzureDevopsArgs};
+use crate::i18n::tr;
+use crate::linear::{Linear, LinearArgs};
+use crate::queue::{self, Operation, Queue};
+use crate::state::{Decision, State, TriageEntry};
+use crate::style::{self, Element};
+use crate::{collect_current_matches, CurrentMatch};
+
+/// Issue tracker `file-issues` creates issues in
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum IssueBackend {
+    /// Azure Boards work items
+    AzureDevops,
+    /// Linear issues
+    Linear,
+}
+
+/// An issue to create for one TODO
+pub struct NewIssue {
+    pub title: String,
+    pub description: String,
+}
+
+/// What fask needs from an issue tracker
+pub trait IssueTracker {
+    /// Create an issue and return the key to reference it by in the source
+    fn create(&self, issue: &NewIssue) -> Result<String>;
+
+    /// Whether an issue key refers to this tracker
+    fn owns(&self, key: &str) -> bool;
+
+    /// Current status of an issue, or None if the tracker doesn't know it
+    fn status(&self, key: &str) -> Result<Option<String>>;
+
+    /// Whether a status means the issue needs no more work. The defaults cover Azure
+    /// Boards' and Linear's built-in workflows.
+    fn is_closed(&self, status: &str) -> bool {
+        matches!(
+            status.to_lowercase().as_str(),
+            "closed"
+                | "done"
+                | "resolved"
+                | "removed"
+                | "completed"
+                | "canceled"
+                | "cancelled"
+                | "duplicate"
+        )
+    }
+}
+
+/// Tracker settings from the command line, for whichever backend is picked
+pub struct TrackerArgs<'a> {
+    pub backend: IssueBackend,
+    pub azure: &'a AzureDevopsArgs,
+    pub linear: &'a LinearArgs,
+}
+
+impl TrackerArgs<'_> {
+    fn connect(&self, root: &Path) -> Result<Box<dyn IssueTracker>> {
+        Ok(match self.backend {
+            IssueBackend::AzureDevops => Box::new(AzureDevops::new(self.azure, root)?),
+            IssueBackend::Linear => Box::new(Linear::new(self.linear)?),
+        })
+    }
+}
+
+/// An issue `file-issues` couldn't create, with what it takes to create it later
+#[derive(Serialize, Deserialize)]
+pub struct QueuedIssue {
+    pub backend: IssueBackend,
+    pub azure: AzureDevopsArgs,
+    pub linear: LinearArgs,
+    pub pattern: String,
+    /// The TODO's ID, for the triage state
+    pub id: String,
+    /// Path relative to the repository root
+    pub file: String,
+    /// The TODO's line, trimmed, to find it again once the issue exists
+    pub text: String,
+    pub title: String,
+    pub description: String,
+}
+
+/// Create a queued issue, write its key into the TODO's line wherever the line is now,
+/// and record it in the triage state
+pub fn file_queued(issue: &QueuedIssue, root: &Path) -> Result<String> {
+    let tracker = TrackerArgs {
+        backend: issue.backend,
+        azure: &issue.azure,
+        linear: &issue.linear,
+    }
+    .connect(root)?;
+    let key = tracker.create(&NewIssue {
+        title: issue.title.clone(),
+        description: issue.description.clone(),
+    })?;
+
+    // The line may have moved or gone since; the issue stands either way
+    let path = root.join(&issue.file);
+    if let Ok(content) = std::fs::read_to_string(&path) {
+        let mut lines: Vec<String> = content.split_inclusive('\n').map(String::from).collect();
+        let annotated = lines
+            .iter_mut()
+            .find(|line| line.trim() == issue.text)
+            .and_then(|line| {
+                let new_line = annotate_line(line, &issue.pattern, &key)?;
+                *line = new_line;
+                Some(())
+            });
+        if annotated.is_some() {
+            std::fs::write(&path, lines.concat())
+                .with_context(|| tr!("Failed to write file: {}", path.display()))?;
+        }
+    }
+
+    let mut state = State::load(root)?;
+    state.triage.insert(
+        issue.id.clone(),
+        TriageEntry {
+            decision: Decision::Filed,
+            decided_on: Local::now().date_naive(),
+            file: issue.file.clone(),
+            text: issue.text.clone(),
+            issue: Some(key.clone()),
+            until: None,
+        },
+    );
+    state.save()?;
+    Ok(key)
+}
+
+/// Title for the issue: the TODO's text after the keyword, without comment closers
+fn issue_title(m: &CurrentMatch, file: &str) -> String {
+    let after = match m.spans.first() {
+        Some(&(_, end)) => &m.line_content[end..],
+        None => m.line_content.as_str(),
+    };
+    // Drop a leading "(owner)" tag
+    let after = after.trim_start();
+    let after = match after
+        .strip_prefix('(')
+        .and_then(|rest| rest.split_once(')'))
+    {
+        Some((_, rest)) => rest,
+        None => after,
+    };
+    let title = after
+        .trim()
+        .trim_end_matches("*/")
+        .trim_end_matches("-->")
+        .trim_start_matches([':', '-', ' '])
+        .trim();
+    if title.is_empty() {
+        format!("TODO in {}:{}", file, m.line_number)
+    } else {
+        title.to_string()
+    }
+}
+
+/// Create an issue for every TODO without an issue reference or triage decision, then
+/// write the new issue key back into the source ("TODO(AB#123)") and the triage state
+pub fn file_issues(
+    pattern: &str,
+    file_type: Option<&str>,
+    directory: &Path,
+    tracker_args: &TrackerArgs,
+) -> Result<()> {
+    let mut state = State::load(directory)?;
+    let mut queue = Queue::load(directory)?;
+    let queued: HashSet<String> = queue.queued_issue_ids().map(String::from).collect();
+    let matches = collect_current_matches(pattern, file_type, directory)?;
+
+    let pending: Vec<&CurrentMatch> = matches
+        .iter()
+        .filter(|m| !has_issue_reference(&m.line_content, pattern))
+        .filter(|m| {
+            let id = m.id(&state.root);
+            !state.triage.contains_key(&id) && !queued.contains(&id)
+        })
+        .collect();
+
+    if pending.is_empty() {
+        println!("{}", tr!("No '{}'s to file.", pattern));
+        return Ok(());
+    }
+
+    let tracker = tracker_args.connect(&state.root)?;
+
+    let mut by_file: BTreeMap<&str, Vec<&CurrentMatch>> = BTreeMap::new();
+    for m in pending {
+        by_file.entry(m.file.as_str()).or_default().push(m);
+    }
+
+    let today = Local::now().date_naive();
+    let mut filed = 0;
+    // After a rate limit or a network failure the rest go straight to the queue
+    let mut offline: Option<anyhow::Error> = None;
+    for (file, file_matches) in by_file {
+        let content =
+            std::fs::read_to_string(file).with_context(|| tr!("Failed to read file: {}", file))?;
+        let mut lines: Vec<String> = content.split_inclusive('\n').map(String::from).collect();
+
+        for m in file_matches {
+            let relative = m.relative_file(&state.root);
+            let issue = NewIssue {
+                title: issue_title(m, &relative),
+                description: format!(
+                    "{}:{}\n\n{}",
+                    relative,
+                    m.line_number,
+                    m.line_content.trim()
+                ),
+            };
+            let created = match &offline {
+                Some(e) => Err(anyhow::anyhow!("{:#}", e)),
+                None => tracker.create(&issue),
+            };
+            let key = match created {
+                Ok(key) => key,
+                Err(e) if offline.is_some() || queue::is_transient(&e) => {
+                    queue.push(
+                        Operation::FileIssue(Box::new(QueuedIssue {
+                            backend: tracker_args.backend,
+                            azure: tracker_args.azure.clone(),
+                            linear: tracker_args.linear.clone(),
+                            pattern: pattern.to_string(),
+                            id: m.id(&state.root),
+                            file: relative,
+                            text: m.line_content.trim().to_string(),
+                            title: issue.title,
+                            description: issue.description,
+                        })),
+                        &e,
+                    );
+                    queue.save()?;
+                    if offline.is_none() {
+                        println!(
+                            "{} {:#}",
+                            style::paint(Element::Warning, tr!("Couldn't reach the tracker:")),
+                            e
+                        );
+                        offline = Some(e);
+                    }
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            println!(
+                "{}",
+                tr!(
+                    "{}:{}: filed as {}",
+                    style::paint(Element::Path, &m.file),
+                    style::paint(Element::LineNumber, m.line_number),
+                    key
+                )
+            );
+
+            if let Some(line) = lines.get_mut(m.line_number - 1) {
+                if let Some(new_line) = annotate_line(line, pattern, &key) {
+                    *line = new_line;
+                }
+            }
+
+            state.triage.insert(
+                m.id(&state.root),
+                TriageEntry {
+                    decision: Decision::Filed,
+                    decided_on: today,
+                    file: relative,
+                    text: m.line_content.trim().to_string(),
+                    issue: Some(key),
+                    until: None,
+                },
+            );
+            filed += 1;
+        }
+
+        // Write back per file so a failure later on keeps what was already filed
+        std::fs::write(file, lines.concat())
+            .with_context(|| tr!("Failed to write file: {}", file))?;
+        state.save()?;
+    }
+
+    println!("{}", tr!("\nFiled {} issue(s).", filed));
+    if offline.is_some() {
+        println!(
+            "{}",
+            tr!(
+                "Queued {} for later; `fask flush` files them.",
+                queue.queued_issue_ids().count()
+            )
+        );
+    }
+    Ok(())
+}
+
+/// List TODOs that reference the tracker's issues, with each issue's current status
+pub fn issue_status(
+    pattern: &str,
+    file_type: Option<&str>,
+    directory: &Path,
+    tracker: &TrackerArgs,
+) -> Result<()> {
+    let root = crate::state::repo_root(directory);
+    let tracker = tracker.connect(&root)?;
+    let matches = collect_current_matches(pattern, file_type, directory)?;
+
+    let mut statuses: BTreeMap<String, Option<String>> = BTreeMap::new();
+    let mut listed = 0;
+    for m in &matches {
+        for key in issue_references(&m.line_content, pattern) {
+            if !tracker.owns(key) {
+                continue;
+            }
+            // Several TODOs often point at the same issue
+            if !statuses.contains_key(key) {
+                statuses.insert(key.to_string(), tracker.status(key)?);
+            }
+            let status = statuses[key].as_deref().unwrap_or("unknown");
+            println!(
+                "{}:{}: {} {} {}",
+                style::paint(Element::Path, &m.file),
+                style::paint(Element::LineNumber, m.line_number),
+                key,
+                style::paint(Element::Label, format!("[{}]", status)),
+                m.line_content.trim()
+            );
+            listed += 1;
+        }
+    }
+
+    if listed == 0 {
+        println!(
+            "{}",
+            tr!("No '{}'s reference this tracker's issues.", pattern)
+        );
+        return Ok(());
+    }
+
+    let mut by_status: BTreeMap<&str, usize> = BTreeMap::new();
+    for status in statuses.values() {
+        *by_status
+            .entry(status.as_deref().unwrap_or("unknown"))
+            .or_default() += 1;
+    }
+    let summary: Vec<String> = by_status
+        .iter()
+        .map(|(status, count)| format!("{} {}", count, status))
+        .collect();
+    println!(
+        "{}",
+        tr!(
+            "\n{} '{}'(s) referencing {} issue(s): {}.",
+            listed,
+            pattern,
+            statuses.len(),
+            summary.join(", ")
+        )
+    );
+
+    Ok(())
+}
+
+/// List TODOs whose referenced issue is already closed; they're likely stale or forgotten.
+/// Returns whether any were found.
+pub fn verify_issues(
+    pattern: &str,
+    file_type: Option<&str>,
+    directory: &Path,
+    tracker: &TrackerArgs,
+) -> Result<bool> {
+    let root = crate::state::repo_root(directory);
+    let tracker = tracker.connect(&root)?;
+    let matches = collect_current_matches(pattern, file_type, directory)?;
+
+    let mut statuses: BTreeMap<String, Option<String>> = BTreeMap::new();
+    let mut checked = 0;
+    let mut stale = 0;
+    for m in &matches {
+        for key in issue_references(&m.line_content, pattern) {
+            if !tracker.owns(key) {
+                continue;
+            }
+            if !statuses.contains_key(key) {
+                statuses.insert(key.to_string(), tracker.status(key)?);
+            }
+            checked += 1;
+            let Some(status) = statuses[key].as_deref() else {
+                continue;
+            };
+            if !tracker.is_closed(status) {
+                continue;
+            }
+            println!(
+                "{}:{}: {} is {}: {}",
+                style::paint(Element::Path, &m.file),
+                style::paint(Element::LineNumber, m.line_number),
+                key,
+                style::paint(Element::Error, status),
+                m.line_content.trim()
+            );
+            stale += 1;
+        }
+    }
+
+    if checked == 0 {
+        println!(
+            "{}",
+            tr!("No '{}'s reference this tracker's issues.", pattern)
+        );
+    } else if stale == 0 {
+        println!(
+            "{}",
+            tr!(
+                "All {} issue reference(s) in '{}'s point at open issues.",
+                checked,
+                pattern
+            )
+        );
+    } else {
+        println!(
+            "{}",
+            tr!(
+                "\n{} of {} issue reference(s) point at closed issues.",
+                stale,
+                checked
+            )
+        );
+    }
+
+    Ok(stale > 0)
+}
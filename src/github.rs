@@ -0,0 +1,188 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::i18n::tr;
+use crate::pr::{comment_body, git, marker_id, PrTodo};
+use crate::todo_id;
+
+/// Just enough of the GitHub API to comment on pull requests
+struct GitHub {
+    api: String,
+    token: String,
+    /// "owner/name"
+    repo: String,
+}
+
+#[derive(Deserialize)]
+struct PullRequest {
+    number: u64,
+    html_url: String,
+    head: Head,
+}
+
+#[derive(Deserialize)]
+struct Head {
+    sha: String,
+}
+
+#[derive(Deserialize)]
+struct ReviewComment {
+    body: String,
+}
+
+/// "owner/name" from a GitHub remote URL in either the https or ssh form
+fn parse_repo(url: &str) -> Option<String> {
+    let url = url.trim().trim_end_matches('/');
+    let path = url
+        .split_once("github.com/")
+        .or_else(|| url.split_once("github.com:"))
+        .map(|(_, path)| path)?;
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    let mut parts = path.split('/');
+    match (parts.next(), parts.next()) {
+        (Some(owner), Some(name)) if !owner.is_empty() && !name.is_empty() => {
+            Some(format!("{}/{}", owner, name))
+        }
+        _ => None,
+    }
+}
+
+impl GitHub {
+    /// Set up from the `origin` remote and $GITHUB_TOKEN (or $GH_TOKEN).
+    /// $GITHUB_API_URL points it at GitHub Enterprise.
+    fn from_env(root: &Path) -> Result<GitHub> {
+        let token = std::env::var("GITHUB_TOKEN")
+            .or_else(|_| std::env::var("GH_TOKEN"))
+            .with_context(|| tr!("Set GITHUB_TOKEN to post review comments"))?;
+        let url = git(&["remote", "get-url", "origin"], root)?;
+        let repo = parse_repo(&url)
+            .with_context(|| tr!("Remote 'origin' is not a GitHub repository: {}", url))?;
+        let api = std::env::var("GITHUB_API_URL")
+            .unwrap_or_else(|_| "https://api.github.com".to_string());
+
+        Ok(GitHub {
+            api: api.trim_end_matches('/').to_string(),
+            token,
+            repo,
+        })
+    }
+
+    fn request(&self, method: &str, path: &str) -> ureq::Request {
+        ureq::request(method, &format!("{}{}", self.api, path))
+            .set("Authorization", &format!("Bearer {}", self.token))
+            .set("Accept", "application/vnd.github+json")
+            .set("User-Agent", "fask")
+    }
+
+    /// The open pull request whose head is `branch`
+    fn find_pull_request(&self, branch: &str) -> Result<PullRequest> {
+        let owner = self.repo.split('/').next().unwrap_or_default();
+        let pulls: Vec<PullRequest> = self
+            .request("GET", &format!("/repos/{}/pulls", self.repo))
+            .query("state", "open")
+            .query("head", &format!("{}:{}", owner, branch))
+            .call()
+            .with_context(|| tr!("Failed to list pull requests"))?
+            .into_json()?;
+        pulls
+            .into_iter()
+            .next()
+            .with_context(|| tr!("No open pull request for branch '{}'", branch))
+    }
+
+    /// Bodies of every review comment on the pull request
+    fn review_comments(&self, number: u64) -> Result<Vec<ReviewComment>> {
+        let mut comments = Vec::new();
+        for page in 1.. {
+            let batch: Vec<ReviewComment> = self
+                .request(
+                    "GET",
+                    &format!("/repos/{}/pulls/{}/comments", self.repo, number),
+                )
+                .query("per_page", "100")
+                .query("page", &page.to_string())
+                .call()
+                .with_context(|| tr!("Failed to list review comments"))?
+                .into_json()?;
+            let done = batch.len() < 100;
+            comments.extend(batch);
+            if done {
+                break;
+            }
+        }
+        Ok(comments)
+    }
+
+    fn create_review_comment(
+        &self,
+        number: u64,
+        commit: &str,
+        todo: &PrTodo,
+        body: &str,
+    ) -> Result<()> {
+        self.request(
+            "POST",
+            &format!("/repos/{}/pulls/{}/comments", self.repo, number),
+        )
+        .send_json(json!({
+            "body": body,
+            "commit_id": commit,
+            "path": todo.file,
+            "line": todo.line_number,
+            "side": "RIGHT",
+        }))
+        .with_context(|| tr!("Failed to comment on {}:{}", todo.file, todo.line_number))?;
+        Ok(())
+    }
+}
+
+/// Post one inline review comment per TODO on the open pull request for the current branch,
+/// skipping TODOs fask already commented on
+pub fn post_review_comments(todos: &[PrTodo], pattern: &str, root: &Path) -> Result<()> {
+    let github = GitHub::from_env(root)?;
+    let branch = git(&["rev-parse", "--abbrev-ref", "HEAD"], root)?;
+    let pr = github.find_pull_request(&branch)?;
+
+    // Line numbers come from the blobs at HEAD, so they only line up with the PR if HEAD is
+    // what was pushed
+    let head = git(&["rev-parse", "HEAD"], root)?;
+    if head != pr.head.sha {
+        anyhow::bail!(tr!(
+            "HEAD ({}) is not the head of {}. Push the branch first.",
+            &head[..8.min(head.len())],
+            pr.html_url
+        ));
+    }
+
+    let posted: HashSet<String> = github
+        .review_comments(pr.number)?
+        .iter()
+        .filter_map(|c| marker_id(&c.body).map(String::from))
+        .collect();
+
+    let mut created = 0;
+    for todo in todos {
+        let id = todo_id(&todo.file, todo.line.trim());
+        if posted.contains(&id) {
+            continue;
+        }
+        let body = comment_body(pattern, todo, &id);
+        github.create_review_comment(pr.number, &pr.head.sha, todo, &body)?;
+        created += 1;
+    }
+
+    println!(
+        "{}",
+        tr!(
+            "Posted {} review comment(s) on {} ({} already there).",
+            created,
+            pr.html_url,
+            todos.len() - created
+        )
+    );
+
+    Ok(())
+}
@@ -0,0 +1,249 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::i18n::tr;
+use crate::pr::{comment_body, git, marker_id, PrTodo};
+use crate::todo_id;
+
+/// Just enough of the GitLab API to hold discussions on merge requests
+struct GitLab {
+    /// e.g. "https://gitlab.com/api/v4"
+    api: String,
+    token: String,
+    /// URL-encoded "group/name", usable as a project ID
+    project: String,
+}
+
+#[derive(Deserialize)]
+struct MergeRequest {
+    iid: u64,
+    web_url: String,
+    #[serde(default)]
+    diff_refs: Option<DiffRefs>,
+}
+
+#[derive(Deserialize)]
+struct DiffRefs {
+    base_sha: String,
+    start_sha: String,
+    head_sha: String,
+}
+
+#[derive(Deserialize)]
+struct Discussion {
+    id: String,
+    notes: Vec<Note>,
+}
+
+#[derive(Deserialize)]
+struct Note {
+    body: String,
+    #[serde(default)]
+    resolved: bool,
+}
+
+/// Host of a remote URL in the https ("https://host/...") or ssh ("git@host:...") form
+pub fn remote_host(url: &str) -> Option<String> {
+    let rest = match url.trim().split_once("://") {
+        Some((_, rest)) => rest,
+        None => url.trim(),
+    };
+    let rest = rest.rsplit_once('@').map_or(rest, |(_, host)| host);
+    let host = rest.split([':', '/']).next()?;
+    (!host.is_empty()).then(|| host.to_string())
+}
+
+/// Project path ("group/subgroup/name") of a remote URL
+pub fn project_path(url: &str, host: &str) -> Option<String> {
+    let (_, path) = url.trim().split_once(host)?;
+    let path = path.strip_prefix(':').unwrap_or(path).trim_matches('/');
+    // ssh://git@host:2222/group/name has a port before the path
+    let path = match path.split_once('/') {
+        Some((port, rest)) if url.contains("://") && port.bytes().all(|b| b.is_ascii_digit()) => {
+            rest
+        }
+        _ => path,
+    };
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    path.contains('/').then(|| path.to_string())
+}
+
+impl GitLab {
+    /// Set up from the `origin` remote and $GITLAB_TOKEN.
+    /// $GITLAB_URL overrides the instance, e.g. when the remote uses an ssh alias.
+    fn from_env(root: &Path) -> Result<GitLab> {
+        let token = std::env::var("GITLAB_TOKEN")
+            .with_context(|| tr!("Set GITLAB_TOKEN to post merge request discussions"))?;
+        let url = git(&["remote", "get-url", "origin"], root)?;
+        let host =
+            remote_host(&url).with_context(|| tr!("Can't parse remote 'origin': {}", url))?;
+        let project = project_path(&url, &host)
+            .with_context(|| tr!("Can't parse remote 'origin': {}", url))?;
+        let base = std::env::var("GITLAB_URL").unwrap_or_else(|_| format!("https://{}", host));
+
+        Ok(GitLab {
+            api: format!("{}/api/v4", base.trim_end_matches('/')),
+            token,
+            project: project.replace('/', "%2F"),
+        })
+    }
+
+    fn request(&self, method: &str, path: &str) -> ureq::Request {
+        ureq::request(
+            method,
+            &format!("{}/projects/{}{}", self.api, self.project, path),
+        )
+        .set("PRIVATE-TOKEN", &self.token)
+        .set("User-Agent", "fask")
+    }
+
+    /// The open merge request from `branch`, with its diff refs
+    fn find_merge_request(&self, branch: &str) -> Result<MergeRequest> {
+        let requests: Vec<MergeRequest> = self
+            .request("GET", "/merge_requests")
+            .query("state", "opened")
+            .query("source_branch", branch)
+            .call()
+            .with_context(|| tr!("Failed to list merge requests"))?
+            .into_json()?;
+        let iid = requests
+            .first()
+            .with_context(|| tr!("No open merge request for branch '{}'", branch))?
+            .iid;
+
+        // Only the single merge request endpoint returns the diff refs
+        self.request("GET", &format!("/merge_requests/{}", iid))
+            .call()
+            .with_context(|| tr!("Failed to fetch merge request"))?
+            .into_json()
+            .with_context(|| tr!("Failed to parse merge request"))
+    }
+
+    fn discussions(&self, iid: u64) -> Result<Vec<Discussion>> {
+        let mut discussions = Vec::new();
+        for page in 1.. {
+            let batch: Vec<Discussion> = self
+                .request("GET", &format!("/merge_requests/{}/discussions", iid))
+                .query("per_page", "100")
+                .query("page", &page.to_string())
+                .call()
+                .with_context(|| tr!("Failed to list discussions"))?
+                .into_json()?;
+            let done = batch.len() < 100;
+            discussions.extend(batch);
+            if done {
+                break;
+            }
+        }
+        Ok(discussions)
+    }
+
+    fn create_discussion(
+        &self,
+        iid: u64,
+        refs: &DiffRefs,
+        todo: &PrTodo,
+        body: &str,
+    ) -> Result<()> {
+        self.request("POST", &format!("/merge_requests/{}/discussions", iid))
+            .send_json(json!({
+                "body": body,
+                "position": {
+                    "position_type": "text",
+                    "base_sha": refs.base_sha,
+                    "start_sha": refs.start_sha,
+                    "head_sha": refs.head_sha,
+                    "new_path": todo.file,
+                    "new_line": todo.line_number,
+                },
+            }))
+            .with_context(|| tr!("Failed to comment on {}:{}", todo.file, todo.line_number))?;
+        Ok(())
+    }
+
+    fn set_resolved(&self, iid: u64, discussion: &str, resolved: bool) -> Result<()> {
+        self.request(
+            "PUT",
+            &format!("/merge_requests/{}/discussions/{}", iid, discussion),
+        )
+        .query("resolved", if resolved { "true" } else { "false" })
+        .call()
+        .with_context(|| tr!("Failed to update discussion"))?;
+        Ok(())
+    }
+}
+
+/// Open one diff discussion per new TODO on the merge request for the current branch.
+/// Discussions whose TODO is gone get resolved, and reopened if it comes back.
+pub fn post_discussions(todos: &[PrTodo], pattern: &str, root: &Path) -> Result<()> {
+    let gitlab = GitLab::from_env(root)?;
+    let branch = git(&["rev-parse", "--abbrev-ref", "HEAD"], root)?;
+    let mr = gitlab.find_merge_request(&branch)?;
+    let refs = mr
+        .diff_refs
+        .as_ref()
+        .with_context(|| tr!("Merge request has no diff yet"))?;
+
+    // Line numbers come from the working tree, so they only line up with what was pushed
+    let head = git(&["rev-parse", "HEAD"], root)?;
+    if head != refs.head_sha {
+        anyhow::bail!(tr!(
+            "HEAD ({}) is not the head of {}. Push the branch first.",
+            &head[..8.min(head.len())],
+            mr.web_url
+        ));
+    }
+
+    let current: HashSet<String> = todos
+        .iter()
+        .map(|t| todo_id(&t.file, t.line.trim()))
+        .collect();
+
+    let mut existing = HashSet::new();
+    let (mut resolved, mut reopened) = (0, 0);
+    for discussion in gitlab.discussions(mr.iid)? {
+        let Some(note) = discussion.notes.first() else {
+            continue;
+        };
+        let Some(id) = marker_id(&note.body) else {
+            continue;
+        };
+        let present = current.contains(id);
+        if present == note.resolved {
+            gitlab.set_resolved(mr.iid, &discussion.id, !present)?;
+            if present {
+                reopened += 1;
+            } else {
+                resolved += 1;
+            }
+        }
+        existing.insert(id.to_string());
+    }
+
+    let mut created = 0;
+    for todo in todos {
+        let id = todo_id(&todo.file, todo.line.trim());
+        if existing.contains(&id) {
+            continue;
+        }
+        let body = comment_body(pattern, todo, &id);
+        gitlab.create_discussion(mr.iid, refs, todo, &body)?;
+        created += 1;
+    }
+
+    println!(
+        "{}",
+        tr!(
+            "Opened {} discussion(s) on {} ({} resolved, {} reopened).",
+            created,
+            mr.web_url,
+            resolved,
+            reopened
+        )
+    );
+
+    Ok(())
+}
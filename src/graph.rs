@@ -0,0 +1,86 @@
+use anyhow::Result;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+
+use crate::export::ExportRow;
+
+/// A directory (or file) and the TODOs under it
+#[derive(Default)]
+struct Node {
+    count: usize,
+    children: BTreeMap<String, Node>,
+}
+
+/// Build the directory tree of the TODOs' files, with counts summed up to the root
+fn tree(rows: &[ExportRow]) -> Node {
+    let mut root = Node::default();
+    for row in rows {
+        let mut node = &mut root;
+        node.count += 1;
+        for part in row.file.split('/') {
+            node = node.children.entry(part.to_string()).or_default();
+            node.count += 1;
+        }
+    }
+    root
+}
+
+/// Escape a string for a quoted DOT ID
+fn dot_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Quote a string as a DOT ID
+fn dot_quote(s: &str) -> String {
+    format!("\"{}\"", dot_escape(s))
+}
+
+fn write_dot_node(out: &mut String, path: &str, name: &str, node: &Node) {
+    // "\n" is DOT's own line break inside a label
+    out.push_str(&format!(
+        "  {} [label=\"{}\\n{}\"];\n",
+        dot_quote(path),
+        dot_escape(name),
+        node.count
+    ));
+    // Files are leaves; only directories get drawn
+    for (child_name, child) in node.children.iter().filter(|(_, c)| !c.children.is_empty()) {
+        let child_path = format!("{}/{}", path, child_name);
+        write_dot_node(out, &child_path, child_name, child);
+        out.push_str(&format!(
+            "  {} -> {};\n",
+            dot_quote(path),
+            dot_quote(&child_path)
+        ));
+    }
+}
+
+/// Print the directory hierarchy with TODO counts as a Graphviz digraph
+pub fn print_dot(rows: &[ExportRow]) {
+    let mut out = String::from("digraph todos {\n  rankdir=LR;\n  node [shape=box];\n");
+    write_dot_node(&mut out, ".", ".", &tree(rows));
+    out.push_str("}\n");
+    print!("{}", out);
+}
+
+/// d3.hierarchy shape: directories have children, files have a value
+fn treemap_node(name: &str, node: &Node) -> Value {
+    if node.children.is_empty() {
+        return json!({ "name": name, "value": node.count });
+    }
+    let children: Vec<Value> = node
+        .children
+        .iter()
+        .map(|(child_name, child)| treemap_node(child_name, child))
+        .collect();
+    json!({ "name": name, "children": children })
+}
+
+/// Print the TODO counts per file, nested by directory, as JSON for d3 treemaps
+pub fn print_treemap(rows: &[ExportRow]) -> Result<()> {
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&treemap_node(".", &tree(rows)))?
+    );
+    Ok(())
+}
@@ -0,0 +1,152 @@
+use anyhow::Result;
+use chrono::{Datelike, Duration, NaiveDate};
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::i18n::tr;
+use crate::style::{self, Element};
+use crate::terminal_width;
+
+/// Width of the longest bar when stdout isn't a terminal
+const DEFAULT_BAR_WIDTH: usize = 50;
+
+/// Time span each histogram bar covers
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Bucket {
+    /// ISO weeks, labeled like "2025-W03"
+    Week,
+    /// Calendar months, labeled like "2025-01"
+    Month,
+}
+
+/// Number of additions in one bucket
+#[derive(Serialize)]
+pub struct BucketCount {
+    pub bucket: String,
+    pub added: usize,
+}
+
+impl Bucket {
+    /// First day of the bucket the date falls in
+    fn start(self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Bucket::Week => date - Duration::days(date.weekday().num_days_from_monday() as i64),
+            Bucket::Month => date.with_day(1).unwrap_or(date),
+        }
+    }
+
+    /// First day of the following bucket
+    fn next(self, start: NaiveDate) -> NaiveDate {
+        match self {
+            Bucket::Week => start + Duration::days(7),
+            Bucket::Month => start
+                .checked_add_months(chrono::Months::new(1))
+                .unwrap_or(start + Duration::days(31)),
+        }
+    }
+
+    fn label(self, start: NaiveDate) -> String {
+        match self {
+            Bucket::Week => start.format("%G-W%V").to_string(),
+            Bucket::Month => start.format("%Y-%m").to_string(),
+        }
+    }
+
+    /// Label of the bucket the date falls in
+    pub fn of(self, date: NaiveDate) -> String {
+        self.label(self.start(date))
+    }
+}
+
+/// Count dates per bucket, from the first bucket to the last with empty ones in between
+pub fn bucket_counts(
+    dates: impl IntoIterator<Item = NaiveDate>,
+    bucket: Bucket,
+) -> Vec<BucketCount> {
+    let mut starts: Vec<NaiveDate> = dates.into_iter().map(|d| bucket.start(d)).collect();
+    starts.sort_unstable();
+    let (Some(&first), Some(&last)) = (starts.first(), starts.last()) else {
+        return Vec::new();
+    };
+
+    let mut counts = Vec::new();
+    let mut start = first;
+    let mut remaining = starts.as_slice();
+    while start <= last {
+        let added = remaining.iter().take_while(|&&s| s == start).count();
+        remaining = &remaining[added..];
+        counts.push(BucketCount {
+            bucket: bucket.label(start),
+            added,
+        });
+        start = bucket.next(start);
+    }
+    counts
+}
+
+/// Additions in one bucket on one branch, for `trend --branches --json`
+#[derive(Serialize)]
+struct BranchCount<'a> {
+    branch: &'a str,
+    #[serde(flatten)]
+    count: &'a BucketCount,
+}
+
+/// Print a chart per branch under its name, or one JSON object per branch and bucket
+pub fn print_branch_histograms(charts: &[(String, Vec<BucketCount>)], json: bool) -> Result<()> {
+    if json {
+        for (branch, counts) in charts {
+            for count in counts {
+                println!("{}", serde_json::to_string(&BranchCount { branch, count })?);
+            }
+        }
+        return Ok(());
+    }
+
+    for (idx, (branch, counts)) in charts.iter().enumerate() {
+        if idx > 0 {
+            println!();
+        }
+        println!("{}", style::paint(Element::Heading, branch));
+        if counts.is_empty() {
+            println!("{}", tr!("No additions found."));
+        }
+        print_histogram(counts, false)?;
+    }
+    Ok(())
+}
+
+/// Print the counts as an ASCII bar chart, or as one JSON object per bucket
+pub fn print_histogram(counts: &[BucketCount], json: bool) -> Result<()> {
+    if json {
+        for c in counts {
+            println!("{}", serde_json::to_string(c)?);
+        }
+        return Ok(());
+    }
+
+    let max = counts.iter().map(|c| c.added).max().unwrap_or(0);
+    let label_width = counts.iter().map(|c| c.bucket.len()).max().unwrap_or(0);
+    let count_width = max.to_string().len();
+    let bar_width = terminal_width()
+        .map(|w| w.saturating_sub(label_width + count_width + 4).max(10))
+        .unwrap_or(DEFAULT_BAR_WIDTH);
+
+    for c in counts {
+        // Round up so a single addition still shows
+        let bar = if max == 0 {
+            0
+        } else {
+            (c.added * bar_width).div_ceil(max)
+        };
+        println!(
+            "{:<lw$}  {:>cw$}  {}",
+            c.bucket,
+            c.added,
+            "#".repeat(bar),
+            lw = label_width,
+            cw = count_width
+        );
+    }
+    Ok(())
+}
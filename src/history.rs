@@ -0,0 +1,167 @@
+use anyhow::Result;
+use chrono::NaiveDate;
+use std::io::BufRead;
+use std::path::Path;
+use std::process::Command;
+
+use crate::markdown;
+use crate::timing;
+use crate::unicode;
+use crate::{for_each_line, git_log_args, stream_git_log};
+
+/// One TODO's life in the history: when and by whom it was added and, if it is gone, resolved
+#[derive(Debug, Clone)]
+pub struct Lifetime {
+    /// File it was last seen in, relative to the repository root
+    pub file: String,
+    pub content: String,
+    pub added: NaiveDate,
+    pub added_by: String,
+    pub resolved: Option<NaiveDate>,
+    pub resolved_by: Option<String>,
+}
+
+impl Lifetime {
+    /// Days from being added to being resolved
+    pub fn days_open(&self) -> Option<i64> {
+        Some((self.resolved? - self.added).num_days())
+    }
+}
+
+/// What one commit did to lines containing the pattern
+#[derive(Default)]
+struct CommitChanges {
+    date: Option<NaiveDate>,
+    author: String,
+    removed: Vec<(String, String)>,
+    added: Vec<(String, String)>,
+}
+
+/// Replay one commit: removals that reappear in the same commit (moved, or edited in place)
+/// keep their original date, the others are resolved
+fn apply(commit: CommitChanges, open: &mut Vec<Lifetime>, resolved: &mut Vec<Lifetime>) {
+    let Some(date) = commit.date else {
+        return;
+    };
+    let mut added: Vec<Option<(String, String)>> = commit.added.into_iter().map(Some).collect();
+
+    for (file, content) in commit.removed {
+        // The TODO as it was; if it was edited in a commit the pickaxe skipped, the oldest
+        // in the same file stands in for it
+        let position = open
+            .iter()
+            .position(|l| l.file == file && unicode::same(&l.content, &content))
+            .or_else(|| {
+                open.iter()
+                    .position(|l| unicode::same(&l.content, &content))
+            })
+            .or_else(|| open.iter().position(|l| l.file == file));
+        let Some(position) = position else {
+            continue;
+        };
+        let mut lifetime = open.remove(position);
+
+        let same_line = added
+            .iter()
+            .position(|a| a.as_ref().is_some_and(|(_, c)| unicode::same(c, &content)))
+            .or_else(|| {
+                added
+                    .iter()
+                    .position(|a| a.as_ref().is_some_and(|(f, _)| *f == file))
+            });
+        match same_line.and_then(|i| added[i].take()) {
+            Some((file, content)) => {
+                tracing::trace!(from = %lifetime.file, to = %file, "moved or edited");
+                lifetime.file = file;
+                lifetime.content = content;
+                open.push(lifetime);
+            }
+            None => {
+                tracing::debug!(file = %lifetime.file, content = %lifetime.content, %date, "resolved");
+                lifetime.resolved = Some(date);
+                lifetime.resolved_by = Some(commit.author.clone());
+                resolved.push(lifetime);
+            }
+        }
+    }
+
+    for (file, content) in added.into_iter().flatten() {
+        open.push(Lifetime {
+            file,
+            content,
+            added: date,
+            added_by: commit.author.clone(),
+            resolved: None,
+            resolved_by: None,
+        });
+    }
+}
+
+/// Parse `git log -p --reverse` output into lifetimes, oldest commit first
+fn parse_lifetimes(output: &mut dyn BufRead, pattern: &str) -> Result<Vec<Lifetime>> {
+    let tasks = markdown::counts_tasks(pattern);
+    let mut open = Vec::new();
+    let mut resolved = Vec::new();
+    let mut commit = CommitChanges::default();
+    let mut old_file = String::new();
+    let mut new_file = String::new();
+    let mut in_hunk = false;
+
+    for_each_line(output, |line| {
+        if line.starts_with("commit ") {
+            apply(std::mem::take(&mut commit), &mut open, &mut resolved);
+            in_hunk = false;
+        } else if line.starts_with("diff --git ") {
+            in_hunk = false;
+        } else if !in_hunk {
+            if let Some(date) = line.strip_prefix("Date:") {
+                commit.date = NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d").ok();
+            } else if let Some(author) = line.strip_prefix("Author:") {
+                commit.author = author.trim().to_string();
+            } else if let Some(file) = line.strip_prefix("--- a/") {
+                old_file = file.to_string();
+            } else if let Some(file) = line.strip_prefix("+++ b/") {
+                new_file = file.to_string();
+            } else if line.starts_with("@@") {
+                in_hunk = true;
+            }
+        } else if let Some(content) = line.strip_prefix('+') {
+            if unicode::contains(content, pattern)
+                || (tasks && markdown::is_task_in(&new_file, content))
+            {
+                commit
+                    .added
+                    .push((new_file.clone(), content.trim().to_string()));
+            }
+        } else if let Some(content) = line.strip_prefix('-') {
+            // Checking a task off removes its open line, which resolves it
+            if unicode::contains(content, pattern)
+                || (tasks && markdown::is_task_in(&old_file, content))
+            {
+                commit
+                    .removed
+                    .push((old_file.clone(), content.trim().to_string()));
+            }
+        }
+    })?;
+    apply(commit, &mut open, &mut resolved);
+
+    resolved.extend(open);
+    Ok(resolved)
+}
+
+/// Replay the history of lines containing the pattern into TODO lifetimes, resolved and open.
+/// Author names go through `.mailmap`.
+pub fn lifetimes(pattern: &str, directory: &Path) -> Result<Vec<Lifetime>> {
+    let _replay = timing::start("git log and replay");
+    let mut cmd = Command::new("git");
+    cmd.arg("log")
+        .args(git_log_args())
+        .arg("--reverse")
+        .args(unicode::pickaxe_args(&markdown::history_pattern(pattern)))
+        .arg("-p")
+        .arg("--format=commit %H%nDate: %ad%nAuthor: %aN")
+        .arg("--date=short")
+        .current_dir(directory);
+    stream_git_log(&mut cmd, None, |output| parse_lifetimes(output, pattern))
+}
@@ -0,0 +1,111 @@
+use clap::ValueEnum;
+use std::io::IsTerminal;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+use crate::gitlab::{project_path, remote_host};
+use crate::pr::git;
+
+/// When to wrap file paths and commit hashes in OSC 8 hyperlinks
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum HyperlinkMode {
+    /// When stdout is a terminal
+    Auto,
+    /// Even when piped, e.g. into a pager that passes them through
+    Always,
+    Never,
+}
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Web URL prefix for commits of the `origin` remote, looked up on first use
+static COMMIT_BASE: OnceLock<Option<String>> = OnceLock::new();
+
+pub fn init(mode: HyperlinkMode) {
+    let enabled = match mode {
+        HyperlinkMode::Always => true,
+        HyperlinkMode::Never => false,
+        HyperlinkMode::Auto => {
+            std::io::stdout().is_terminal() && std::env::var("TERM").map_or(true, |t| t != "dumb")
+        }
+    };
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// `text` made clickable, opening `url`
+fn link(url: &str, text: &str) -> String {
+    format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, text)
+}
+
+/// Percent-encode everything but unreserved characters and '/'
+fn encode_path(path: &str) -> String {
+    let mut encoded = String::with_capacity(path.len());
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// The file:// URL of a path relative to `base`, or to the working directory when empty
+pub fn file_url(path: &str, base: &Path) -> Option<String> {
+    let full = base.join(path);
+    let absolute = full
+        .canonicalize()
+        .or_else(|_| std::path::absolute(&full))
+        .ok()?;
+    let absolute = crate::slash_path(&absolute.to_string_lossy());
+    // Windows paths come back as "//?/C:/..." and need a leading slash before the drive
+    let absolute = absolute.strip_prefix("//?/").unwrap_or(&absolute);
+    let absolute = if absolute.starts_with('/') {
+        absolute.to_string()
+    } else {
+        format!("/{}", absolute)
+    };
+    Some(format!("file://{}", encode_path(&absolute)))
+}
+
+/// A file path (relative to `base`, or to the working directory when empty) linked to
+/// the file's file:// URL
+pub fn file(path: &str, base: &Path) -> String {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return path.to_string();
+    }
+    match file_url(path, base) {
+        Some(url) => link(&url, path),
+        None => path.to_string(),
+    }
+}
+
+/// Web URL prefix for commits on the forge `origin` points at, if it looks like one
+fn commit_base(root: &Path) -> Option<String> {
+    let url = git(&["remote", "get-url", "origin"], root).ok()?;
+    let host = remote_host(&url)?;
+    let project = project_path(&url, &host)?;
+    // GitHub, Gitea and Forgejo all use "/commit/"
+    let commit = if host.contains("gitlab") {
+        "-/commit"
+    } else if host.contains("bitbucket") {
+        "commits"
+    } else {
+        "commit"
+    };
+    Some(format!("https://{}/{}/{}/", host, project, commit))
+}
+
+/// A commit hash (shown as `text`) linked to the commit's page on the forge. Left as is
+/// when the repository in `directory` has no recognizable `origin`.
+pub fn commit(hash: &str, text: &str, directory: &Path) -> String {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return text.to_string();
+    }
+    match COMMIT_BASE.get_or_init(|| commit_base(directory)) {
+        Some(base) => link(&format!("{}{}", base, hash), text),
+        None => text.to_string(),
+    }
+}
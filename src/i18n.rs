@@ -0,0 +1,518 @@
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+/// Languages fask has messages in, English first since the messages are written in it
+const LOCALES: [&str; 3] = ["en", "de", "fr"];
+
+/// Message catalog: English text, German, French. English is the key, gettext-style, so an
+/// untranslated message (or locale) prints as written in the code.
+const CATALOG: &[(&str, &str, &str)] = &[
+    (
+        "Searching for '{}' in current files...",
+        "Suche nach '{}' in den aktuellen Dateien...",
+        "Recherche de '{}' dans les fichiers actuels...",
+    ),
+    (
+        "Searching for '{}' in lines added since {}...",
+        "Suche nach '{}' in Zeilen, die seit {} hinzugefügt wurden...",
+        "Recherche de '{}' dans les lignes ajoutées depuis le {}...",
+    ),
+    (
+        "No matches found.",
+        "Keine Treffer gefunden.",
+        "Aucun résultat.",
+    ),
+    (
+        "Found {} match(es):",
+        "{} Treffer gefunden:",
+        "{} résultat(s) trouvé(s) :",
+    ),
+    (
+        "Found {} match(es) in commit messages:",
+        "{} Treffer in Commit-Nachrichten gefunden:",
+        "{} résultat(s) trouvé(s) dans les messages de commit :",
+    ),
+    (
+        "No '{}' found in lines added since {} (lines may have been removed).",
+        "Kein '{}' in Zeilen, die seit {} hinzugefügt wurden (die Zeilen wurden evtl. entfernt).",
+        "Aucun '{}' dans les lignes ajoutées depuis le {} (elles ont peut-être été supprimées).",
+    ),
+    (
+        "{} snoozed match(es) hidden (use --show-snoozed).",
+        "{} zurückgestellte(r) Treffer ausgeblendet (--show-snoozed zeigt sie).",
+        "{} résultat(s) reporté(s) masqué(s) (--show-snoozed pour les voir).",
+    ),
+    (
+        "Skipped {} file(s) that couldn't be searched (-v for details).",
+        "{} Datei(en) übersprungen, die nicht durchsucht werden konnten (-v für Details).",
+        "{} fichier(s) ignoré(s) qui n'ont pas pu être parcourus (-v pour les détails).",
+    ),
+    (
+        "Skipped {} file(s) that couldn't be searched:",
+        "{} Datei(en) übersprungen, die nicht durchsucht werden konnten:",
+        "{} fichier(s) ignoré(s) qui n'ont pas pu être parcourus :",
+    ),
+    (
+        "Skipped {} minified file(s) (--include-minified to search them).",
+        "{} minifizierte Datei(en) übersprungen (--include-minified durchsucht sie).",
+        "{} fichier(s) minifié(s) ignoré(s) (--include-minified pour les parcourir).",
+    ),
+    (
+        "Skipped {} minified file(s) (--include-minified to search them, -v for details).",
+        "{} minifizierte Datei(en) übersprungen (--include-minified durchsucht sie, -v für Details).",
+        "{} fichier(s) minifié(s) ignoré(s) (--include-minified pour les parcourir, -v pour les détails).",
+    ),
+    (
+        "No '{}' matches '{}': give an ID as `fask triage` shows it, or file:line",
+        "Kein '{}' passt zu '{}': Gib eine ID an, wie `fask triage` sie zeigt, oder Datei:Zeile",
+        "Aucun '{}' ne correspond à '{}' : donnez un ID tel que `fask triage` l'affiche, ou fichier:ligne",
+    ),
+    (
+        "git ls-files failed. Is this a git repository?",
+        "git ls-files ist fehlgeschlagen. Ist das ein Git-Repository?",
+        "git ls-files a échoué. Est-ce un dépôt git ?",
+    ),
+    (
+        "git log failed. Is this a git repository?",
+        "git log ist fehlgeschlagen. Ist das ein Git-Repository?",
+        "git log a échoué. Est-ce un dépôt git ?",
+    ),
+    (
+        "No results saved yet: run `fask current` first",
+        "Noch keine Ergebnisse gespeichert: Führe zuerst `fask current` aus",
+        "Aucun résultat enregistré : lancez d'abord `fask current`",
+    ),
+    (
+        "Bookmarked {} ({}:{}).",
+        "{} ({}:{}) gemerkt.",
+        "{} ({}:{}) ajouté aux favoris.",
+    ),
+    (
+        "Removed bookmark {}.",
+        "Lesezeichen {} entfernt.",
+        "Favori {} retiré.",
+    ),
+    (
+        "Removed bookmark {} ({}:{}).",
+        "Lesezeichen {} ({}:{}) entfernt.",
+        "Favori {} ({}:{}) retiré.",
+    ),
+    (
+        "No bookmarks. Pin a TODO with `fask bookmark <id>`.",
+        "Keine Lesezeichen. Merke dir ein TODO mit `fask bookmark <id>`.",
+        "Aucun favori. Épinglez un TODO avec `fask bookmark <id>`.",
+    ),
+    (
+        "{} has no labels.",
+        "{} hat keine Labels.",
+        "{} n'a aucune étiquette.",
+    ),
+    (
+        "{} is labeled {}.",
+        "{} hat die Labels {}.",
+        "{} a les étiquettes {}.",
+    ),
+    ("Failed to read mapping file: {}", "Zuordnungsdatei konnte nicht gelesen werden: {}", "Impossible de lire le fichier de correspondance : {}"),
+    ("{}:{}: expected \"file<TAB>line<TAB>issue\"", "{}:{}: \"Datei<TAB>Zeile<TAB>Issue\" erwartet", "{}:{} : \"fichier<TAB>ligne<TAB>ticket\" attendu"),
+    ("{}:{}: invalid line number", "{}:{}: ungültige Zeilennummer", "{}:{} : numéro de ligne invalide"),
+    ("Failed to read file: {}", "Datei konnte nicht gelesen werden: {}", "Impossible de lire le fichier : {}"),
+    ("{}:{}: no such line, skipping", "{}:{}: Zeile existiert nicht, übersprungen", "{}:{} : ligne inexistante, ignorée"),
+    ("{}:{}: no unannotated '{}' on this line, skipping", "{}:{}: kein '{}' ohne Annotation in dieser Zeile, übersprungen", "{}:{} : aucun '{}' non annoté sur cette ligne, ignorée"),
+    ("Failed to write file: {}", "Datei konnte nicht geschrieben werden: {}", "Impossible d'écrire le fichier : {}"),
+    ("Annotated {} '{}'(s) in {} file(s).", "{} '{}' in {} Datei(en) annotiert.", "{} '{}' annoté(s) dans {} fichier(s)."),
+    ("Would annotate {} '{}'(s) in {} file(s).", "Würde {} '{}' in {} Datei(en) annotieren.", "{} '{}' seraient annotés dans {} fichier(s)."),
+    ("Failed to execute git commit", "git commit konnte nicht ausgeführt werden", "Impossible d'exécuter git commit"),
+    ("git commit failed", "git commit ist fehlgeschlagen", "git commit a échoué"),
+    ("can't create {}: {}", "{} kann nicht angelegt werden: {}", "impossible de créer {} : {}"),
+    ("{}:{}: already owned by {}, skipping", "{}:{}: gehört schon {}, übersprungen", "{}:{} : appartient déjà à {}, ignoré"),
+    ("{}:{}: no one to suggest, skipping", "{}:{}: niemand vorzuschlagen, übersprungen", "{}:{} : personne à suggérer, ignoré"),
+    ("{}:{}: no '{}' to tag on this line, skipping", "{}:{}: kein '{}' zum Markieren in dieser Zeile, übersprungen", "{}:{} : aucun '{}' à marquer sur cette ligne, ignoré"),
+    ("\nAssigned {} '{}'(s) in {} file(s).", "\n{} '{}' in {} Datei(en) zugewiesen.", "\n{} '{}' attribué(s) dans {} fichier(s)."),
+    ("\nWould assign {} '{}'(s) in {} file(s).", "\nWürde {} '{}' in {} Datei(en) zuweisen.", "\n{} '{}' seraient attribués dans {} fichier(s)."),
+    ("Unknown revision '{}'", "Unbekannte Revision '{}'", "Révision inconnue '{}'"),
+    ("No branches match '{}'", "Keine Branches passen zu '{}'", "Aucune branche ne correspond à '{}'"),
+    ("Failed to execute git grep", "git grep konnte nicht ausgeführt werden", "Impossible d'exécuter git grep"),
+    ("git grep failed: {}", "git grep ist fehlgeschlagen: {}", "git grep a échoué : {}"),
+    ("Searching for '{}' at {} ({})...\n", "Suche nach '{}' in {} ({})...\n", "Recherche de '{}' dans {} ({})...\n"),
+    ("No '{}' found at {}.", "Kein '{}' in {} gefunden.", "Aucun '{}' trouvé dans {}."),
+    ("\nFound {} '{}'(s) in {} file(s) at {}.", "\n{} '{}' in {} Datei(en) in {} gefunden.", "\n{} '{}' trouvé(s) dans {} fichier(s) dans {}."),
+    ("Searching for '{}' on {} branch(es) matching {}...\n", "Suche nach '{}' in {} Branch(es), die zu {} passen...\n", "Recherche de '{}' dans {} branche(s) correspondant à {}...\n"),
+    ("BRANCH", "BRANCH", "BRANCHE"),
+    ("TODOS", "TODOS", "TODOS"),
+    ("FILES", "DATEIEN", "FICHIERS"),
+    ("Set AZURE_DEVOPS_PAT to talk to Azure DevOps", "Setze AZURE_DEVOPS_PAT, um mit Azure DevOps zu sprechen", "Définissez AZURE_DEVOPS_PAT pour utiliser Azure DevOps"),
+    ("Remote 'origin' is not an Azure Repos URL. Pass --azure-org and --azure-project.", "Remote 'origin' ist keine Azure-Repos-URL. Gib --azure-org und --azure-project an.", "Le remote 'origin' n'est pas une URL Azure Repos. Passez --azure-org et --azure-project."),
+    ("Failed to create work item", "Work Item konnte nicht angelegt werden", "Impossible de créer l'élément de travail"),
+    ("Failed to parse work item", "Work Item konnte nicht gelesen werden", "Impossible d'analyser l'élément de travail"),
+    ("Failed to fetch work item", "Work Item konnte nicht abgerufen werden", "Impossible de récupérer l'élément de travail"),
+    ("No commits between --from and --to", "Keine Commits zwischen --from und --to", "Aucun commit entre --from et --to"),
+    ("Present throughout the {} commit(s) searched, since {}", "In allen {} durchsuchten Commit(s) vorhanden, seit {}", "Présent dans les {} commit(s) parcourus, depuis {}"),
+    ("Not found at either end of the {} commit(s) searched. If it was removed, pass --from a commit that has it.", "An keinem Ende der {} durchsuchten Commit(s) gefunden. Wurde es entfernt, gib mit --from einen Commit an, der es enthält.", "Introuvable aux deux extrémités des {} commit(s) parcourus. S'il a été supprimé, passez à --from un commit qui le contient."),
+    ("\nChecked {} of {} commit(s).", "\n{} von {} Commit(s) geprüft.", "\n{} commit(s) sur {} vérifiés."),
+    ("today", "heute", "aujourd'hui"),
+    ("1 day ago", "vor 1 Tag", "il y a 1 jour"),
+    ("{} days ago", "vor {} Tagen", "il y a {} jours"),
+    ("{} weeks ago", "vor {} Wochen", "il y a {} semaines"),
+    ("{} months ago", "vor {} Monaten", "il y a {} mois"),
+    ("{} years ago", "vor {} Jahren", "il y a {} ans"),
+    ("{} ({}:{}) is not bookmarked", "{} ({}:{}) hat kein Lesezeichen", "{} ({}:{}) n'est pas en favori"),
+    ("Bookmarks ({}):", "Lesezeichen ({}):", "Favoris ({}) :"),
+    ("(since {})", "(seit {})", "(depuis le {})"),
+    ("{}: {} (no longer found)", "{}: {} (nicht mehr gefunden)", "{} : {} (introuvable)"),
+    ("\n{} bookmark(s) point at TODOs that were resolved or edited (`fask state prune` drops them).", "\n{} Lesezeichen zeigen auf erledigte oder geänderte TODOs (`fask state prune` entfernt sie).", "\n{} favori(s) pointent vers des TODO résolus ou modifiés (`fask state prune` les supprime)."),
+    ("Can't find a merge base with '{}'", "Keine Merge-Basis mit '{}' gefunden", "Aucune base de fusion avec '{}'"),
+    ("\n{} '{}'(s) at {} ({}), {} now: net {}.", "\n{} '{}' in {} ({}), jetzt {}: netto {}.", "\n{} '{}' dans {} ({}), {} maintenant : net {}."),
+    ("Failed to read report: {}", "Bericht konnte nicht gelesen werden: {}", "Impossible de lire le rapport : {}"),
+    ("Failed to parse report: {}", "Bericht konnte nicht geparst werden: {}", "Impossible d'analyser le rapport : {}"),
+    ("Failed to parse {}:{}", "{}:{} konnte nicht geparst werden", "Impossible d'analyser {}:{}"),
+    ("New ({}):", "Neu ({}):", "Nouveaux ({}) :"),
+    ("Resolved ({}):", "Erledigt ({}):", "Résolus ({}) :"),
+    ("Moved ({}):", "Verschoben ({}):", "Déplacés ({}) :"),
+    ("{} new, {} resolved, {} moved ({} before, {} after).", "{} neu, {} erledigt, {} verschoben ({} vorher, {} nachher).", "{} nouveau(x), {} résolu(s), {} déplacé(s) ({} avant, {} après)."),
+    ("Failed to read config: {}", "Konfiguration konnte nicht gelesen werden: {}", "Impossible de lire la configuration : {}"),
+    ("Failed to parse config: {}", "Konfiguration konnte nicht geparst werden: {}", "Impossible d'analyser la configuration : {}"),
+    (" (known: {})", " (bekannt: {})", " (connues : {})"),
+    ("; did you mean '{}'?", "; meintest du '{}'?", " ; vouliez-vous dire '{}' ?"),
+    ("unknown key '{}' in {}{}", "unbekannter Schlüssel '{}' in {}{}", "clé inconnue '{}' dans {}{}"),
+    ("{} must be {}", "{} muss {} sein", "{} doit être {}"),
+    ("{} must be a string", "{} muss ein String sein", "{} doit être une chaîne"),
+    ("type '{}' must be a list of globs", "Typ '{}' muss eine Liste von Globs sein", "le type '{}' doit être une liste de globs"),
+    ("type '{}' has no globs", "Typ '{}' hat keine Globs", "le type '{}' n'a aucun glob"),
+    ("invalid glob '{}': {}", "ungültiger Glob '{}': {}", "glob invalide '{}' : {}"),
+    ("rule id '{}' is taken by a built-in rule", "Regel-ID '{}' gehört einer eingebauten Regel", "l'id de règle '{}' est prise par une règle intégrée"),
+    ("rule id '{}' is used by an earlier rule", "Regel-ID '{}' wird schon von einer früheren Regel benutzt", "l'id de règle '{}' est déjà utilisée par une règle précédente"),
+    ("a [[lint.rules]] entry has no id", "ein [[lint.rules]]-Eintrag hat keine ID", "une entrée [[lint.rules]] n'a pas d'id"),
+    ("invalid regex in {}: {}", "ungültige Regex in {}: {}", "regex invalide dans {} : {}"),
+    ("invalid where condition: {}", "ungültige where-Bedingung: {}", "condition where invalide : {}"),
+    ("rule has no require, forbid or where, so it never flags anything", "Regel hat weder require, forbid noch where und meldet daher nie etwas", "la règle n'a ni require, ni forbid, ni where, elle ne signale donc jamais rien"),
+    ("{} has no effect while {} is disabled", "{} wirkt nicht, solange {} ausgeschaltet ist", "{} n'a aucun effet tant que {} est désactivée"),
+    ("exclude '{}' drops every file type '{}' picks", "exclude '{}' verwirft jede Datei, die Typ '{}' auswählt", "exclude '{}' écarte tous les fichiers que le type '{}' retient"),
+    ("No config at {}; the defaults apply.", "Keine Konfiguration in {}; es gelten die Voreinstellungen.", "Aucune configuration dans {} ; les valeurs par défaut s'appliquent."),
+    ("{} is valid.", "{} ist gültig.", "{} est valide."),
+    ("\n{} error(s), {} warning(s).", "\n{} Fehler, {} Warnung(en).", "\n{} erreur(s), {} avertissement(s)."),
+    ("Failed to listen on {}", "Konnte nicht auf {} lauschen", "Impossible d'écouter sur {}"),
+    ("Failed to resolve {}", "{} konnte nicht aufgelöst werden", "Impossible de résoudre {}"),
+    ("Failed to start watching for changes", "Überwachung von Änderungen konnte nicht gestartet werden", "Impossible de surveiller les modifications"),
+    ("Failed to watch {}", "{} konnte nicht überwacht werden", "Impossible de surveiller {}"),
+    ("Failed to set up the shutdown handler", "Handler zum Beenden konnte nicht eingerichtet werden", "Impossible d'installer le gestionnaire d'arrêt"),
+    ("Watching {} and serving TODO data on http://{}/api/todos", "Überwache {} und stelle TODO-Daten auf http://{}/api/todos bereit", "Surveillance de {}, données TODO servies sur http://{}/api/todos"),
+    ("Request failed: {}", "Anfrage fehlgeschlagen: {}", "La requête a échoué : {}"),
+    ("Refresh failed: {}", "Aktualisierung fehlgeschlagen: {}", "L'actualisation a échoué : {}"),
+    ("Failed to create {}", "{} konnte nicht angelegt werden", "Impossible de créer {}"),
+    ("Failed to write {}", "{} konnte nicht geschrieben werden", "Impossible d'écrire {}"),
+    ("and {} more", "und {} weitere", "et {} de plus"),
+    ("{} overdue '{}'(s)", "{} überfällige(s) '{}'", "{} '{}' en retard"),
+    ("Failed to show desktop notification", "Desktop-Benachrichtigung konnte nicht angezeigt werden", "Impossible d'afficher la notification"),
+    ("No '{}'s with deadlines.", "Keine '{}' mit Fristen.", "Aucun '{}' avec échéance."),
+    ("{} day(s) overdue", "{} Tag(e) überfällig", "{} jour(s) de retard"),
+    ("due today", "heute fällig", "échéance aujourd'hui"),
+    ("due in {} day(s)", "fällig in {} Tag(en)", "échéance dans {} jour(s)"),
+    ("\n{} '{}'(s) with deadlines, {} overdue.", "\n{} '{}' mit Fristen, {} überfällig.", "\n{} '{}' avec échéance, {} en retard."),
+    ("No duplicate '{}'s found.", "Keine doppelten '{}' gefunden.", "Aucun '{}' en double."),
+    ("{} occurrences in {} file(s)", "{} Vorkommen in {} Datei(en)", "{} occurrences dans {} fichier(s)"),
+    ("\n{} group(s) of duplicate '{}'s.", "\n{} Gruppe(n) doppelter '{}'.", "\n{} groupe(s) de '{}' en double."),
+    ("  owner:    {} (tag)", "  Besitzer: {} (Tag)", "  responsable : {} (tag)"),
+    ("  owner:    none, suggested {} (fask assign --auto)", "  Besitzer: keiner, vorgeschlagen {} (fask assign --auto)", "  responsable : aucun, suggéré {} (fask assign --auto)"),
+    ("  owner:    none", "  Besitzer: keiner", "  responsable : aucun"),
+    ("  touched:  not committed yet", "  geändert: noch nicht committet", "  modifié :     pas encore commité"),
+    ("  touched:  {} by {}", "  geändert: {} von {}", "  modifié :     {} par {}"),
+    ("  metadata: {}", "  Metadaten: {}", "  métadonnées : {}"),
+    ("  triage:   {} on {}", "  Triage:   {} am {}", "  tri :         {} le {}"),
+    ("\nNot committed yet, so there is no history.", "\nNoch nicht committet, daher gibt es keine Historie.", "\nPas encore commité, il n'y a donc pas d'historique."),
+    ("{} days, ", "{} Tage, ", "{} jours, "),
+    ("  age:      {}added {} in {}", "  Alter:    {}hinzugefügt {} in {}", "  âge :         {}ajouté {} dans {}"),
+    ("\nHistory ({} commit(s), oldest first):", "\nHistorie ({} Commit(s), älteste zuerst):", "\nHistorique ({} commit(s), du plus ancien) :"),
+    ("No '{}'s to file.", "Keine '{}' einzureichen.", "Aucun '{}' à créer."),
+    ("Couldn't reach the tracker:", "Tracker nicht erreichbar:", "Tracker injoignable :"),
+    ("{}:{}: filed as {}", "{}:{}: eingereicht als {}", "{}:{} : créé sous {}"),
+    ("\nFiled {} issue(s).", "\n{} Issue(s) eingereicht.", "\n{} ticket(s) créé(s)."),
+    ("Queued {} for later; `fask flush` files them.", "{} für später eingereiht; `fask flush` reicht sie ein.", "{} mis en file d'attente ; `fask flush` les crée."),
+    ("No '{}'s reference this tracker's issues.", "Keine '{}' verweisen auf Issues dieses Trackers.", "Aucun '{}' ne référence les tickets de ce tracker."),
+    ("\n{} '{}'(s) referencing {} issue(s): {}.", "\n{} '{}' verweisen auf {} Issue(s): {}.", "\n{} '{}' référencent {} ticket(s) : {}."),
+    ("All {} issue reference(s) in '{}'s point at open issues.", "Alle {} Issue-Verweise in '{}' zeigen auf offene Issues.", "Les {} référence(s) de ticket dans les '{}' pointent vers des tickets ouverts."),
+    ("\n{} of {} issue reference(s) point at closed issues.", "\n{} von {} Issue-Verweis(en) zeigen auf geschlossene Issues.", "\n{} référence(s) de ticket sur {} pointent vers des tickets fermés."),
+    ("Set GITHUB_TOKEN to post review comments", "Setze GITHUB_TOKEN, um Review-Kommentare zu posten", "Définissez GITHUB_TOKEN pour publier des commentaires de revue"),
+    ("Remote 'origin' is not a GitHub repository: {}", "Remote 'origin' ist kein GitHub-Repository: {}", "Le remote 'origin' n'est pas un dépôt GitHub : {}"),
+    ("Failed to list pull requests", "Pull Requests konnten nicht aufgelistet werden", "Impossible de lister les pull requests"),
+    ("No open pull request for branch '{}'", "Kein offener Pull Request für Branch '{}'", "Aucune pull request ouverte pour la branche '{}'"),
+    ("Failed to list review comments", "Review-Kommentare konnten nicht aufgelistet werden", "Impossible de lister les commentaires de revue"),
+    ("Failed to comment on {}:{}", "{}:{} konnte nicht kommentiert werden", "Impossible de commenter {}:{}"),
+    ("HEAD ({}) is not the head of {}. Push the branch first.", "HEAD ({}) ist nicht der Stand von {}. Pushe zuerst den Branch.", "HEAD ({}) n'est pas la tête de {}. Poussez d'abord la branche."),
+    ("Posted {} review comment(s) on {} ({} already there).", "{} Review-Kommentar(e) auf {} gepostet ({} schon vorhanden).", "{} commentaire(s) de revue publié(s) sur {} ({} déjà présents)."),
+    ("Set GITLAB_TOKEN to post merge request discussions", "Setze GITLAB_TOKEN, um Diskussionen in Merge Requests zu eröffnen", "Définissez GITLAB_TOKEN pour ouvrir des discussions de merge request"),
+    ("Can't parse remote 'origin': {}", "Remote 'origin' kann nicht gelesen werden: {}", "Impossible d'analyser le remote 'origin' : {}"),
+    ("Failed to list merge requests", "Merge Requests konnten nicht aufgelistet werden", "Impossible de lister les merge requests"),
+    ("No open merge request for branch '{}'", "Kein offener Merge Request für Branch '{}'", "Aucune merge request ouverte pour la branche '{}'"),
+    ("Failed to fetch merge request", "Merge Request konnte nicht abgerufen werden", "Impossible de récupérer la merge request"),
+    ("Failed to parse merge request", "Merge Request konnte nicht gelesen werden", "Impossible d'analyser la merge request"),
+    ("Failed to list discussions", "Diskussionen konnten nicht aufgelistet werden", "Impossible de lister les discussions"),
+    ("Failed to update discussion", "Diskussion konnte nicht aktualisiert werden", "Impossible de mettre à jour la discussion"),
+    ("Merge request has no diff yet", "Merge Request hat noch keinen Diff", "La merge request n'a pas encore de diff"),
+    ("Opened {} discussion(s) on {} ({} resolved, {} reopened).", "{} Diskussion(en) auf {} eröffnet ({} erledigt, {} wieder geöffnet).", "{} discussion(s) ouverte(s) sur {} ({} résolue(s), {} rouverte(s))."),
+    ("No additions found.", "Keine Hinzufügungen gefunden.", "Aucun ajout trouvé."),
+    ("Wrote {} deadline(s) to {}.", "{} Frist(en) nach {} geschrieben.", "{} échéance(s) écrite(s) dans {}."),
+    ("Failed to read results: {}", "Ergebnisse konnten nicht gelesen werden: {}", "Impossible de lire les résultats : {}"),
+    ("Failed to parse results: {}", "Ergebnisse konnten nicht geparst werden: {}", "Impossible d'analyser les résultats : {}"),
+    ("No match #{}: the last run (`fask last`) had {} match(es)", "Kein Treffer #{}: der letzte Lauf (`fask last`) hatte {} Treffer", "Pas de résultat n°{} : la dernière exécution (`fask last`) en avait {}"),
+    ("Failed to execute git lfs", "git lfs konnte nicht ausgeführt werden", "Impossible d'exécuter git lfs"),
+    ("git lfs smudge failed: {}", "git lfs smudge ist fehlgeschlagen: {}", "git lfs smudge a échoué : {}"),
+    ("Set LINEAR_API_KEY to talk to Linear", "Setze LINEAR_API_KEY, um mit Linear zu sprechen", "Définissez LINEAR_API_KEY pour utiliser Linear"),
+    ("Linear API request failed", "Anfrage an die Linear-API fehlgeschlagen", "La requête à l'API Linear a échoué"),
+    ("Failed to parse Linear API response", "Antwort der Linear-API konnte nicht gelesen werden", "Impossible d'analyser la réponse de l'API Linear"),
+    ("Linear API error: {}", "Fehler der Linear-API: {}", "Erreur de l'API Linear : {}"),
+    ("No Linear {} named '{}'", "Kein Linear-{} namens '{}'", "Aucun {} Linear nommé '{}'"),
+    ("Pass --linear-team to create Linear issues", "Gib --linear-team an, um Linear-Issues anzulegen", "Passez --linear-team pour créer des tickets Linear"),
+    ("Linear did not return the new issue", "Linear hat das neue Issue nicht zurückgegeben", "Linear n'a pas renvoyé le nouveau ticket"),
+    ("Unknown lint rule '{}'. Known rules: {}", "Unbekannte Lint-Regel '{}'. Bekannte Regeln: {}", "Règle de lint inconnue '{}'. Règles connues : {}"),
+    ("No lint violations.", "Keine Lint-Verstöße.", "Aucune violation de lint."),
+    ("\n{} lint violation(s).", "\n{} Lint-Verstoß/-Verstöße.", "\n{} violation(s) de lint."),
+    ("Failed to create log file: {}", "Logdatei konnte nicht angelegt werden: {}", "Impossible de créer le fichier journal : {}"),
+    ("Bad Content-Length", "Ungültige Content-Length", "Content-Length invalide"),
+    ("Message without a Content-Length header", "Nachricht ohne Content-Length-Header", "Message sans en-tête Content-Length"),
+    ("Message isn't JSON", "Nachricht ist kein JSON", "Le message n'est pas du JSON"),
+    ("Invalid --rg-args", "Ungültige --rg-args", "--rg-args invalides"),
+    ("Invalid --git-args", "Ungültige --git-args", "--git-args invalides"),
+    ("No '{}' additions found.", "Keine hinzugefügten '{}' gefunden.", "Aucun ajout de '{}' trouvé."),
+    ("Failed to enter {}", "Konnte nicht nach {} wechseln", "Impossible d'entrer dans {}"),
+    ("Name the TODOs to assign to {}, or use --auto", "Nenne die TODOs, die {} zugewiesen werden sollen, oder nutze --auto", "Indiquez les TODO à attribuer à {}, ou utilisez --auto"),
+    ("not committed", "nicht committet", "non commité"),
+    ("(not committed yet)", "(noch nicht committet)", "(pas encore commité)"),
+    ("(suggested owner: {})", "(vorgeschlagener Besitzer: {})", "(responsable suggéré : {})"),
+    ("(in a string)", "(in einem String)", "(dans une chaîne)"),
+    ("(cell {}, line {})", "(Zelle {}, Zeile {})", "(cellule {}, ligne {})"),
+    ("(labels: {})", "(Labels: {})", "(labels : {})"),
+    ("(worktree: {})", "(Worktree: {})", "(worktree : {})"),
+    ("{}bookmarked", "{}mit Lesezeichen", "{}en favori"),
+    ("Trailing backslash", "Backslash am Ende", "Barre oblique inverse finale"),
+    ("Unclosed {} quote", "Nicht geschlossenes {}-Anführungszeichen", "Guillemet {} non fermé"),
+    ("Failed to execute git ls-files", "git ls-files konnte nicht ausgeführt werden", "Impossible d'exécuter git ls-files"),
+    ("Failed to execute git log", "git log konnte nicht ausgeführt werden", "Impossible d'exécuter git log"),
+    ("Failed to write to git log", "Konnte nicht an git log schreiben", "Impossible d'écrire vers git log"),
+    ("Failed to read git log output", "Ausgabe von git log konnte nicht gelesen werden", "Impossible de lire la sortie de git log"),
+    ("Failed to wait for git log", "Warten auf git log fehlgeschlagen", "Impossible d'attendre git log"),
+    ("{}:{}: {} (added {} in {}){}", "{}:{}: {} (hinzugefügt {} in {}){}", "{}:{} : {} (ajouté {} dans {}){}"),
+    ("(not materialized)", "(nicht materialisiert)", "(non matérialisé)"),
+    ("{}:{}:{}: {} (added {} in {})", "{}:{}:{}: {} (hinzugefügt {} in {})", "{}:{}:{} : {} (ajouté {} dans {})"),
+    ("{} (added {} in {})", "{} (hinzugefügt {} in {})", "{} (ajouté {} dans {})"),
+    ("{} (message, {}): {}", "{} (Nachricht, {}): {}", "{} (message, {}) : {}"),
+    ("Range '{}' isn't of the form A..B", "Bereich '{}' hat nicht die Form A..B", "La plage '{}' n'est pas de la forme A..B"),
+    ("One of --date, --since-ref or --range is required", "Eine der Optionen --date, --since-ref oder --range ist nötig", "L'une des options --date, --since-ref ou --range est requise"),
+    ("No '{}' additions found since {}.", "Keine hinzugefügten '{}' seit {} gefunden.", "Aucun ajout de '{}' depuis le {}."),
+    ("{} ({} match(es))", "{} ({} Treffer)", "{} ({} résultat(s))"),
+    ("missing key in '{}'", "fehlender Schlüssel in '{}'", "clé manquante dans '{}'"),
+    ("Serving metrics on http://{}/metrics", "Stelle Metriken auf http://{}/metrics bereit", "Métriques servies sur http://{}/metrics"),
+    ("Failed to read notebook: {}", "Notebook konnte nicht gelesen werden: {}", "Impossible de lire le notebook : {}"),
+    ("Failed to parse notebook: {}", "Notebook konnte nicht geparst werden: {}", "Impossible d'analyser le notebook : {}"),
+    ("Failed to query Notion database", "Notion-Datenbank konnte nicht abgefragt werden", "Impossible d'interroger la base Notion"),
+    ("Set NOTION_TOKEN to export to Notion", "Setze NOTION_TOKEN, um nach Notion zu exportieren", "Définissez NOTION_TOKEN pour exporter vers Notion"),
+    ("Pass --notion-database or set NOTION_DATABASE_ID", "Gib --notion-database an oder setze NOTION_DATABASE_ID", "Passez --notion-database ou définissez NOTION_DATABASE_ID"),
+    ("Failed to update Notion page for {}", "Notion-Seite für {} konnte nicht aktualisiert werden", "Impossible de mettre à jour la page Notion de {}"),
+    ("Failed to create Notion page for {}", "Notion-Seite für {} konnte nicht angelegt werden", "Impossible de créer la page Notion de {}"),
+    ("Exported {} TODO(s) to Notion: {} created, {} updated.", "{} TODO(s) nach Notion exportiert: {} angelegt, {} aktualisiert.", "{} TODO exporté(s) vers Notion : {} créé(s), {} mis à jour."),
+    ("Failed to write note: {}", "Notiz konnte nicht geschrieben werden: {}", "Impossible d'écrire la note : {}"),
+    ("Wrote {} note(s) with {} task(s) to {}.", "{} Notiz(en) mit {} Aufgabe(n) nach {} geschrieben.", "{} note(s) avec {} tâche(s) écrite(s) dans {}."),
+    ("Failed to execute git cat-file", "git cat-file konnte nicht ausgeführt werden", "Impossible d'exécuter git cat-file"),
+    ("Failed to read git cat-file output", "Ausgabe von git cat-file konnte nicht gelesen werden", "Impossible de lire la sortie de git cat-file"),
+    ("git cat-file is closed", "git cat-file ist beendet", "git cat-file est fermé"),
+    ("Bad git cat-file header: {}", "Ungültiger git-cat-file-Header: {}", "En-tête git cat-file invalide : {}"),
+    ("Failed to execute git", "git konnte nicht ausgeführt werden", "Impossible d'exécuter git"),
+    ("git {} failed: {}", "git {} ist fehlgeschlagen: {}", "git {} a échoué : {}"),
+    ("Can't find a merge base with '{}'. Try --base.", "Keine Merge-Basis mit '{}' gefunden. Versuche --base.", "Aucune base de fusion avec '{}'. Essayez --base."),
+    ("Searching for '{}' added since {} ({})...\n", "Suche nach '{}', hinzugefügt seit {} ({})...\n", "Recherche de '{}' ajouté depuis {} ({})...\n"),
+    ("No '{}' added in {} commit(s).", "Kein '{}' in {} Commit(s) hinzugefügt.", "Aucun '{}' ajouté dans {} commit(s)."),
+    ("\nFound {} '{}'(s) added in {} commit(s).", "\n{} hinzugefügte '{}' in {} Commit(s) gefunden.", "\n{} '{}' ajouté(s) dans {} commit(s)."),
+    ("{} {}\nQueued the comments; `fask flush` posts them.", "{} {}\nKommentare eingereiht; `fask flush` postet sie.", "{} {}\nCommentaires mis en file d'attente ; `fask flush` les publie."),
+    ("Couldn't post the comments:", "Kommentare konnten nicht gepostet werden:", "Impossible de publier les commentaires :"),
+    ("No profile '{}' in .fask.toml (defined: {})", "Kein Profil '{}' in .fask.toml (definiert: {})", "Aucun profil '{}' dans .fask.toml (définis : {})"),
+    ("bad age '{}' (like 90d, 12w, 6m or 1y)", "ungültiges Alter '{}' (etwa 90d, 12w, 6m oder 1y)", "âge invalide '{}' (comme 90d, 12w, 6m ou 1y)"),
+    ("missing closing '\"'", "schließendes '\"' fehlt", "'\"' fermant manquant"),
+    ("bad age unit in '{}' (d, w, m or y)", "ungültige Alterseinheit in '{}' (d, w, m oder y)", "unité d'âge invalide dans '{}' (d, w, m ou y)"),
+    ("missing value in '{}'", "fehlender Wert in '{}'", "valeur manquante dans '{}'"),
+    ("bad regex in '{}': {}", "ungültige Regex in '{}': {}", "regex invalide dans '{}' : {}"),
+    ("missing ')'", "fehlende ')'", "')' manquante"),
+    ("unexpected ')'", "unerwartete ')'", "')' inattendue"),
+    ("expected a term before '{}'", "Ausdruck vor '{}' erwartet", "terme attendu avant '{}'"),
+    ("expected a term at the end", "Ausdruck am Ende erwartet", "terme attendu à la fin"),
+    ("empty query", "leere Abfrage", "requête vide"),
+    ("unexpected '{}'", "unerwartetes '{}'", "'{}' inattendu"),
+    ("file an issue for {}", "Issue für {} einreichen", "créer un ticket pour {}"),
+    ("comment on the pull request ({} TODO(s))", "Pull Request kommentieren ({} TODO(s))", "commenter la pull request ({} TODO)"),
+    ("comment on the merge request ({} TODO(s))", "Merge Request kommentieren ({} TODO(s))", "commenter la merge request ({} TODO)"),
+    ("{}: filed as {}", "{}: eingereicht als {}", "{} : créé sous {}"),
+    ("Failed to read queue: {}", "Warteschlange konnte nicht gelesen werden: {}", "Impossible de lire la file d'attente : {}"),
+    ("Failed to parse queue: {}", "Warteschlange konnte nicht geparst werden: {}", "Impossible d'analyser la file d'attente : {}"),
+    ("Failed to remove {}", "{} konnte nicht entfernt werden", "Impossible de supprimer {}"),
+    ("Failed to write queue: {}", "Warteschlange konnte nicht geschrieben werden: {}", "Impossible d'écrire la file d'attente : {}"),
+    ("Nothing queued.", "Nichts eingereiht.", "Rien en file d'attente."),
+    ("{} {}: {} (attempt {}, next after {})", "{} {}: {} (Versuch {}, nächster nach {})", "{} {} : {} (tentative {}, prochaine après {})"),
+    ("Still failing:", "Scheitert weiterhin:", "Échoue toujours :"),
+    ("Dropped:", "Verworfen:", "Abandonné :"),
+    ("\n{} done, {} dropped, {} still queued ({} not due yet; --force retries them now).", "\n{} erledigt, {} verworfen, {} noch eingereiht ({} noch nicht fällig; --force versucht sie jetzt).", "\n{} fait(s), {} abandonné(s), {} encore en file ({} pas encore dus ; --force les retente maintenant)."),
+    ("line {}: {}", "Zeile {}: {}", "ligne {} : {}"),
+    ("--rg-args needs the rg backend, but {} is in use (pick it with --search-backend rg)", "--rg-args braucht das rg-Backend, aber {} ist aktiv (wähle es mit --search-backend rg)", "--rg-args nécessite le backend rg, mais {} est utilisé (choisissez-le avec --search-backend rg)"),
+    ("Invalid pattern '{}'", "Ungültiges Muster '{}'", "Motif invalide '{}'"),
+    ("Failed to read pattern file: {}", "Musterdatei konnte nicht gelesen werden: {}", "Impossible de lire le fichier de motifs : {}"),
+    ("No patterns in {}", "Keine Muster in {}", "Aucun motif dans {}"),
+    ("Invalid pattern on line {} of {} (--fixed-strings takes the lines literally)", "Ungültiges Muster in Zeile {} von {} (--fixed-strings nimmt die Zeilen wörtlich)", "Motif invalide à la ligne {} de {} (--fixed-strings prend les lignes telles quelles)"),
+    ("{} takes no --pattern, so --pattern-file doesn't apply to it", "{} hat kein --pattern, --pattern-file gilt dafür also nicht", "{} n'a pas de --pattern, --pattern-file ne s'y applique donc pas"),
+    ("Give --pattern or --pattern-file, not both", "Entweder --pattern oder --pattern-file angeben, nicht beides", "Indiquez --pattern ou --pattern-file, pas les deux"),
+    ("Failed to read the file list from stdin", "Dateiliste konnte nicht von stdin gelesen werden", "Impossible de lire la liste de fichiers depuis stdin"),
+    ("Failed to read file list: {}", "Dateiliste konnte nicht gelesen werden: {}", "Impossible de lire la liste de fichiers : {}"),
+    ("Invalid glob '{}'", "Ungültiger Glob '{}'", "Glob invalide '{}'"),
+    ("Failed to execute ripgrep. Is 'rg' installed? (--search-backend internal needs nothing)", "ripgrep konnte nicht ausgeführt werden. Ist 'rg' installiert? (--search-backend internal braucht nichts)", "Impossible d'exécuter ripgrep. 'rg' est-il installé ? (--search-backend internal ne nécessite rien)"),
+    ("ripgrep failed: {}", "ripgrep ist fehlgeschlagen: {}", "ripgrep a échoué : {}"),
+    ("Failed to execute ugrep. Is 'ugrep' installed? (--search-backend internal needs nothing)", "ugrep konnte nicht ausgeführt werden. Ist 'ugrep' installiert? (--search-backend internal braucht nichts)", "Impossible d'exécuter ugrep. 'ugrep' est-il installé ? (--search-backend internal ne nécessite rien)"),
+    ("ugrep failed: {}", "ugrep ist fehlgeschlagen: {}", "ugrep a échoué : {}"),
+    ("Serving dashboard on http://{}/", "Stelle Dashboard auf http://{}/ bereit", "Tableau de bord servi sur http://{}/"),
+    ("Serving TODO data on http://{}/api/todos", "Stelle TODO-Daten auf http://{}/api/todos bereit", "Données TODO servies sur http://{}/api/todos"),
+    ("  {}: average line of {} characters", "  {}: durchschnittliche Zeile mit {} Zeichen", "  {} : ligne moyenne de {} caractères"),
+    ("git ls-files failed: {}", "git ls-files ist fehlgeschlagen: {}", "git ls-files a échoué : {}"),
+    ("Failed to execute git diff", "git diff konnte nicht ausgeführt werden", "Impossible d'exécuter git diff"),
+    ("git diff failed. Is this a git repository?", "git diff ist fehlgeschlagen. Ist das ein Git-Repository?", "git diff a échoué. Est-ce un dépôt git ?"),
+    ("No '{}' added in staged changes.", "Kein '{}' in den vorgemerkten Änderungen hinzugefügt.", "Aucun '{}' ajouté dans les modifications indexées."),
+    ("blocked", "blockiert", "bloqué"),
+    ("allowed", "erlaubt", "autorisé"),
+    ("\n{} new '{}'(s) staged{}.", "\n{} neue(s) '{}' vorgemerkt{}.", "\n{} nouveau(x) '{}' indexé(s){}."),
+    ("\n{} new '{}'(s) staged, all with issue references.", "\n{} neue(s) '{}' vorgemerkt, alle mit Issue-Verweis.", "\n{} nouveau(x) '{}' indexé(s), tous avec une référence de ticket."),
+    ("No stashes.", "Keine Stashes.", "Aucun stash."),
+    ("Searching for '{}' in {} stash(es)...\n", "Suche nach '{}' in {} Stash(es)...\n", "Recherche de '{}' dans {} stash(s)...\n"),
+    ("No '{}' found in stashes.", "Kein '{}' in Stashes gefunden.", "Aucun '{}' trouvé dans les stashs."),
+    ("\nFound {} match(es) in stashes.", "\n{} Treffer in Stashes gefunden.", "\n{} résultat(s) trouvé(s) dans les stashs."),
+    ("filed as {}", "eingereicht als {}", "créé sous {}"),
+    ("snoozed until {}", "zurückgestellt bis {}", "reporté jusqu'au {}"),
+    ("accepted", "akzeptiert", "accepté"),
+    ("filed", "eingereicht", "créé"),
+    ("snoozed", "zurückgestellt", "reporté"),
+    ("Failed to read state: {}", "Zustand konnte nicht gelesen werden: {}", "Impossible de lire l'état : {}"),
+    ("Failed to parse state: {}", "Zustand konnte nicht geparst werden: {}", "Impossible d'analyser l'état : {}"),
+    ("Failed to write state: {}", "Zustand konnte nicht geschrieben werden: {}", "Impossible d'écrire l'état : {}"),
+    ("State: {}", "Zustand: {}", "État : {}"),
+    ("Repository: {}\n", "Repository: {}\n", "Dépôt : {}\n"),
+    ("No triage decisions.", "Keine Triage-Entscheidungen.", "Aucune décision de tri."),
+    ("Triage decisions ({}):", "Triage-Entscheidungen ({}):", "Décisions de tri ({}) :"),
+    ("\nBookmarks ({}):", "\nLesezeichen ({}):", "\nFavoris ({}) :"),
+    ("  {}  {}: {} (since {})", "  {}  {}: {} (seit {})", "  {}  {} : {} (depuis le {})"),
+    ("\nLabels ({}):", "\nLabels ({}):", "\nLabels ({}) :"),
+    ("  {}  {}: {} (bookmark)", "  {}  {}: {} (Lesezeichen)", "  {}  {} : {} (favori)"),
+    ("  {}  {}: {} (labels)", "  {}  {}: {} (Labels)", "  {}  {} : {} (labels)"),
+    ("Would prune {} entr(ies).", "Würde {} Eintrag/Einträge entfernen.", "{} entrée(s) seraient supprimées."),
+    ("Pruned {} entr(ies).", "{} Eintrag/Einträge entfernt.", "{} entrée(s) supprimée(s)."),
+    ("RESOLVED", "ERLEDIGT", "RÉSOLUS"),
+    ("all", "alle", "tous"),
+    ("COUNT", "ANZ.", "NB"),
+    ("MEDIAN", "MEDIAN", "MÉD."),
+    ("MAX", "MAX", "MAX"),
+    ("\n{}  (days open)", "\n{}  (Tage offen)", "\n{}  (jours ouverts)"),
+    ("by directory", "nach Verzeichnis", "par répertoire"),
+    ("by author", "nach Autor", "par auteur"),
+    ("AUTHOR", "AUTOR", "AUTEUR"),
+    ("ADDED", "NEU", "AJOUT"),
+    ("NET", "NETTO", "NET"),
+    ("{} '{}'(s) added, {} resolved, {} open.", "{} '{}' hinzugefügt, {} erledigt, {} offen.", "{} '{}' ajouté(s), {} résolu(s), {} ouvert(s)."),
+    ("No resolved '{}'s to time.", "Keine erledigten '{}' zum Auswerten.", "Aucun '{}' résolu à mesurer."),
+    ("Ignoring unknown theme '{}' in .fask.toml", "Ignoriere unbekanntes Theme '{}' in .fask.toml", "Thème inconnu '{}' ignoré dans .fask.toml"),
+    ("Ignoring unknown [theme] key '{}' in .fask.toml", "Ignoriere unbekannten [theme]-Schlüssel '{}' in .fask.toml", "Clé [theme] inconnue '{}' ignorée dans .fask.toml"),
+    ("Ignoring [theme] {} in .fask.toml: {}", "Ignoriere [theme] {} in .fask.toml: {}", "[theme] {} ignoré dans .fask.toml : {}"),
+    ("FILE", "DATEI", "FICHIER"),
+    ("LINE", "ZEILE", "LIGNE"),
+    ("KEYWORD", "STICHWORT", "MOT-CLÉ"),
+    ("OWNER", "BESITZER", "RESPONSABLE"),
+    ("AGE", "ALTER", "ÂGE"),
+    ("TEXT", "TEXT", "TEXTE"),
+    ("\ntiming:", "\nZeitmessung:", "\ndurées :"),
+    ("No '{}'s found.", "Keine '{}' gefunden.", "Aucun '{}' trouvé."),
+    ("LOCATION", "ORT", "EMPLACEMENT"),
+    ("SCORE", "WERT", "SCORE"),
+    ("SEV", "STUFE", "GRAV."),
+    ("COPIES", "KOPIEN", "COPIES"),
+    ("Failed to run editor '{}'", "Editor '{}' konnte nicht gestartet werden", "Impossible de lancer l'éditeur '{}'"),
+    ("Nothing to triage.", "Nichts zu sichten.", "Rien à trier."),
+    ("\n{} '{}'(s) to triage.", "\n{} '{}' zu sichten.", "\n{} '{}' à trier."),
+    ("Unknown action '{}'.", "Unbekannte Aktion '{}'.", "Action inconnue '{}'."),
+    ("No '{}' with ID {} in the current files", "Kein '{}' mit ID {} in den aktuellen Dateien", "Aucun '{}' avec l'ID {} dans les fichiers actuels"),
+    ("Snoozed {} ({}:{}) until {}.", "{} ({}:{}) bis {} zurückgestellt.", "{} ({}:{}) reporté jusqu'au {}."),
+    ("Wrote {} TODO(s) to {}.", "{} TODO(s) nach {} geschrieben.", "{} TODO écrit(s) dans {}."),
+    ("{} has no owner, e.g. {}(alice)", "{} hat keinen Besitzer, z. B. {}(alice)", "{} n'a pas de responsable, p. ex. {}(alice)"),
+    ("{} has no issue reference, e.g. {}(#123)", "{} hat keinen Issue-Verweis, z. B. {}(#123)", "{} n'a pas de référence de ticket, p. ex. {}(#123)"),
+    ("{} is {} characters long (max {})", "{} ist {} Zeichen lang (max. {})", "{} fait {} caractères (max. {})"),
+    ("{} has no {}", "{} hat kein {}", "{} n'a pas de {}"),
+    ("{} breaks rule {}", "{} verletzt Regel {}", "{} enfreint la règle {}"),
+    ("'{}' is not allowed", "'{}' ist nicht erlaubt", "'{}' n'est pas autorisé"),
+    (" (added {})", " (hinzugefügt {})", " (ajouté {})"),
+    ("Unsupported locale '{}' (available: {})", "Nicht unterstützte Sprache '{}' (verfügbar: {})", "Langue non prise en charge '{}' (disponibles : {})"),
+    ("invalid date '{}'. Use YYYY-MM-DD, YYYY/MM/DD, a week (2025-W14) or a month (2025-03)", "ungültiges Datum '{}'. Verwende JJJJ-MM-TT, JJJJ/MM/TT, eine Woche (2025-W14) oder einen Monat (2025-03)", "date invalide '{}'. Utilisez AAAA-MM-JJ, AAAA/MM/JJ, une semaine (2025-W14) ou un mois (2025-03)"),
+];
+
+static LOCALE: OnceLock<usize> = OnceLock::new();
+
+/// The language part of a locale name: "de_DE.UTF-8" -> "de"
+fn language(name: &str) -> String {
+    name.split(['_', '.', '-', '@'])
+        .next()
+        .unwrap_or_default()
+        .to_lowercase()
+}
+
+/// Pick the language of the messages: `--locale`, else LC_ALL, LC_MESSAGES or LANG, else
+/// English. Only a `--locale` fask has no messages for is an error, given in the language
+/// of the environment.
+pub fn init(locale: Option<&str>) -> Result<(), String> {
+    let from_env = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok().filter(|v| !v.is_empty()))
+        .next()
+        .and_then(|name| LOCALES.iter().position(|l| *l == language(&name)))
+        .unwrap_or(0);
+    let chosen = match locale {
+        Some(name) => LOCALES.iter().position(|l| *l == language(name)),
+        None => Some(from_env),
+    };
+    let _ = LOCALE.set(chosen.unwrap_or(from_env));
+    match (chosen, locale) {
+        (None, Some(name)) => Err(tr!(
+            "Unsupported locale '{}' (available: {})",
+            name,
+            LOCALES.join(", ")
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// `message` in the chosen language
+pub fn translate(message: &'static str) -> &'static str {
+    let entry = CATALOG.iter().find(|(english, _, _)| *english == message);
+    match (LOCALE.get(), entry) {
+        (Some(1), Some((_, german, _))) => german,
+        (Some(2), Some((_, _, french))) => french,
+        _ => message,
+    }
+}
+
+/// Put `args` into the `{}` placeholders of `template`, in order
+pub fn fill(template: &str, args: &[&dyn Display]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut args = args.iter();
+    let mut pieces = template.split("{}").peekable();
+    while let Some(piece) = pieces.next() {
+        out.push_str(piece);
+        if pieces.peek().is_some() {
+            if let Some(arg) = args.next() {
+                out.push_str(&arg.to_string());
+            }
+        }
+    }
+    out
+}
+
+/// `format!` for user-facing messages: the message in the chosen language, with its `{}`
+/// filled in order
+#[macro_export]
+macro_rules! tr {
+    ($message:literal $(, $arg:expr)* $(,)?) => {
+        $crate::i18n::fill(
+            $crate::i18n::translate($message),
+            &[$(&$arg as &dyn std::fmt::Display),*],
+        )
+    };
+}
+pub use crate::tr;
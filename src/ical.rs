@@ -0,0 +1,115 @@
+use anyhow::{Context, Result};
+use chrono::{Duration, Utc};
+use clap::{Args, ValueEnum};
+use std::path::PathBuf;
+
+use crate::export::ExportRow;
+use crate::i18n::tr;
+
+/// Calendar component each deadline becomes
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum IcsComponent {
+    /// All-day VEVENT on the due date, shown by every calendar app
+    Event,
+    /// VTODO with a DUE date, for task-aware apps
+    Todo,
+}
+
+/// Where `export --format ical` writes its calendar
+#[derive(Args)]
+#[command(next_help_heading = "iCalendar")]
+pub struct IcalArgs {
+    /// File to write the calendar to ("-" for stdout)
+    #[arg(long, default_value = "todos.ics")]
+    pub ics_file: PathBuf,
+
+    /// Calendar component per deadline
+    #[arg(long, value_enum, default_value = "event")]
+    pub ics_as: IcsComponent,
+}
+
+/// Escape a TEXT value (RFC 5545 3.3.11)
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Append a content line, folded to 75 octets with CRLF line endings (RFC 5545 3.1)
+fn push_line(out: &mut String, line: &str) {
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+/// Write an iCalendar file with one entry per TODO that has a deadline
+pub fn write_calendar(rows: &[ExportRow], args: &IcalArgs) -> Result<()> {
+    let stamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+    let mut out = String::new();
+    push_line(&mut out, "BEGIN:VCALENDAR");
+    push_line(&mut out, "VERSION:2.0");
+    push_line(&mut out, "PRODID:-//fask//TODO deadlines//EN");
+
+    let mut entries = 0;
+    for row in rows {
+        let Some(due) = row.deadline else {
+            continue;
+        };
+        let component = match args.ics_as {
+            IcsComponent::Event => "VEVENT",
+            IcsComponent::Todo => "VTODO",
+        };
+        push_line(&mut out, &format!("BEGIN:{}", component));
+        push_line(&mut out, &format!("UID:{}@fask", row.id));
+        push_line(&mut out, &format!("DTSTAMP:{}", stamp));
+        match args.ics_as {
+            IcsComponent::Event => {
+                push_line(
+                    &mut out,
+                    &format!("DTSTART;VALUE=DATE:{}", due.format("%Y%m%d")),
+                );
+                let end = due + Duration::days(1);
+                push_line(
+                    &mut out,
+                    &format!("DTEND;VALUE=DATE:{}", end.format("%Y%m%d")),
+                );
+            }
+            IcsComponent::Todo => {
+                push_line(
+                    &mut out,
+                    &format!("DUE;VALUE=DATE:{}", due.format("%Y%m%d")),
+                );
+            }
+        }
+        push_line(&mut out, &format!("SUMMARY:{}", escape(&row.text)));
+        let description = format!("{}:{}\n{}", row.file, row.line_number, row.text);
+        push_line(&mut out, &format!("DESCRIPTION:{}", escape(&description)));
+        push_line(&mut out, &format!("END:{}", component));
+        entries += 1;
+    }
+    push_line(&mut out, "END:VCALENDAR");
+
+    if args.ics_file.as_os_str() == "-" {
+        print!("{}", out);
+        return Ok(());
+    }
+    std::fs::write(&args.ics_file, out)
+        .with_context(|| tr!("Failed to write {}", args.ics_file.display()))?;
+    println!(
+        "{}",
+        tr!(
+            "Wrote {} deadline(s) to {}.",
+            entries,
+            args.ics_file.display()
+        )
+    );
+    Ok(())
+}
@@ -0,0 +1,65 @@
+use anyhow::Result;
+use std::path::Path;
+
+use crate::i18n::tr;
+use crate::state::{LabelEntry, State};
+use crate::{collect_current_matches, find_todo};
+
+/// Add labels to a TODO (by ID or file:line), or take them off with `remove`
+pub fn label(
+    target: &str,
+    labels: &[String],
+    remove: bool,
+    pattern: &str,
+    directory: &Path,
+) -> Result<()> {
+    let mut state = State::load(directory)?;
+    let matches = collect_current_matches(pattern, None, directory)?;
+
+    // Labels can be taken off by ID even once the TODO is gone
+    let (id, location) = match find_todo(&matches, target, &state.root) {
+        Some(m) => (
+            m.id(&state.root),
+            Some((
+                m.relative_file(&state.root),
+                m.line_content.trim().to_string(),
+            )),
+        ),
+        None if remove && state.labels.contains_key(target) => (target.to_string(), None),
+        None => anyhow::bail!(tr!(
+            "No '{}' matches '{}': give an ID as `fask triage` shows it, or file:line",
+            pattern,
+            target
+        )),
+    };
+
+    if remove {
+        if let Some(entry) = state.labels.get_mut(&id) {
+            for label in labels {
+                entry.labels.remove(label);
+            }
+            if entry.labels.is_empty() {
+                state.labels.remove(&id);
+            }
+        }
+    } else if let Some((file, text)) = location {
+        let entry = state
+            .labels
+            .entry(id.clone())
+            .or_insert_with(|| LabelEntry {
+                file,
+                text,
+                labels: Default::default(),
+            });
+        entry.labels.extend(labels.iter().cloned());
+    }
+    state.save()?;
+
+    let now = state.labels_of(&id);
+    if now.is_empty() {
+        println!("{}", tr!("{} has no labels.", id));
+    } else {
+        println!("{}", tr!("{} is labeled {}.", id, now.join(", ")));
+    }
+    Ok(())
+}
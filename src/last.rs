@@ -0,0 +1,102 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::cache::repo_cache_dir;
+use crate::i18n::tr;
+use crate::state::repo_root;
+use crate::triage::open_in_editor;
+use crate::{ContextLines, CurrentMatch};
+
+/// A saved match, with the spans the JSON output leaves out
+#[derive(Serialize, Deserialize)]
+struct SavedMatch {
+    #[serde(flatten)]
+    m: CurrentMatch,
+    spans: Vec<(usize, usize)>,
+}
+
+/// The results of the last `current` run, in the order (and so with the numbers) they were
+/// printed. Stored as `last.json` in the per-user cache rather than the state directory,
+/// which can be the repository's committed `.fask/`: it holds absolute paths from this
+/// machine.
+#[derive(Serialize, Deserialize)]
+pub struct LastRun {
+    pub pattern: String,
+    pub context: ContextLines,
+    /// Where fask ran; the match paths are relative to it
+    pub cwd: PathBuf,
+    matches: Vec<SavedMatch>,
+}
+
+impl LastRun {
+    pub fn matches(self) -> Vec<CurrentMatch> {
+        self.matches
+            .into_iter()
+            .map(|saved| CurrentMatch {
+                spans: saved.spans,
+                ..saved.m
+            })
+            .collect()
+    }
+}
+
+fn last_path(directory: &Path) -> PathBuf {
+    repo_cache_dir(&repo_root(directory)).join("last.json")
+}
+
+/// Remember the results of a `current` run for `fask last` and `fask open`
+pub fn save(
+    directory: &Path,
+    pattern: &str,
+    context: ContextLines,
+    matches: &[CurrentMatch],
+) -> Result<()> {
+    let run = LastRun {
+        pattern: pattern.to_string(),
+        context,
+        cwd: std::env::current_dir()?,
+        matches: matches
+            .iter()
+            .map(|m| SavedMatch {
+                m: m.clone(),
+                spans: m.spans.clone(),
+            })
+            .collect(),
+    };
+    let path = last_path(directory);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, serde_json::to_string(&run)?)?;
+    Ok(())
+}
+
+/// The results of the last `current` run in the repository containing `directory`
+pub fn load(directory: &Path) -> Result<LastRun> {
+    let path = last_path(directory);
+    if !path.exists() {
+        anyhow::bail!(tr!("No results saved yet: run `fask current` first"));
+    }
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| tr!("Failed to read results: {}", path.display()))?;
+    serde_json::from_str(&content)
+        .with_context(|| tr!("Failed to parse results: {}", path.display()))
+}
+
+/// Open match number `n` (counting from 1) of the last run in the editor
+pub fn open(n: usize, directory: &Path) -> Result<()> {
+    let run = load(directory)?;
+    let count = run.matches.len();
+    let Some(saved) = n.checked_sub(1).and_then(|i| run.matches.get(i)) else {
+        anyhow::bail!(tr!(
+            "No match #{}: the last run (`fask last`) had {} match(es)",
+            n,
+            count
+        ));
+    };
+    open_in_editor(
+        &run.cwd.join(&saved.m.file).to_string_lossy(),
+        saved.m.line_number,
+    )
+}
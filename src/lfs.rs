@@ -0,0 +1,114 @@
+use anyhow::{Context, Result};
+use rayon::prelude::*;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::i18n::tr;
+use crate::search::{hit, pattern_regex, Hit, Internal};
+use crate::skipped;
+
+/// First line of a Git LFS pointer file, which stands in for content not checked out
+const POINTER_HEADER: &[u8] = b"version https://git-lfs.github.com/spec/";
+
+/// Pointers are a few lines of text; anything bigger is real content
+const MAX_POINTER_SIZE: u64 = 1024;
+
+/// Whether to search the content behind LFS pointers (--lfs-smudge)
+static SMUDGE: AtomicBool = AtomicBool::new(false);
+
+pub fn init(smudge: bool) {
+    SMUDGE.store(smudge, Ordering::Relaxed);
+}
+
+/// Whether --lfs-smudge is on
+pub fn smudging() -> bool {
+    SMUDGE.load(Ordering::Relaxed)
+}
+
+pub fn is_pointer(path: &Path) -> bool {
+    if std::fs::metadata(path).map_or(true, |m| m.len() > MAX_POINTER_SIZE) {
+        return false;
+    }
+    std::fs::read(path).is_ok_and(|content| content.starts_with(POINTER_HEADER))
+}
+
+/// Whether to leave out a file's hits because it is an LFS pointer: the stub is no TODO, and
+/// with --lfs-smudge its content is searched instead
+pub fn skip_pointer(file: &str) -> bool {
+    if !is_pointer(Path::new(file)) {
+        return false;
+    }
+    if !smudging() {
+        skipped::record(file, "Git LFS pointer (--lfs-smudge to search its content)");
+    }
+    true
+}
+
+/// The content an LFS pointer stands for, from `git lfs smudge`, which downloads it unless
+/// it's in the local LFS store already
+fn smudge(path: &Path) -> Result<Vec<u8>> {
+    let pointer = std::fs::read(path)?;
+    let dir = path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let mut child = Command::new("git")
+        .args(["lfs", "smudge", "--"])
+        .arg(path.file_name().unwrap_or_default())
+        .current_dir(dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| tr!("Failed to execute git lfs"))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(&pointer)?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        anyhow::bail!(tr!(
+            "git lfs smudge failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(output.stdout)
+}
+
+/// With --lfs-smudge, search the content behind the LFS pointers under `directory`. Hits
+/// carry the pointer's path and the content's line numbers.
+pub fn smudged_hits(pattern: &str, file_type: Option<&str>, paths: &[PathBuf]) -> Result<Vec<Hit>> {
+    if !smudging() {
+        return Ok(Vec::new());
+    }
+    let re = pattern_regex(pattern)?;
+    let pointers: Vec<_> = Internal::files_in(paths, file_type)?
+        .into_iter()
+        .filter(|path| is_pointer(path))
+        .collect();
+    tracing::debug!(count = pointers.len(), "smudging LFS pointers");
+
+    Ok(pointers
+        .par_iter()
+        .flat_map_iter(|path| {
+            let file = path.display().to_string();
+            let content = match smudge(path) {
+                Ok(content) => content,
+                Err(e) => {
+                    skipped::record(&file, format!("{:#}", e));
+                    return Vec::new();
+                }
+            };
+            // Most LFS content is binary, which is left out as ripgrep does
+            if content[..content.len().min(8192)].contains(&0) {
+                return Vec::new();
+            }
+            String::from_utf8_lossy(&content)
+                .lines()
+                .enumerate()
+                .filter_map(|(idx, line)| hit(&file, idx + 1, line, &re))
+                .collect::<Vec<_>>()
+        })
+        .collect())
+}
@@ -0,0 +1,44 @@
+//! The parsing and matching core of fask: metadata and tags on TODO lines, Unicode-aware
+//! matching, stable TODO IDs and re-filtering of exported reports. Without the `cli`
+//! feature none of it runs a subprocess or touches the filesystem, so it builds for
+//! wasm32, e.g. for a browser report viewer:
+//!
+//! ```text
+//! cargo build --lib --no-default-features --target wasm32-unknown-unknown
+//! ```
+
+pub mod annotate;
+pub mod diff;
+pub mod i18n;
+pub mod metadata;
+pub mod report;
+pub mod unicode;
+
+/// Stable ID for a TODO: a hash of its file (relative to the search root) and trimmed text,
+/// so it survives the line moving around but changes when the TODO itself is edited
+pub fn todo_id(file: &str, text: &str) -> String {
+    let file = slash_path(file);
+    let file = file.strip_prefix("./").unwrap_or(&file);
+    short_hash(file.bytes().chain([0]).chain(text.trim().bytes()))
+}
+
+/// Eight hex digits of FNV-1a, which unlike std's hasher is guaranteed not to change
+/// between releases
+pub fn short_hash(bytes: impl IntoIterator<Item = u8>) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:08x}", hash >> 32)
+}
+
+/// A path with '/' separators, the form git uses, on every platform.
+/// Only Windows turns backslashes around; elsewhere they can be part of a file name.
+pub fn slash_path(path: &str) -> String {
+    if cfg!(windows) {
+        path.replace('\\', "/")
+    } else {
+        path.to_string()
+    }
+}
@@ -0,0 +1,166 @@
+use anyhow::{Context, Result};
+use clap::Args;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::cell::OnceCell;
+
+use crate::file_issues::{IssueTracker, NewIssue};
+use crate::i18n::tr;
+
+const API: &str = "https://api.linear.app/graphql";
+
+/// Where and how `--backend linear` creates issues
+#[derive(Args, Clone, Serialize, Deserialize)]
+#[command(next_help_heading = "Linear")]
+pub struct LinearArgs {
+    /// Team key new issues are created in (e.g., ENG)
+    #[arg(long)]
+    pub linear_team: Option<String>,
+
+    /// Project to add new issues to, by name
+    #[arg(long)]
+    pub linear_project: Option<String>,
+
+    /// Label to put on new issues, by name (repeatable)
+    #[arg(long)]
+    pub linear_label: Vec<String>,
+}
+
+/// Talks to the Linear GraphQL API with a key from $LINEAR_API_KEY
+pub struct Linear {
+    token: String,
+    team: Option<String>,
+    project: Option<String>,
+    labels: Vec<String>,
+    /// Team, project and label IDs for new issues, looked up on first use
+    create_input: OnceCell<Value>,
+}
+
+/// Whether a key has Linear's "ENG-123" identifier shape
+fn is_identifier(key: &str) -> bool {
+    key.split_once('-').is_some_and(|(team, number)| {
+        team.starts_with(|c: char| c.is_ascii_uppercase())
+            && team
+                .chars()
+                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+            && !number.is_empty()
+            && number.chars().all(|c| c.is_ascii_digit())
+    })
+}
+
+impl Linear {
+    pub fn new(args: &LinearArgs) -> Result<Linear> {
+        let token = std::env::var("LINEAR_API_KEY")
+            .with_context(|| tr!("Set LINEAR_API_KEY to talk to Linear"))?;
+        Ok(Linear {
+            token,
+            team: args.linear_team.clone(),
+            project: args.linear_project.clone(),
+            labels: args.linear_label.clone(),
+            create_input: OnceCell::new(),
+        })
+    }
+
+    /// Run a GraphQL query and return its "data", failing on any reported error
+    fn query(&self, query: &str, variables: Value) -> Result<Value> {
+        let api = std::env::var("LINEAR_API_URL").unwrap_or_else(|_| API.to_string());
+        let response: Value = ureq::post(&api)
+            .set("Authorization", &self.token)
+            .set("User-Agent", "fask")
+            .send_json(json!({ "query": query, "variables": variables }))
+            .with_context(|| tr!("Linear API request failed"))?
+            .into_json()
+            .with_context(|| tr!("Failed to parse Linear API response"))?;
+
+        if let Some(message) = response["errors"][0]["message"].as_str() {
+            anyhow::bail!(tr!("Linear API error: {}", message));
+        }
+        Ok(response["data"].clone())
+    }
+
+    /// ID of the first node a `query($name: String!)` returns under `field`
+    fn find_id(&self, field: &str, query: &str, name: &str) -> Result<String> {
+        let data = self.query(query, json!({ "name": name }))?;
+        data[field]["nodes"][0]["id"]
+            .as_str()
+            .map(String::from)
+            .with_context(|| tr!("No Linear {} named '{}'", field, name))
+    }
+
+    /// IssueCreateInput fields shared by every new issue
+    fn base_input(&self) -> Result<Value> {
+        let team = self
+            .team
+            .as_deref()
+            .with_context(|| tr!("Pass --linear-team to create Linear issues"))?;
+        let mut input = json!({
+            "teamId": self.find_id(
+                "teams",
+                "query($name: String!) { teams(filter: { key: { eq: $name } }) { nodes { id } } }",
+                team,
+            )?,
+        });
+        if let Some(project) = &self.project {
+            input["projectId"] = json!(self.find_id(
+                "projects",
+                "query($name: String!) { projects(filter: { name: { eq: $name } }) { nodes { id } } }",
+                project,
+            )?);
+        }
+        if !self.labels.is_empty() {
+            let ids = self
+                .labels
+                .iter()
+                .map(|label| {
+                    self.find_id(
+                        "issueLabels",
+                        "query($name: String!) { issueLabels(filter: { name: { eq: $name } }) { nodes { id } } }",
+                        label,
+                    )
+                })
+                .collect::<Result<Vec<_>>>()?;
+            input["labelIds"] = json!(ids);
+        }
+        Ok(input)
+    }
+}
+
+impl IssueTracker for Linear {
+    fn create(&self, issue: &NewIssue) -> Result<String> {
+        let mut input = match self.create_input.get() {
+            Some(input) => input.clone(),
+            None => {
+                let input = self.base_input()?;
+                self.create_input.get_or_init(|| input).clone()
+            }
+        };
+        input["title"] = json!(issue.title);
+        input["description"] = json!(issue.description);
+
+        let data = self.query(
+            "mutation($input: IssueCreateInput!) { issueCreate(input: $input) { issue { identifier } } }",
+            json!({ "input": input }),
+        )?;
+        data["issueCreate"]["issue"]["identifier"]
+            .as_str()
+            .map(String::from)
+            .with_context(|| tr!("Linear did not return the new issue"))
+    }
+
+    fn owns(&self, key: &str) -> bool {
+        is_identifier(key)
+    }
+
+    fn status(&self, key: &str) -> Result<Option<String>> {
+        // Unknown identifiers come back as an error rather than a null issue
+        let data = match self.query(
+            "query($id: String!) { issue(id: $id) { state { name } } }",
+            json!({ "id": key }),
+        ) {
+            Ok(data) => data,
+            Err(e) if e.to_string().contains("not found") => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        Ok(data["issue"]["state"]["name"].as_str().map(String::from))
+    }
+}
@@ -0,0 +1,224 @@
+use anyhow::Result;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use crate::annotate::{deadline, has_issue_reference, owner};
+use crate::collect_current_matches;
+use crate::config::Config;
+use crate::i18n::tr;
+use crate::markdown;
+use crate::style::{self, Element};
+
+/// Built-in rule IDs; custom rules from `.fask.toml` add their own
+const MISSING_OWNER: &str = "missing-owner";
+const MISSING_ISSUE: &str = "missing-issue";
+pub const BANNED_KEYWORD: &str = "banned-keyword";
+pub const TOO_LONG: &str = "too-long";
+pub const MISSING_METADATA: &str = "missing-metadata";
+pub const BUILTIN_RULES: [&str; 5] = [
+    MISSING_OWNER,
+    MISSING_ISSUE,
+    BANNED_KEYWORD,
+    TOO_LONG,
+    MISSING_METADATA,
+];
+
+/// One policy violation
+#[derive(Serialize)]
+struct Violation {
+    file: String,
+    line: usize,
+    column: usize,
+    rule: String,
+    message: String,
+    text: String,
+}
+
+/// Which rules run and what they look for
+pub struct LintOptions {
+    pub pattern: String,
+    pub file_type: Option<String>,
+    pub directory: PathBuf,
+    /// Run only these rules (all when empty)
+    pub enable: Vec<String>,
+    pub disable: Vec<String>,
+    /// Banned keywords; empty means the config's, or "XXX"
+    pub banned: Vec<String>,
+    pub json: bool,
+}
+
+/// Regex matching any of the keywords as a whole word
+fn keywords_regex(keywords: &[String]) -> String {
+    let escaped: Vec<String> = keywords
+        .iter()
+        .map(|k| {
+            k.chars()
+                .map(|c| {
+                    if c.is_alphanumeric() || c == '_' {
+                        c.to_string()
+                    } else {
+                        format!("\\{}", c)
+                    }
+                })
+                .collect()
+        })
+        .collect();
+    format!("\\b(?:{})\\b", escaped.join("|"))
+}
+
+/// Check the current TODOs against the enabled rules. Returns whether anything was flagged.
+pub fn lint(options: &LintOptions) -> Result<bool> {
+    let config = Config::load(&options.directory)?;
+    let lint_config = &config.lint;
+    let pattern = options.pattern.as_str();
+
+    // Catch typos in rule IDs instead of silently running everything
+    let known: Vec<&str> = BUILTIN_RULES
+        .iter()
+        .copied()
+        .chain(lint_config.rules.iter().map(|r| r.id.as_str()))
+        .collect();
+    for id in options
+        .enable
+        .iter()
+        .chain(&options.disable)
+        .chain(&lint_config.disable)
+    {
+        if !known.contains(&id.as_str()) {
+            anyhow::bail!(tr!(
+                "Unknown lint rule '{}'. Known rules: {}",
+                id,
+                known.join(", ")
+            ));
+        }
+    }
+    let enabled = |id: &str| {
+        (options.enable.is_empty() || options.enable.iter().any(|e| e == id))
+            && !options.disable.iter().any(|d| d == id)
+            && !lint_config.disable.iter().any(|d| d == id)
+    };
+
+    let file_type = options.file_type.as_deref();
+    let directory: &Path = &options.directory;
+    let mut violations = Vec::new();
+
+    for m in collect_current_matches(pattern, file_type, directory)? {
+        let mut flag = |rule: &str, message: String| {
+            violations.push(Violation {
+                file: m.file.clone(),
+                line: m.line_number,
+                column: m.column,
+                rule: rule.to_string(),
+                message,
+                text: m.line_content.trim().to_string(),
+            })
+        };
+        let line = m.line_content.as_str();
+        // A Markdown task has no tag to put an owner or issue in
+        let task = markdown::is_task_in(&m.file, line);
+
+        if enabled(MISSING_OWNER) && !task && owner(line, pattern).is_none() {
+            flag(
+                MISSING_OWNER,
+                tr!("{} has no owner, e.g. {}(alice)", pattern, pattern),
+            );
+        }
+        if enabled(MISSING_ISSUE) && !task && !has_issue_reference(line, pattern) {
+            flag(
+                MISSING_ISSUE,
+                tr!("{} has no issue reference, e.g. {}(#123)", pattern, pattern),
+            );
+        }
+        if let (true, Some(max)) = (enabled(TOO_LONG), lint_config.max_length) {
+            let length = line.trim().chars().count();
+            if length > max {
+                flag(
+                    TOO_LONG,
+                    tr!("{} is {} characters long (max {})", pattern, length, max),
+                );
+            }
+        }
+        if enabled(MISSING_METADATA) {
+            for key in &lint_config.required_keys {
+                // owner, issue and due can also be written in the tag or the text
+                let present = match key.as_str() {
+                    "owner" => task || owner(line, pattern).is_some(),
+                    "issue" => task || has_issue_reference(line, pattern),
+                    "due" => deadline(line, pattern).is_some(),
+                    _ => m.metadata.contains_key(key),
+                };
+                if !present {
+                    flag(MISSING_METADATA, tr!("{} has no {}", pattern, key));
+                }
+            }
+        }
+        for rule in &lint_config.rules {
+            if !enabled(&rule.id) {
+                continue;
+            }
+            let broken = rule.require.as_ref().is_some_and(|re| !re.is_match(line))
+                || rule.forbid.as_ref().is_some_and(|re| re.is_match(line))
+                || rule.r#where.as_ref().is_some_and(|f| {
+                    // A missing key is missing-metadata's business, not this rule's
+                    m.metadata.contains_key(f.key()) && !f.matches(&m.metadata)
+                });
+            if broken {
+                let message = rule
+                    .message
+                    .clone()
+                    .unwrap_or_else(|| tr!("{} breaks rule {}", pattern, rule.id));
+                flag(&rule.id, message);
+            }
+        }
+    }
+
+    let banned = if !options.banned.is_empty() {
+        options.banned.clone()
+    } else {
+        lint_config
+            .banned
+            .clone()
+            .unwrap_or_else(|| vec!["XXX".to_string()])
+    };
+    if enabled(BANNED_KEYWORD) && !banned.is_empty() {
+        let regex = keywords_regex(&banned);
+        for m in collect_current_matches(&regex, file_type, directory)? {
+            let (start, end) = m.spans.first().copied().unwrap_or((0, 0));
+            violations.push(Violation {
+                message: tr!("'{}' is not allowed", &m.line_content[start..end]),
+                file: m.file,
+                line: m.line_number,
+                column: m.column,
+                rule: BANNED_KEYWORD.to_string(),
+                text: m.line_content.trim().to_string(),
+            });
+        }
+    }
+
+    violations.sort_by(|a, b| (&a.file, a.line, &a.rule).cmp(&(&b.file, b.line, &b.rule)));
+
+    if options.json {
+        for v in &violations {
+            println!("{}", serde_json::to_string(v)?);
+        }
+        return Ok(!violations.is_empty());
+    }
+
+    for v in &violations {
+        println!(
+            "{}:{}:{}: {} {}",
+            style::paint(Element::Path, &v.file),
+            style::paint(Element::LineNumber, v.line),
+            v.column,
+            style::paint(Element::Error, &v.rule),
+            v.message
+        );
+    }
+    if violations.is_empty() {
+        println!("{}", tr!("No lint violations."));
+    } else {
+        eprintln!("{}", tr!("\n{} lint violation(s).", violations.len()));
+    }
+
+    Ok(!violations.is_empty())
+}
@@ -0,0 +1,37 @@
+use anyhow::{Context, Result};
+use std::io::IsTerminal;
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::i18n::tr;
+use crate::style;
+use tracing::Level;
+
+/// Set up diagnostics: nothing by default, debug events with `-v`, trace events with `-vv`.
+/// They go to stderr, or without colors to `log_file`.
+pub fn init(verbose: u8, log_file: Option<&Path>) -> Result<()> {
+    let level = match verbose {
+        0 if log_file.is_none() => return Ok(()),
+        0 | 1 => Level::DEBUG,
+        _ => Level::TRACE,
+    };
+    let builder = tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_target(false);
+
+    match log_file {
+        Some(path) => {
+            let file = std::fs::File::create(path)
+                .with_context(|| tr!("Failed to create log file: {}", path.display()))?;
+            builder
+                .with_ansi(false)
+                .with_writer(Mutex::new(file))
+                .init();
+        }
+        None => builder
+            .with_ansi(std::io::stderr().is_terminal() && !style::plain())
+            .with_writer(std::io::stderr)
+            .init(),
+    }
+    Ok(())
+}
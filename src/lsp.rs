@@ -0,0 +1,287 @@
+use anyhow::{Context, Result};
+use chrono::{Local, NaiveDate};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::blame;
+use crate::export::severity;
+use crate::git_log_added_lines;
+use crate::i18n::tr;
+use crate::state::repo_root;
+use fask::{metadata, unicode};
+
+/// JSON-RPC error code for methods the server doesn't implement
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// Read one message: headers up to a blank line, then Content-Length bytes of JSON.
+/// None at the end of the input.
+fn read_message(input: &mut impl BufRead) -> Result<Option<Value>> {
+    let mut length = None;
+    loop {
+        let mut header = String::new();
+        if input.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                length = Some(
+                    value
+                        .trim()
+                        .parse::<usize>()
+                        .with_context(|| tr!("Bad Content-Length"))?,
+                );
+            }
+        }
+    }
+    let length = length.with_context(|| tr!("Message without a Content-Length header"))?;
+    let mut body = vec![0; length];
+    input.read_exact(&mut body)?;
+    serde_json::from_slice(&body)
+        .map(Some)
+        .with_context(|| tr!("Message isn't JSON"))
+}
+
+fn write_message(output: &mut impl Write, message: &Value) -> Result<()> {
+    let body = serde_json::to_string(message)?;
+    write!(output, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    output.flush()?;
+    Ok(())
+}
+
+/// The path of a file:// URI, percent-decoded
+fn uri_path(uri: &str) -> Option<PathBuf> {
+    let encoded = uri.strip_prefix("file://")?.as_bytes();
+    let mut decoded = Vec::with_capacity(encoded.len());
+    let mut i = 0;
+    while i < encoded.len() {
+        let escaped = (encoded[i] == b'%')
+            .then(|| std::str::from_utf8(encoded.get(i + 1..i + 3)?).ok())
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(encoded[i]);
+                i += 1;
+            }
+        }
+    }
+    let path = String::from_utf8(decoded).ok()?;
+    // Windows paths come as "/C:/..."
+    match path.strip_prefix('/') {
+        Some(rest) if rest.get(1..2) == Some(":") => Some(PathBuf::from(rest)),
+        _ => Some(PathBuf::from(path)),
+    }
+}
+
+/// The commit HEAD is at in the repository at `root`, if any
+fn head(root: &Path) -> Option<String> {
+    Command::new("git")
+        .args(["rev-parse", "--verify", "--quiet", "HEAD"])
+        .current_dir(root)
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+}
+
+/// Editors count columns in UTF-16 code units
+fn utf16_column(line: &str, at: usize) -> usize {
+    line[..at].encode_utf16().count()
+}
+
+/// When the lines with a pattern on them were added, in one repository's history
+struct Ages {
+    /// HEAD when the history was read
+    head: String,
+    /// The newest commit date for each file (relative to the top of the repository) and
+    /// trimmed line text
+    added: HashMap<(String, String), NaiveDate>,
+}
+
+struct Server {
+    patterns: Vec<String>,
+    /// Open documents by URI, as last sent
+    documents: HashMap<String, String>,
+    /// By repository root
+    ages: HashMap<PathBuf, Ages>,
+}
+
+impl Server {
+    /// Read when the matching lines of the document's repository were added, the way
+    /// `since` does and through the same diff cache, unless HEAD hasn't moved since the
+    /// last time. Lines typed since have no age.
+    fn index(&mut self, uri: &str) {
+        let Some(directory) = uri_path(uri).and_then(|p| Some(p.parent()?.to_path_buf())) else {
+            return;
+        };
+        let root = repo_root(&directory);
+        let Some(head) = head(&root) else {
+            return;
+        };
+        if self.ages.get(&root).is_some_and(|ages| ages.head == head) {
+            return;
+        }
+        // Newline-separated patterns are alternatives, see `unicode::alternatives`
+        let lines = match git_log_added_lines(&self.patterns.join("\n"), None, None, &[], &root) {
+            Ok(lines) => lines,
+            Err(e) => {
+                tracing::debug!(error = %e, "no ages from the history");
+                return;
+            }
+        };
+        let mut added: HashMap<(String, String), NaiveDate> = HashMap::new();
+        for line in lines {
+            let date = added
+                .entry((line.file, line.content.trim().to_string()))
+                .or_insert(line.commit_date);
+            *date = (*date).max(line.commit_date);
+        }
+        self.ages.insert(root, Ages { head, added });
+    }
+
+    /// The ages of the document's repository, and the document's path in it
+    fn ages_of(&self, uri: &str) -> Option<(&Ages, String)> {
+        let path = uri_path(uri)?;
+        let path = path.canonicalize().unwrap_or(path);
+        self.ages.iter().find_map(|(root, ages)| {
+            let file = path.strip_prefix(root).ok()?.to_string_lossy();
+            Some((ages, file.replace('\\', "/")))
+        })
+    }
+
+    /// A diagnostic per line with a pattern on it, from the first one on the line
+    fn diagnostics(&self, uri: &str) -> Vec<Value> {
+        let Some(text) = self.documents.get(uri) else {
+            return Vec::new();
+        };
+        let ages = self.ages_of(uri);
+        let today = Local::now().date_naive();
+        let mut diagnostics = Vec::new();
+        for (idx, line) in text.lines().enumerate() {
+            let first = self
+                .patterns
+                .iter()
+                .flat_map(|p| unicode::spans(line, p).into_iter().map(move |s| (p, s)))
+                .min_by_key(|(_, (start, _))| *start);
+            let Some((pattern, (start, end))) = first else {
+                continue;
+            };
+            // FIXME, XXX and the like are warnings, the rest information
+            let severity = match severity(line, &metadata::metadata(line, pattern)) {
+                "high" => 2,
+                _ => 3,
+            };
+            let mut message = line[start..].trim().to_string();
+            let added = ages
+                .as_ref()
+                .and_then(|(ages, file)| ages.added.get(&(file.clone(), line.trim().to_string())));
+            if let Some(&date) = added {
+                message.push_str(&tr!(" (added {})", blame::ago(date, today)));
+            }
+            diagnostics.push(json!({
+                "range": {
+                    "start": { "line": idx, "character": utf16_column(line, start) },
+                    "end": { "line": idx, "character": utf16_column(line, end) },
+                },
+                "severity": severity,
+                "source": "fask",
+                "message": message,
+            }));
+        }
+        diagnostics
+    }
+}
+
+/// Speak the language server protocol on stdin and stdout, publishing a diagnostic for every
+/// line of an open document with one of the patterns on it
+pub fn lsp(patterns: Vec<String>) -> Result<()> {
+    let mut input = std::io::stdin().lock();
+    let mut output = std::io::stdout().lock();
+    let mut server = Server {
+        patterns,
+        documents: HashMap::new(),
+        ages: HashMap::new(),
+    };
+
+    while let Some(message) = read_message(&mut input)? {
+        let method = message["method"].as_str().unwrap_or_default();
+        let params = &message["params"];
+        let uri = params["textDocument"]["uri"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+        let response =
+            |result: Value| json!({ "jsonrpc": "2.0", "id": message["id"], "result": result });
+
+        match method {
+            "initialize" => write_message(
+                &mut output,
+                &response(json!({
+                    "capabilities": {
+                        "textDocumentSync": { "openClose": true, "change": 1, "save": true },
+                    },
+                    "serverInfo": { "name": "fask", "version": env!("CARGO_PKG_VERSION") },
+                })),
+            )?,
+            "shutdown" => write_message(&mut output, &response(Value::Null))?,
+            "exit" => return Ok(()),
+            "textDocument/didOpen" => {
+                let text = params["textDocument"]["text"].as_str().unwrap_or_default();
+                server.documents.insert(uri.clone(), text.to_string());
+                server.index(&uri);
+            }
+            "textDocument/didChange" => {
+                // Full sync: the last change holds the whole text
+                let text = params["contentChanges"]
+                    .as_array()
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change["text"].as_str());
+                if let Some(text) = text {
+                    server.documents.insert(uri.clone(), text.to_string());
+                }
+            }
+            // A commit since the last save moves HEAD
+            "textDocument/didSave" => server.index(&uri),
+            "textDocument/didClose" => {
+                server.documents.remove(&uri);
+            }
+            _ => {
+                // Requests need an answer, notifications don't
+                if message.get("id").is_some() && !method.is_empty() {
+                    write_message(
+                        &mut output,
+                        &json!({
+                            "jsonrpc": "2.0",
+                            "id": message["id"],
+                            "error": { "code": METHOD_NOT_FOUND, "message": format!("Unknown method {}", method) },
+                        }),
+                    )?;
+                }
+                continue;
+            }
+        }
+
+        if method.starts_with("textDocument/did") {
+            write_message(
+                &mut output,
+                &json!({
+                    "jsonrpc": "2.0",
+                    "method": "textDocument/publishDiagnostics",
+                    "params": { "uri": uri, "diagnostics": server.diagnostics(&uri) },
+                }),
+            )?;
+        }
+    }
+    Ok(())
+}
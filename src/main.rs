@@ -1,10 +1,83 @@
 use anyhow::{Context, Result};
-use chrono::NaiveDate;
-use clap::{Parser, Subcommand};
+use chrono::{Local, NaiveDate};
+use clap::{Args, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
 use rayon::prelude::*;
 use std::collections::HashMap;
+use std::io::{BufRead, BufReader, IsTerminal, Write};
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::process::{Command, ExitCode, Stdio};
+use std::sync::OnceLock;
+use std::time::Instant;
+
+use fask::diff::WriteMode;
+use fask::{annotate, i18n, metadata, short_hash, slash_path, todo_id, unicode};
+use i18n::tr;
+use style::Element;
+
+mod archive;
+mod argv;
+mod assign;
+mod at;
+mod azure_devops;
+mod bisect;
+mod blame;
+mod bookmark;
+mod cache;
+mod check;
+mod compare;
+mod config;
+mod config_check;
+mod daemon;
+mod dates;
+mod due;
+mod dupes;
+mod explain;
+mod export;
+mod file_issues;
+mod github;
+mod gitlab;
+mod graph;
+mod histogram;
+mod history;
+mod hyperlink;
+mod ical;
+mod label;
+mod last;
+mod lfs;
+mod linear;
+mod lint;
+mod logging;
+mod lsp;
+mod markdown;
+mod metrics;
+mod notebook;
+mod notion;
+mod obsidian;
+mod odb;
+mod pr;
+mod profile;
+mod query;
+mod queue;
+mod rg_json;
+mod score;
+mod search;
+mod server;
+mod skipped;
+mod sparse;
+mod staged;
+mod stash;
+mod state;
+mod stats;
+mod style;
+mod syntax;
+mod table;
+mod timing;
+mod top;
+mod tree;
+mod triage;
+mod vscode;
+mod worktree;
+mod xlsx;
 
 #[derive(Parser)]
 #[command(name = "fask")]
@@ -12,6 +85,317 @@ use std::process::Command;
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+
+    /// Report the wall time spent in each phase (git log, diff parsing, relocation, rendering) on stderr
+    #[arg(long, global = true)]
+    timing: bool,
+
+    /// Log what fask does on stderr: -v for each commit and file processed, -vv for every line
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    verbose: u8,
+
+    /// Write the log to this file instead of stderr (at -v level unless given more)
+    #[arg(long, global = true)]
+    log_file: Option<PathBuf>,
+
+    /// Make file paths and commit hashes clickable (OSC 8 hyperlinks)
+    #[arg(long, global = true, value_enum, default_value = "auto")]
+    hyperlinks: hyperlink::HyperlinkMode,
+
+    /// Tool that searches the current files (default: rg if installed, else ugrep, else internal)
+    #[arg(
+        id = "search_backend",
+        long = "search-backend",
+        global = true,
+        value_enum
+    )]
+    backend: Option<search::Backend>,
+
+    /// Extra ripgrep options for the rg backend, e.g. '--pcre2 --hidden' (quoted like a shell)
+    #[arg(long, global = true, allow_hyphen_values = true)]
+    rg_args: Option<String>,
+
+    /// Search for any of the patterns in a file instead of --pattern, one regex per line
+    /// ("#" starts a comment); for every command that takes --pattern
+    #[arg(long, global = true, value_name = "FILE")]
+    pattern_file: Option<PathBuf>,
+
+    /// Take the lines of --pattern-file as literal text instead of regexes
+    #[arg(long, global = true, requires = "pattern_file")]
+    fixed_strings: bool,
+
+    /// Extra git log options for the history scans (since, trend, stats, pr, ...), e.g.
+    /// '--first-parent --since-as-filter=2025-01-01' (quoted like a shell)
+    #[arg(long, global = true, allow_hyphen_values = true)]
+    git_args: Option<String>,
+
+    /// Only TODOs labeled with this (`fask label`); repeat to require several
+    #[arg(long = "label", global = true, value_name = "LABEL")]
+    label_filter: Vec<String>,
+
+    /// Search files that look minified or bundled too (by default they are skipped)
+    #[arg(long, global = true)]
+    include_minified: bool,
+
+    /// Average line length, in characters, above which a file counts as minified
+    #[arg(long, global = true, value_name = "N", default_value = "300")]
+    minified_line_length: usize,
+
+    /// Search the content behind Git LFS pointer files (with `git lfs smudge`, which may
+    /// download it) instead of skipping them
+    #[arg(long, global = true)]
+    lfs_smudge: bool,
+
+    /// Plain ASCII output: no colors, dimming, symbols, hyperlinks or wrapping, one line per
+    /// match, for screen readers and terminals that handle them badly
+    #[arg(long, global = true)]
+    plain: bool,
+
+    /// Print commit dates as dates instead of how long ago they were ("3 months ago")
+    #[arg(long, global = true)]
+    absolute_dates: bool,
+
+    /// Search even when a running `fask daemon` has the matches
+    #[arg(long, global = true)]
+    no_daemon: bool,
+
+    /// Built-in color theme, under the colors of any [theme] section in .fask.toml
+    #[arg(long, global = true, value_enum)]
+    theme: Option<style::Theme>,
+
+    /// Language of the messages: en, de or fr (default: from LC_ALL, LC_MESSAGES or LANG)
+    #[arg(long, global = true)]
+    locale: Option<String>,
+
+    /// Use the options of a [profiles.<name>] section of .fask.toml (pattern, type, exclude,
+    /// where, format) that aren't given on the command line
+    #[arg(long, global = true)]
+    profile: Option<String>,
+}
+
+/// How search results are printed
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    /// Colored matches with context lines
+    Text,
+    /// Tab-separated "file<TAB>line<TAB>text" records without color, for fzf
+    Picker,
+    /// ripgrep's JSON Lines messages (begin, match, end, summary)
+    RgJson,
+    /// One JSON object per match, including its parsed [key=value] metadata
+    Json,
+    /// An aligned table of file, line, keyword, owner, age and text, one row per match
+    Table,
+    /// "file:line:column: severity: message" lines for a VS Code problem matcher
+    Vscode,
+    /// One JSON object per match with a file:// URI, severity and message, for VS Code tasks
+    VscodeJson,
+}
+
+/// What to do with lines wider than the terminal
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum LongLines {
+    /// Cut the line at the terminal edge and end it with an ellipsis
+    Truncate,
+    /// Continue the line below, indented past the line-number gutter
+    Wrap,
+    /// Print lines as they are
+    Off,
+}
+
+/// File filters for the commands that walk git history
+#[derive(Args)]
+struct PathFilters {
+    /// File pattern to include (e.g., "*.rs", "*.js")
+    #[arg(short = 't', long)]
+    file_type: Option<String>,
+
+    /// Skip files matching this glob (repeatable)
+    #[arg(long)]
+    exclude: Vec<String>,
+}
+
+/// Which matching lines count, beyond the pattern, for `current` and `since`
+#[derive(Args)]
+struct LineFilters {
+    /// Leave out lines that also match this pattern, e.g. "TODO\(tracked\)" (repeatable)
+    #[arg(long = "not", value_name = "PATTERN", value_parser = search::pattern_regex)]
+    not: Vec<regex::Regex>,
+
+    /// Only lines that also match this pattern, e.g. "urgent" (repeatable; all must match)
+    #[arg(long, value_name = "PATTERN", value_parser = search::pattern_regex)]
+    all_of: Vec<regex::Regex>,
+}
+
+impl LineFilters {
+    fn keeps(&self, line: &str) -> bool {
+        self.all_of.iter().all(|re| re.is_match(line))
+            && !self.not.iter().any(|re| re.is_match(line))
+    }
+}
+
+/// Whether `assign` and `annotate` preview their changes, write them or both
+#[derive(Args)]
+struct WriteArgs {
+    /// Print a unified diff of the changes instead of making them
+    #[arg(long, conflicts_with = "write")]
+    dry_run: bool,
+
+    /// Make the changes and print their unified diff
+    #[arg(long)]
+    write: bool,
+}
+
+impl WriteArgs {
+    fn mode(&self) -> WriteMode {
+        match (self.dry_run, self.write) {
+            (true, _) => WriteMode::DryRun,
+            (false, true) => WriteMode::WriteWithDiff,
+            (false, false) => WriteMode::Write,
+        }
+    }
+}
+
+/// How many lines around each match `current` and `since` print
+#[derive(Args)]
+struct ContextArgs {
+    /// Number of context lines to show
+    #[arg(short = 'C', long, default_value = "2")]
+    context: usize,
+
+    /// Number of lines to show after each match (default: --context)
+    #[arg(short = 'A', long, value_name = "NUM")]
+    after_context: Option<usize>,
+
+    /// Number of lines to show before each match (default: --context)
+    #[arg(short = 'B', long, value_name = "NUM")]
+    before_context: Option<usize>,
+}
+
+impl ContextArgs {
+    fn lines(&self) -> ContextLines {
+        ContextLines {
+            before: self.before_context.unwrap_or(self.context),
+            after: self.after_context.unwrap_or(self.context),
+        }
+    }
+}
+
+/// Lines of context before and after a match
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
+struct ContextLines {
+    before: usize,
+    after: usize,
+}
+
+impl ContextLines {
+    /// The first and last line to print around a match, within a file of `len` lines
+    fn around(self, line_number: usize, len: usize) -> (usize, usize) {
+        (
+            line_number.saturating_sub(self.before).max(1),
+            (line_number + self.after).min(len),
+        )
+    }
+
+    fn is_empty(self) -> bool {
+        self.before == 0 && self.after == 0
+    }
+}
+
+impl PathFilters {
+    /// The filters as git pathspecs, so git prunes other files' diffs itself.
+    /// Like ripgrep's globs, a glob without a slash matches the file name at any depth.
+    /// `[types]` names become a pathspec per glob, since pathspecs have no "{a,b}".
+    fn pathspecs(&self) -> Vec<String> {
+        let anywhere = |glob: &str| {
+            if glob.contains('/') {
+                glob.trim_start_matches('/').to_string()
+            } else {
+                format!("**/{}", glob)
+            }
+        };
+        let included = self.file_type.iter().flat_map(|t| search::type_globs(t));
+        let excluded = self.exclude.iter().flat_map(|t| search::type_globs(t));
+        included
+            .map(|glob| match glob.strip_prefix('!') {
+                Some(glob) => format!(":(glob,exclude){}", anywhere(glob)),
+                None => format!(":(glob){}", anywhere(&glob)),
+            })
+            .chain(excluded.map(|glob| format!(":(glob,exclude){}", anywhere(&glob))))
+            .collect()
+    }
+}
+
+/// Output options shared by the search commands
+#[derive(Args)]
+struct OutputArgs {
+    /// Output format
+    #[arg(long, value_enum, default_value = "text")]
+    format: OutputFormat,
+
+    /// Terminate records and file names with NUL instead of newline (for xargs -0, fzf --read0)
+    #[arg(short = '0', long, alias = "print0")]
+    null: bool,
+
+    /// Only print the names of files with matches
+    #[arg(short = 'l', long)]
+    files_with_matches: bool,
+
+    /// How to print lines wider than the terminal
+    #[arg(long, value_enum, default_value = "truncate")]
+    long_lines: LongLines,
+
+    /// Print the file name once above its matches
+    #[arg(long, overrides_with = "no_heading")]
+    heading: bool,
+
+    /// Print the file name on every line instead of as a header
+    #[arg(long, overrides_with = "heading")]
+    no_heading: bool,
+
+    /// Cut lines longer than this many columns, even when not on a terminal or with
+    /// --long-lines wrap/off (e.g. minified files)
+    #[arg(short = 'M', long, value_name = "NUM")]
+    max_columns: Option<usize>,
+
+    /// Sort by path, then line, then date, so reports from consecutive runs diff cleanly
+    #[arg(long)]
+    stable: bool,
+
+    /// Print only the matched text of each match, with its location (text format)
+    #[arg(short = 'o', long)]
+    only_matching: bool,
+}
+
+impl OutputArgs {
+    /// Width and mode to fit `line` with: the given ones, unless the line is longer than
+    /// --max-columns, in which case it gets cut there (or at the width, if narrower)
+    fn fit(
+        &self,
+        line: &str,
+        width: Option<usize>,
+        long_lines: LongLines,
+    ) -> (Option<usize>, LongLines) {
+        match self.max_columns {
+            Some(max) if line.chars().count() > max => {
+                let width = width.filter(|_| long_lines != LongLines::Off);
+                (Some(width.map_or(max, |w| w.min(max))), LongLines::Truncate)
+            }
+            _ => (width, long_lines),
+        }
+    }
+
+    /// Whether to use the heading layout, given the command's default. --plain defaults to
+    /// one self-contained line per match.
+    fn heading(&self, default: bool) -> bool {
+        if self.heading {
+            true
+        } else if self.no_heading {
+            false
+        } else {
+            default && !style::plain()
+        }
+    }
 }
 
 #[derive(Subcommand)]
@@ -22,93 +406,2417 @@ enum Commands {
         #[arg(short, long, default_value = "TODO")]
         pattern: String,
 
-        /// Number of context lines to show
-        #[arg(short = 'C', long, default_value = "2")]
-        context: usize,
+        /// Only search the files listed in this file, one per line or NUL-separated ("-" for
+        /// stdin), e.g. from `git diff --name-only`, even ones ignore rules leave out
+        #[arg(long, value_name = "FILE", conflicts_with = "worktrees")]
+        files_from: Option<PathBuf>,
+
+        #[command(flatten)]
+        context: ContextArgs,
+
+        /// File pattern to include (e.g., "*.rs", "*.js")
+        #[arg(short = 't', long)]
+        file_type: Option<String>,
+
+        /// Directory to search in (default: current directory)
+        #[arg(short, long, default_value = ".", value_parser = archive::target)]
+        directory: PathBuf,
+
+        /// Include snoozed TODOs
+        #[arg(long)]
+        show_snoozed: bool,
+
+        /// Include files not yet tracked by git (default)
+        #[arg(long, overrides_with = "no_untracked")]
+        untracked: bool,
+
+        /// Only search files tracked by git
+        #[arg(long, overrides_with = "untracked")]
+        no_untracked: bool,
+
+        /// Also search the repository's other worktrees, noting which one each match is in
+        #[arg(long)]
+        worktrees: bool,
+
+        /// Only show TODOs whose metadata matches, e.g. owner=alice, due<2025-07-01 or
+        /// ticket (repeatable; all must match)
+        #[arg(long = "where", value_parser = metadata::Filter::parse)]
+        filters: Vec<metadata::Filter>,
+
+        /// Note who last touched each match and how long ago (one git blame per file)
+        #[arg(long)]
+        show_age: bool,
+
+        /// Suggest an owner for TODOs without one, from the blame of the lines around them
+        #[arg(long)]
+        suggest_owner: bool,
+
+        /// What to do with matches inside string literals rather than comments
+        #[arg(long, value_enum, default_value = "include")]
+        strings: syntax::Strings,
+
+        #[command(flatten)]
+        lines: LineFilters,
+
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+
+    /// Search the current files with a query over the matches, e.g.
+    /// 'keyword:FIXME AND owner:alice AND age>90d AND path:src/**'
+    Query {
+        /// Terms (keyword:, owner:, author:, path:, text:, age>90d or a metadata condition like
+        /// due<2025-07-01) joined with AND, OR and NOT, grouped with parentheses
+        #[arg(value_parser = query::parse)]
+        query: query::Query,
+
+        /// Pattern to search for; keyword: picks among what it matched
+        #[arg(short, long, default_value = "TODO|FIXME|XXX|HACK|BUG")]
+        pattern: String,
+
+        #[command(flatten)]
+        context: ContextArgs,
 
         /// File pattern to include (e.g., "*.rs", "*.js")
         #[arg(short = 't', long)]
         file_type: Option<String>,
 
         /// Directory to search in (default: current directory)
-        #[arg(short, long, default_value = ".")]
+        #[arg(short, long, default_value = ".", value_parser = archive::target)]
         directory: PathBuf,
+
+        #[command(flatten)]
+        output: OutputArgs,
     },
 
     /// Search for TODOs added after a specific date in git history
     Since {
-        /// Date in YYYY-MM-DD format (e.g., "2025-12-01")
+        /// Date as YYYY-MM-DD, YYYY/MM/DD, a week (2025-W14) or a month (2025-03), from its first day
+        #[arg(short, long, value_parser = dates::parse_start, required_unless_present_any = ["since_ref", "range"])]
+        date: Option<NaiveDate>,
+
+        /// Only commits after this one up to HEAD, e.g. HEAD~200, instead of a date
+        #[arg(long, conflicts_with_all = ["date", "range"])]
+        since_ref: Option<String>,
+
+        /// Only commits in this revision range, e.g. main..feature, instead of a date
+        #[arg(long, conflicts_with = "date")]
+        range: Option<String>,
+
+        /// Pattern to search for (default: "TODO")
+        #[arg(short, long, default_value = "TODO")]
+        pattern: String,
+
+        #[command(flatten)]
+        context: ContextArgs,
+
+        /// Directory to search in (default: current directory)
+        #[arg(short = 'D', long, default_value = ".", value_parser = archive::target)]
+        directory: PathBuf,
+
+        /// Include snoozed TODOs
+        #[arg(long)]
+        show_snoozed: bool,
+
+        /// Also search commit messages (text output only)
+        #[arg(long)]
+        include_messages: bool,
+
+        /// Count the additions per week or month as a bar chart instead of listing them
+        #[arg(long, value_enum)]
+        bucket: Option<histogram::Bucket>,
+
+        /// List the matches under a header per week or month they were added in, with counts
+        #[arg(long, value_enum, conflicts_with = "bucket")]
+        group_by: Option<histogram::Bucket>,
+
+        #[command(flatten)]
+        paths: PathFilters,
+
+        #[command(flatten)]
+        lines: LineFilters,
+
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+
+    /// Chart how many TODOs were added per week or month over the whole history
+    Trend {
+        /// Time span per bar
+        #[arg(long, value_enum, default_value = "month")]
+        bucket: histogram::Bucket,
+
+        /// One JSON object per bucket instead of the chart
+        #[arg(long)]
+        json: bool,
+
+        /// Chart the history of every branch matching this glob, e.g. 'release/*'
+        #[arg(long, value_name = "GLOB")]
+        branches: Option<String>,
+
+        /// Pattern to search for (default: "TODO")
+        #[arg(short, long, default_value = "TODO")]
+        pattern: String,
+
+        /// Directory inside the repository (default: current directory)
+        #[arg(short, long, default_value = ".", value_parser = archive::target)]
+        directory: PathBuf,
+
+        #[command(flatten)]
+        paths: PathFilters,
+    },
+
+    /// Serve TODO data over HTTP on localhost
+    Serve {
+        /// Address to listen on (":PORT" binds all interfaces)
+        #[arg(short, long, default_value = "127.0.0.1:7878")]
+        listen: String,
+
+        /// Also serve the embedded HTML dashboard at "/"
+        #[arg(long)]
+        web: bool,
+
+        /// Pattern to search for (default: "TODO")
+        #[arg(short, long, default_value = "TODO")]
+        pattern: String,
+
+        /// File pattern to include (e.g., "*.rs", "*.js")
+        #[arg(short = 't', long)]
+        file_type: Option<String>,
+
+        /// Directory to search in (default: current directory)
+        #[arg(short, long, default_value = ".", value_parser = archive::target)]
+        directory: PathBuf,
+    },
+
+    /// Keep the TODOs in memory, refreshed as files change, for other commands and over http
+    Daemon {
+        /// Address to listen on (":PORT" binds all interfaces)
+        #[arg(short, long, default_value = "127.0.0.1:7879")]
+        listen: String,
+
+        /// Pattern to search for (default: "TODO")
+        #[arg(short, long, default_value = "TODO")]
+        pattern: String,
+
+        /// File pattern to include (e.g., "*.rs", "*.js")
+        #[arg(short = 't', long)]
+        file_type: Option<String>,
+
+        /// Directory to search in (default: current directory)
+        #[arg(short, long, default_value = ".", value_parser = archive::target)]
+        directory: PathBuf,
+    },
+
+    /// Run a language server on stdin and stdout that shows TODOs as diagnostics in editors
+    Lsp {
+        /// Patterns to report (repeatable; FIXME, XXX and BUG lines are warnings)
+        #[arg(short, long = "pattern", default_values = ["TODO", "FIXME"])]
+        patterns: Vec<String>,
+    },
+
+    /// Add issue references to TODO lines from a mapping file
+    Annotate {
+        /// File of "file<TAB>line<TAB>issue" records (e.g., edited picker output)
+        mapping: PathBuf,
+
+        /// Pattern to annotate (default: "TODO")
+        #[arg(short, long, default_value = "TODO")]
+        pattern: String,
+
+        /// Directory the mapping paths are relative to (default: current directory)
+        #[arg(short, long, default_value = ".", value_parser = archive::target)]
+        directory: PathBuf,
+
+        #[command(flatten)]
+        write: WriteArgs,
+
+        /// Commit the rewritten files
+        #[arg(long, conflicts_with = "dry_run")]
+        commit: bool,
+    },
+
+    /// Create issues for TODOs without an issue reference and write the keys back
+    FileIssues {
+        /// Issue tracker to file in
+        #[arg(short, long, value_enum)]
+        backend: file_issues::IssueBackend,
+
+        /// Pattern to search for (default: "TODO")
+        #[arg(short, long, default_value = "TODO")]
+        pattern: String,
+
+        /// File pattern to include (e.g., "*.rs", "*.js")
+        #[arg(short = 't', long)]
+        file_type: Option<String>,
+
+        /// Directory to search in (default: current directory)
+        #[arg(short, long, default_value = ".", value_parser = archive::target)]
+        directory: PathBuf,
+
+        #[command(flatten)]
+        azure: azure_devops::AzureDevopsArgs,
+
+        #[command(flatten)]
+        linear: linear::LinearArgs,
+    },
+
+    /// Retry the issues and review comments that failed on a rate limit or the network
+    Flush {
+        /// Retry everything now, even what is still waiting out its backoff
+        #[arg(long)]
+        force: bool,
+
+        /// Directory of the repository (default: current directory)
+        #[arg(short, long, default_value = ".", value_parser = archive::target)]
+        directory: PathBuf,
+    },
+
+    /// List TODOs that reference issues in a tracker, with the issues' current status
+    IssueStatus {
+        /// Issue tracker the references point to
+        #[arg(short, long, value_enum)]
+        backend: file_issues::IssueBackend,
+
+        /// Pattern to search for (default: "TODO")
+        #[arg(short, long, default_value = "TODO")]
+        pattern: String,
+
+        /// File pattern to include (e.g., "*.rs", "*.js")
+        #[arg(short = 't', long)]
+        file_type: Option<String>,
+
+        /// Directory to search in (default: current directory)
+        #[arg(short, long, default_value = ".", value_parser = archive::target)]
+        directory: PathBuf,
+
+        #[command(flatten)]
+        azure: azure_devops::AzureDevopsArgs,
+
+        #[command(flatten)]
+        linear: linear::LinearArgs,
+    },
+
+    /// Flag TODOs whose referenced issue is already closed
+    VerifyIssues {
+        /// Issue tracker the references point to
+        #[arg(short, long, value_enum)]
+        backend: file_issues::IssueBackend,
+
+        /// Pattern to search for (default: "TODO")
+        #[arg(short, long, default_value = "TODO")]
+        pattern: String,
+
+        /// File pattern to include (e.g., "*.rs", "*.js")
+        #[arg(short = 't', long)]
+        file_type: Option<String>,
+
+        /// Directory to search in (default: current directory)
+        #[arg(short, long, default_value = ".", value_parser = archive::target)]
+        directory: PathBuf,
+
+        #[command(flatten)]
+        azure: azure_devops::AzureDevopsArgs,
+
+        #[command(flatten)]
+        linear: linear::LinearArgs,
+    },
+
+    /// List TODOs with deadlines, soonest first
+    Due {
+        /// Only show TODOs due within this many days (overdue ones always show)
+        #[arg(short, long)]
+        within: Option<i64>,
+
+        /// Raise a desktop notification summarizing overdue TODOs
+        #[arg(long)]
+        notify: bool,
+
+        /// Pattern to search for (default: "TODO")
+        #[arg(short, long, default_value = "TODO")]
+        pattern: String,
+
+        /// File pattern to include (e.g., "*.rs", "*.js")
+        #[arg(short = 't', long)]
+        file_type: Option<String>,
+
+        /// Directory to search in (default: current directory)
+        #[arg(short, long, default_value = ".", value_parser = archive::target)]
+        directory: PathBuf,
+    },
+
+    /// Export the current TODOs to another tool
+    Export {
+        /// Where to export to
+        #[arg(short, long, value_enum)]
+        format: export::ExportFormat,
+
+        /// Pattern to search for (default: "TODO")
+        #[arg(short, long, default_value = "TODO")]
+        pattern: String,
+
+        /// File pattern to include (e.g., "*.rs", "*.js")
+        #[arg(short = 't', long)]
+        file_type: Option<String>,
+
+        /// Directory to search in (default: current directory)
+        #[arg(short, long, default_value = ".", value_parser = archive::target)]
+        directory: PathBuf,
+
+        #[command(flatten)]
+        notion: notion::NotionArgs,
+
+        #[command(flatten)]
+        obsidian: obsidian::ObsidianArgs,
+
+        #[command(flatten)]
+        ical: ical::IcalArgs,
+
+        #[command(flatten)]
+        xlsx: xlsx::XlsxArgs,
+
+        /// Anonymize the export for sharing outside the team (comma-separated or repeatable)
+        #[arg(long, value_enum, value_delimiter = ',')]
+        redact: Vec<export::Redact>,
+    },
+
+    /// List TODOs without a triage decision, or step through them with --interactive
+    Triage {
+        /// Walk through each TODO and record a decision
         #[arg(short, long)]
-        date: String,
+        interactive: bool,
 
         /// Pattern to search for (default: "TODO")
         #[arg(short, long, default_value = "TODO")]
         pattern: String,
 
-        /// Number of context lines to show
-        #[arg(short = 'C', long, default_value = "2")]
-        context: usize,
+        /// File pattern to include (e.g., "*.rs", "*.js")
+        #[arg(short = 't', long)]
+        file_type: Option<String>,
+
+        /// Directory to search in (default: current directory)
+        #[arg(short, long, default_value = ".", value_parser = archive::target)]
+        directory: PathBuf,
+    },
+
+    /// Hide a TODO from the default output until a date
+    Snooze {
+        /// ID of the TODO (as shown by `fask triage`)
+        id: String,
+
+        /// Date after which the TODO shows up again (YYYY-MM-DD, a week or a month, to its last day)
+        #[arg(short, long, value_parser = dates::parse_end)]
+        until: NaiveDate,
+
+        /// Pattern the TODO matches (default: "TODO")
+        #[arg(short, long, default_value = "TODO")]
+        pattern: String,
+
+        /// Directory to search in (default: current directory)
+        #[arg(short, long, default_value = ".", value_parser = archive::target)]
+        directory: PathBuf,
+    },
+
+    /// Pin TODOs to tackle soon; `current` marks them with ★
+    Bookmark {
+        /// TODOs to pin, by ID (as `fask triage` shows it) or file:line
+        #[arg(required = true)]
+        targets: Vec<String>,
+
+        /// Unpin them instead
+        #[arg(long)]
+        remove: bool,
+
+        /// Pattern the TODOs match (default: "TODO")
+        #[arg(short, long, default_value = "TODO")]
+        pattern: String,
+
+        /// Directory to search in (default: current directory)
+        #[arg(short, long, default_value = ".", value_parser = archive::target)]
+        directory: PathBuf,
+    },
+
+    /// Label a TODO, e.g. `fask label <id> perf`, to pick it out later with --label
+    Label {
+        /// The TODO's ID (as `fask triage` shows it) or its location as file:line
+        target: String,
+
+        /// Labels to add
+        #[arg(required = true)]
+        labels: Vec<String>,
+
+        /// Take the labels off instead
+        #[arg(long)]
+        remove: bool,
+
+        /// Pattern the TODO matches (default: "TODO")
+        #[arg(short, long, default_value = "TODO")]
+        pattern: String,
+
+        /// Directory to search in (default: current directory)
+        #[arg(short, long, default_value = ".", value_parser = archive::target)]
+        directory: PathBuf,
+    },
+
+    /// List the bookmarked TODOs and where they are now
+    Bookmarks {
+        /// Pattern the TODOs match (default: "TODO")
+        #[arg(short, long, default_value = "TODO")]
+        pattern: String,
+
+        /// Directory to search in (default: current directory)
+        #[arg(short, long, default_value = ".", value_parser = archive::target)]
+        directory: PathBuf,
+    },
+
+    /// Report identical or near-identical TODOs, e.g. copy-pasted across files
+    Dupes {
+        /// Word overlap (0.0-1.0) at which two TODOs count as near-identical
+        #[arg(long, default_value = "0.8")]
+        threshold: f64,
+
+        /// Pattern to search for (default: "TODO")
+        #[arg(short, long, default_value = "TODO")]
+        pattern: String,
+
+        /// File pattern to include (e.g., "*.rs", "*.js")
+        #[arg(short = 't', long)]
+        file_type: Option<String>,
+
+        /// Directory to search in (default: current directory)
+        #[arg(short, long, default_value = ".", value_parser = archive::target)]
+        directory: PathBuf,
+    },
+
+    /// Show the TODOs most worth fixing first, as a compact table
+    Top {
+        /// How many TODOs to show
+        #[arg(short = 'n', long, default_value = "20")]
+        limit: usize,
+
+        /// What to rank by
+        #[arg(long, value_enum, default_value = "score")]
+        by: top::TopBy,
+
+        /// Word overlap (0.0-1.0) at which two TODOs count as near-identical, for --by duplicates
+        #[arg(long, default_value = "0.8")]
+        threshold: f64,
+
+        /// Pattern to search for (default: "TODO")
+        #[arg(short, long, default_value = "TODO")]
+        pattern: String,
+
+        /// File pattern to include (e.g., "*.rs", "*.js")
+        #[arg(short = 't', long)]
+        file_type: Option<String>,
+
+        /// Directory to search in (default: current directory)
+        #[arg(short, long, default_value = ".", value_parser = archive::target)]
+        directory: PathBuf,
+    },
+
+    /// Show the repository as a tree with the TODO count and share of every directory
+    Tree {
+        /// How many levels below the directory to show (default: all)
+        #[arg(long)]
+        depth: Option<usize>,
+
+        /// Pattern to search for (default: "TODO")
+        #[arg(short, long, default_value = "TODO")]
+        pattern: String,
+
+        /// File pattern to include (e.g., "*.rs", "*.js")
+        #[arg(short = 't', long)]
+        file_type: Option<String>,
+
+        /// Directory to search in (default: current directory)
+        #[arg(short, long, default_value = ".", value_parser = archive::target)]
+        directory: PathBuf,
+    },
+
+    /// Check staged changes for new TODOs; exits with 1 if any would be committed (for pre-commit hooks)
+    Staged {
+        /// Only block TODOs without an issue reference like TODO(#123)
+        #[arg(long)]
+        allow_annotated: bool,
+
+        /// Pattern to search for (default: "TODO")
+        #[arg(short, long, default_value = "TODO")]
+        pattern: String,
+
+        /// Directory to search in (default: current directory)
+        #[arg(short, long, default_value = ".", value_parser = archive::target)]
+        directory: PathBuf,
+    },
+
+    /// Flag TODOs that break team policy; exits with 1 if any do
+    Lint {
+        /// Run only these rules, by ID (repeatable; default: all)
+        #[arg(long)]
+        enable: Vec<String>,
+
+        /// Skip these rules, by ID (repeatable)
+        #[arg(long)]
+        disable: Vec<String>,
+
+        /// Keyword the banned-keyword rule flags (repeatable; default: XXX)
+        #[arg(long)]
+        banned: Vec<String>,
+
+        /// Print one JSON object per violation
+        #[arg(long)]
+        json: bool,
+
+        /// Pattern to search for (default: "TODO")
+        #[arg(short, long, default_value = "TODO")]
+        pattern: String,
+
+        /// File pattern to include (e.g., "*.rs", "*.js")
+        #[arg(short = 't', long)]
+        file_type: Option<String>,
+
+        /// Directory to search in (default: current directory)
+        #[arg(short, long, default_value = ".", value_parser = archive::target)]
+        directory: PathBuf,
+    },
+
+    /// Report TODOs added on the current branch since it forked from the base branch
+    Pr {
+        /// Branch the pull request targets
+        #[arg(short, long, default_value = "origin/main")]
+        base: String,
+
+        /// Post the TODOs as inline review comments on the branch's GitHub pull request or
+        /// GitLab merge request
+        #[arg(long)]
+        comment: bool,
+
+        /// Pattern to search for (default: "TODO")
+        #[arg(short, long, default_value = "TODO")]
+        pattern: String,
+
+        /// Directory to search in (default: current directory)
+        #[arg(short, long, default_value = ".", value_parser = archive::target)]
+        directory: PathBuf,
+    },
+
+    /// Search the diffs of stash entries for TODOs
+    Stash {
+        /// Pattern to search for (default: "TODO")
+        #[arg(short, long, default_value = "TODO")]
+        pattern: String,
+
+        /// Directory to search in (default: current directory)
+        #[arg(short, long, default_value = ".", value_parser = archive::target)]
+        directory: PathBuf,
+    },
+
+    /// Search the tree at a past commit, tag or branch without checking it out
+    At {
+        /// Commit, tag or branch to search (e.g., v1.0)
+        #[arg(required_unless_present = "branches")]
+        reference: Option<String>,
+
+        /// Search the tip of every branch matching this glob instead, e.g. 'release/*'
+        #[arg(long, value_name = "GLOB", conflicts_with = "reference")]
+        branches: Option<String>,
+
+        /// Pattern to search for (default: "TODO")
+        #[arg(short, long, default_value = "TODO")]
+        pattern: String,
+
+        #[command(flatten)]
+        paths: PathFilters,
+
+        /// Directory to search in (default: current directory)
+        #[arg(short, long, default_value = ".", value_parser = archive::target)]
+        directory: PathBuf,
+    },
+
+    /// Binary-search history for the commit that introduced or removed a TODO
+    Bisect {
+        /// Text to look for, e.g. "TODO: flaky retry"
+        #[arg(required_unless_present = "id")]
+        text: Option<String>,
+
+        /// TODO ID (as shown by fask triage) to look for instead of text
+        #[arg(long, conflicts_with = "text")]
+        id: Option<String>,
+
+        /// Pattern the TODO with --id matches (default: "TODO")
+        #[arg(short, long, default_value = "TODO")]
+        pattern: String,
+
+        /// Oldest commit to consider (default: the root commit)
+        #[arg(long)]
+        from: Option<String>,
+
+        /// Newest commit to consider
+        #[arg(long, default_value = "HEAD")]
+        to: String,
+
+        /// Directory to search in (default: current directory)
+        #[arg(short, long, default_value = ".", value_parser = archive::target)]
+        directory: PathBuf,
+    },
+
+    /// Compare the number of TODOs with a base branch, e.g. as a merge gate in CI
+    Check {
+        /// Branch to compare with, from its merge base with HEAD
+        #[arg(long, default_value = "origin/main")]
+        against: String,
+
+        /// Exit with status 1 if the net number of TODOs went up
+        #[arg(long)]
+        fail_on_increase: bool,
+
+        /// Pattern to search for (default: "TODO")
+        #[arg(short, long, default_value = "TODO")]
+        pattern: String,
+
+        #[command(flatten)]
+        paths: PathFilters,
+
+        /// Directory to search in (default: current directory)
+        #[arg(short, long, default_value = ".", value_parser = archive::target)]
+        directory: PathBuf,
+    },
+
+    /// Show the full story of one TODO: who added it and when, the edits since, its owner
+    Explain {
+        /// The TODO's ID (as `fask triage` shows it) or its location as file:line
+        target: String,
+
+        /// Pattern to search for (default: "TODO")
+        #[arg(short, long, default_value = "TODO")]
+        pattern: String,
+
+        /// Directory to search in (default: current directory)
+        #[arg(short, long, default_value = ".", value_parser = archive::target)]
+        directory: PathBuf,
+    },
+
+    /// Print the results of the last `current` run again, without searching
+    Last {
+        /// Directory of the repository (default: current directory)
+        #[arg(short, long, default_value = ".", value_parser = archive::target)]
+        directory: PathBuf,
+
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+
+    /// Open match number N of the last `current` run in $VISUAL / $EDITOR
+    Open {
+        /// The match's number, as `current` and `last` print it
+        number: usize,
+
+        /// Directory of the repository (default: current directory)
+        #[arg(short, long, default_value = ".", value_parser = archive::target)]
+        directory: PathBuf,
+    },
+
+    /// Add owner tags to TODOs, e.g. "TODO: x" becomes "TODO(alice): x"
+    Assign {
+        /// TODOs to assign, by ID (as `fask triage` shows it) or file:line
+        /// (default with --auto: every TODO without an owner)
+        targets: Vec<String>,
+
+        /// Who to assign them to
+        #[arg(long, required_unless_present = "auto", conflicts_with = "auto")]
+        to: Option<String>,
+
+        /// Assign each TODO to its suggested owner, from the blame of the lines around it
+        #[arg(long)]
+        auto: bool,
+
+        /// Pattern to search for (default: "TODO")
+        #[arg(short, long, default_value = "TODO")]
+        pattern: String,
+
+        /// Directory to search in (default: current directory)
+        #[arg(short, long, default_value = ".", value_parser = archive::target)]
+        directory: PathBuf,
+
+        #[command(flatten)]
+        write: WriteArgs,
+
+        /// Commit the rewritten files
+        #[arg(long, conflicts_with = "dry_run")]
+        commit: bool,
+    },
+
+    /// Compare two `--format json` reports: TODOs that are new, resolved or moved
+    Compare {
+        /// The earlier report
+        old: PathBuf,
+
+        /// The later report
+        new: PathBuf,
+
+        /// Exit with status 1 if the later report has new TODOs
+        #[arg(long)]
+        fail_on_new: bool,
+    },
+
+    /// Work with the repository's `.fask.toml`
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+
+    /// Inspect and prune the state fask keeps per repository
+    State {
+        #[command(subcommand)]
+        action: StateAction,
+    },
+
+    /// Summarize the pattern's history: how many were added, resolved and are still open
+    Stats {
+        /// Show how long resolved TODOs stayed open, per directory and author
+        #[arg(long)]
+        resolution_time: bool,
+
+        /// Print the open count per day (added minus resolved) for a burn-down chart instead
+        #[arg(long, value_enum, conflicts_with = "resolution_time")]
+        burndown: Option<stats::BurndownFormat>,
+
+        /// Show TODOs added, resolved and net per author (names go through .mailmap)
+        #[arg(long, conflicts_with = "burndown")]
+        by_author: bool,
+
+        /// Only count --by-author changes on or after this date (YYYY-MM-DD, a week or a month)
+        #[arg(long, requires = "by_author", value_parser = dates::parse_start)]
+        since: Option<NaiveDate>,
+
+        /// Only count --by-author changes on or before this date (YYYY-MM-DD, a week or a month)
+        #[arg(long, requires = "by_author", value_parser = dates::parse_end)]
+        until: Option<NaiveDate>,
+
+        /// Pattern to search for (default: "TODO")
+        #[arg(short, long, default_value = "TODO")]
+        pattern: String,
+
+        /// Directory inside the repository (default: current directory)
+        #[arg(short, long, default_value = ".", value_parser = archive::target)]
+        directory: PathBuf,
+    },
+
+    /// Export TODO counts as Prometheus metrics
+    Metrics {
+        /// Address to serve /metrics on (default: ":9300")
+        #[arg(short, long, conflicts_with = "textfile")]
+        listen: Option<String>,
+
+        /// Write the metrics to a file for node_exporter's textfile collector instead
+        #[arg(long)]
+        textfile: Option<PathBuf>,
+
+        /// Pattern to count, repeat for several keywords (default: "TODO")
+        #[arg(short, long, default_value = "TODO")]
+        pattern: Vec<String>,
+
+        /// File pattern to include (e.g., "*.rs", "*.js")
+        #[arg(short = 't', long)]
+        file_type: Option<String>,
+
+        /// Directory to search in (default: current directory)
+        #[arg(short, long, default_value = ".", value_parser = archive::target)]
+        directory: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigAction {
+    /// Validate the config: syntax, unknown keys, globs, regexes and conflicting options
+    Check {
+        /// Directory inside the repository (default: current directory)
+        #[arg(short, long, default_value = ".", value_parser = archive::target)]
+        directory: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+enum StateAction {
+    /// Print where the state file lives
+    Path {
+        /// Directory inside the repository (default: current directory)
+        #[arg(short, long, default_value = ".", value_parser = archive::target)]
+        directory: PathBuf,
+    },
+
+    /// Summarize what is stored
+    Show {
+        /// Print the raw JSON instead
+        #[arg(long)]
+        json: bool,
+
+        /// Directory inside the repository (default: current directory)
+        #[arg(short, long, default_value = ".", value_parser = archive::target)]
+        directory: PathBuf,
+    },
+
+    /// Remove entries for TODOs that no longer exist and expired snoozes
+    Prune {
+        /// Only list what would be removed
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Directory inside the repository (default: current directory)
+        #[arg(short, long, default_value = ".", value_parser = archive::target)]
+        directory: PathBuf,
+    },
+}
+
+fn main() -> Result<ExitCode> {
+    let args: Vec<std::ffi::OsString> = std::env::args_os().collect();
+    // Before the real parse, so the errors in values (a bad --date) come in the language too
+    let locale = Cli::command()
+        .ignore_errors(true)
+        .try_get_matches_from(&args)
+        .ok()
+        .and_then(|m| m.get_one::<String>("locale").cloned());
+    i18n::init(locale.as_deref()).map_err(anyhow::Error::msg)?;
+    let args = search::apply_pattern_file(args)?;
+    let matches = Cli::command().get_matches_from(profile::apply(args)?);
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    let started = Instant::now();
+    // Windows consoles only understand the color escapes once asked to
+    #[cfg(windows)]
+    let _ = enable_ansi_support::enable_ansi_support();
+    if cli.timing {
+        timing::enable();
+    }
+    // The config of the repository the command runs in, as the commands that read more of
+    // it load it; `config check` is the one to say what is wrong with a broken one
+    let directory = config::command_directory(&matches);
+    let config = match matches.subcommand_name() {
+        Some("config") => config::Config::load(&directory).unwrap_or_default(),
+        _ => config::Config::load(&directory)?,
+    };
+    let theme = match cli.plain {
+        true => config::ThemeConfig::default(),
+        false => config.theme,
+    };
+    style::init(cli.plain, cli.theme, &theme);
+    search::init_types(config.types);
+    logging::init(cli.verbose, cli.log_file.as_deref())?;
+    hyperlink::init(if cli.plain {
+        hyperlink::HyperlinkMode::Never
+    } else {
+        cli.hyperlinks
+    });
+    let rg_args = match &cli.rg_args {
+        Some(args) => shell_words(args).with_context(|| tr!("Invalid --rg-args"))?,
+        None => Vec::new(),
+    };
+    search::init(cli.backend, rg_args)?;
+    if let Some(args) = &cli.git_args {
+        let _ = GIT_LOG_ARGS.set(shell_words(args).with_context(|| tr!("Invalid --git-args"))?);
+    }
+    let _ = LABELS.set(cli.label_filter.clone());
+    skipped::init_minified((!cli.include_minified).then_some(cli.minified_line_length));
+    lfs::init(cli.lfs_smudge);
+    dates::init(cli.absolute_dates);
+    daemon::init(!cli.no_daemon);
+    if let Some(profile) = &cli.profile {
+        tracing::debug!(profile, "using profile");
+    }
+
+    // Clean up extracted archives before any exit, failed checks included
+    let result = run(cli.command);
+    archive::cleanup();
+    let code = result?;
+
+    skipped::report(cli.verbose > 0);
+    timing::report(started.elapsed());
+    Ok(code)
+}
+
+/// Run a command; the exit code is a failure when a check it ran found problems
+fn run(command: Commands) -> Result<ExitCode> {
+    match command {
+        Commands::Current {
+            pattern,
+            files_from,
+            context,
+            file_type,
+            directory,
+            show_snoozed,
+            untracked: _,
+            no_untracked,
+            worktrees,
+            filters,
+            show_age,
+            suggest_owner,
+            strings,
+            lines,
+            output,
+        } => search_current_files(
+            &pattern,
+            context.lines(),
+            file_type,
+            directory,
+            &MatchFilters {
+                show_snoozed,
+                untracked: !no_untracked,
+                worktrees,
+                files_from,
+                strings,
+                metadata: filters,
+                lines,
+                query: None,
+            },
+            &MatchNotes {
+                age: show_age,
+                suggested_owner: suggest_owner,
+            },
+            &output,
+        )?,
+
+        Commands::Query {
+            query,
+            pattern,
+            context,
+            file_type,
+            directory,
+            output,
+        } => search_current_files(
+            &pattern,
+            context.lines(),
+            file_type,
+            directory,
+            &MatchFilters {
+                show_snoozed: false,
+                untracked: true,
+                worktrees: false,
+                files_from: None,
+                strings: syntax::Strings::Include,
+                metadata: Vec::new(),
+                lines: LineFilters {
+                    not: Vec::new(),
+                    all_of: Vec::new(),
+                },
+                query: Some(query),
+            },
+            &MatchNotes {
+                age: false,
+                suggested_owner: false,
+            },
+            &output,
+        )?,
+
+        Commands::Since {
+            date,
+            since_ref,
+            range,
+            pattern,
+            directory,
+            bucket: Some(bucket),
+            paths,
+            lines,
+            output,
+            ..
+        } => since_histogram(
+            &History::new(date, since_ref, range, &directory)?,
+            &pattern,
+            &directory,
+            bucket,
+            &paths,
+            &lines,
+            &output,
+        )?,
+
+        Commands::Since {
+            date,
+            since_ref,
+            range,
+            pattern,
+            context,
+            directory,
+            show_snoozed,
+            include_messages,
+            bucket: None,
+            group_by,
+            paths,
+            lines,
+            output,
+        } => search_since_date(SinceOptions {
+            history: &History::new(date, since_ref, range, &directory)?,
+            pattern: &pattern,
+            context: context.lines(),
+            directory,
+            show_snoozed,
+            include_messages,
+            group_by,
+            pathspecs: paths.pathspecs(),
+            lines: &lines,
+            output: &output,
+        })?,
+
+        Commands::Trend {
+            bucket,
+            json,
+            branches: Some(glob),
+            pattern,
+            directory,
+            paths,
+        } => {
+            let mut charts = Vec::new();
+            for branch in at::branches(&glob, &directory)? {
+                let added_lines = git_log_added_lines(
+                    &pattern,
+                    None,
+                    Some(&branch),
+                    &paths.pathspecs(),
+                    &directory,
+                )?;
+                let counts =
+                    histogram::bucket_counts(added_lines.iter().map(|a| a.commit_date), bucket);
+                charts.push((branch, counts));
+            }
+            histogram::print_branch_histograms(&charts, json)?;
+        }
+
+        Commands::Trend {
+            bucket,
+            json,
+            branches: None,
+            pattern,
+            directory,
+            paths,
+        } => {
+            let added_lines =
+                git_log_added_lines(&pattern, None, None, &paths.pathspecs(), &directory)?;
+            let counts =
+                histogram::bucket_counts(added_lines.iter().map(|a| a.commit_date), bucket);
+            if counts.is_empty() && !json {
+                println!("{}", tr!("No '{}' additions found.", pattern));
+            }
+            histogram::print_histogram(&counts, json)?;
+        }
+
+        Commands::Serve {
+            listen,
+            web,
+            pattern,
+            file_type,
+            directory,
+        } => server::serve(
+            &listen,
+            web,
+            server::ServeOptions {
+                pattern,
+                file_type,
+                directory,
+            },
+        )?,
+
+        Commands::Daemon {
+            listen,
+            pattern,
+            file_type,
+            directory,
+        } => daemon::daemon(
+            &listen,
+            server::ServeOptions {
+                pattern,
+                file_type,
+                directory,
+            },
+        )?,
+
+        Commands::Lsp { patterns } => lsp::lsp(patterns)?,
+
+        Commands::Annotate {
+            mapping,
+            pattern,
+            directory,
+            write,
+            commit,
+        } => annotate::annotate(
+            &mapping,
+            &pattern,
+            &directory,
+            &state::repo_root(&directory),
+            write.mode(),
+            commit,
+        )?,
+
+        Commands::FileIssues {
+            backend,
+            pattern,
+            file_type,
+            directory,
+            azure,
+            linear,
+        } => file_issues::file_issues(
+            &pattern,
+            file_type.as_deref(),
+            &directory,
+            &file_issues::TrackerArgs {
+                backend,
+                azure: &azure,
+                linear: &linear,
+            },
+        )?,
+
+        Commands::IssueStatus {
+            backend,
+            pattern,
+            file_type,
+            directory,
+            azure,
+            linear,
+        } => file_issues::issue_status(
+            &pattern,
+            file_type.as_deref(),
+            &directory,
+            &file_issues::TrackerArgs {
+                backend,
+                azure: &azure,
+                linear: &linear,
+            },
+        )?,
+
+        Commands::VerifyIssues {
+            backend,
+            pattern,
+            file_type,
+            directory,
+            azure,
+            linear,
+        } => {
+            let tracker = file_issues::TrackerArgs {
+                backend,
+                azure: &azure,
+                linear: &linear,
+            };
+            if file_issues::verify_issues(&pattern, file_type.as_deref(), &directory, &tracker)? {
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+
+        Commands::Due {
+            within,
+            notify,
+            pattern,
+            file_type,
+            directory,
+        } => due::due(&pattern, file_type.as_deref(), &directory, within, notify)?,
+
+        Commands::Export {
+            format,
+            pattern,
+            file_type,
+            directory,
+            notion,
+            obsidian,
+            ical,
+            xlsx,
+            redact,
+        } => export::export(
+            &pattern,
+            file_type.as_deref(),
+            &directory,
+            &redact,
+            &export::ExportArgs {
+                format,
+                notion: &notion,
+                obsidian: &obsidian,
+                ical: &ical,
+                xlsx: &xlsx,
+            },
+        )?,
+
+        Commands::Triage {
+            interactive,
+            pattern,
+            file_type,
+            directory,
+        } => triage::triage(&pattern, file_type.as_deref(), &directory, interactive)?,
+
+        Commands::Snooze {
+            id,
+            until,
+            pattern,
+            directory,
+        } => triage::snooze(&id, until, &pattern, &directory)?,
+
+        Commands::Bookmark {
+            targets,
+            remove,
+            pattern,
+            directory,
+        } => bookmark::bookmark(&targets, remove, &pattern, &directory)?,
+
+        Commands::Label {
+            target,
+            labels,
+            remove,
+            pattern,
+            directory,
+        } => label::label(&target, &labels, remove, &pattern, &directory)?,
+
+        Commands::Bookmarks { pattern, directory } => bookmark::bookmarks(&pattern, &directory)?,
+
+        Commands::Dupes {
+            threshold,
+            pattern,
+            file_type,
+            directory,
+        } => dupes::find_dupes(&pattern, file_type.as_deref(), &directory, threshold)?,
+
+        Commands::Tree {
+            depth,
+            pattern,
+            file_type,
+            directory,
+        } => tree::tree(&pattern, file_type.as_deref(), &directory, depth)?,
+
+        Commands::Top {
+            limit,
+            by,
+            threshold,
+            pattern,
+            file_type,
+            directory,
+        } => top::top(
+            &pattern,
+            file_type.as_deref(),
+            &directory,
+            by,
+            limit,
+            threshold,
+        )?,
+
+        Commands::Staged {
+            allow_annotated,
+            pattern,
+            directory,
+        } => {
+            if staged::check_staged(&pattern, &directory, allow_annotated)? {
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+
+        Commands::Lint {
+            enable,
+            disable,
+            banned,
+            json,
+            pattern,
+            file_type,
+            directory,
+        } => {
+            let options = lint::LintOptions {
+                pattern,
+                file_type,
+                directory,
+                enable,
+                disable,
+                banned,
+                json,
+            };
+            if lint::lint(&options)? {
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+
+        Commands::Flush { force, directory } => queue::flush(&directory, force)?,
+
+        Commands::Pr {
+            base,
+            comment,
+            pattern,
+            directory,
+        } => pr::search_pr(&pattern, &base, &directory, comment)?,
+
+        Commands::Stash { pattern, directory } => stash::search_stashes(&pattern, &directory)?,
+
+        Commands::Bisect {
+            text,
+            id,
+            pattern,
+            from,
+            to,
+            directory,
+        } => {
+            let target = match (&text, &id) {
+                (_, Some(id)) => bisect::Target::Id {
+                    id,
+                    pattern: &pattern,
+                },
+                (Some(text), None) => bisect::Target::Text(text),
+                (None, None) => unreachable!("clap requires text or --id"),
+            };
+            bisect::bisect(&target, from.as_deref(), &to, &directory)?
+        }
+
+        Commands::Check {
+            against,
+            fail_on_increase,
+            pattern,
+            paths,
+            directory,
+        } => {
+            if check::check(&against, &pattern, &paths.pathspecs(), &directory)? && fail_on_increase
+            {
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+
+        Commands::Explain {
+            target,
+            pattern,
+            directory,
+        } => explain::explain(&target, &pattern, &directory)?,
+
+        Commands::Last { directory, output } => {
+            let started = Instant::now();
+            let run = last::load(&directory)?;
+            // The saved paths are relative to where that run was
+            std::env::set_current_dir(&run.cwd)
+                .with_context(|| tr!("Failed to enter {}", run.cwd.display()))?;
+            let (pattern, context) = (run.pattern.clone(), run.context);
+            let matches = run.matches();
+            print_current(
+                &matches,
+                &pattern,
+                context,
+                &MatchAnnotations::default(),
+                0,
+                &output,
+                started,
+            )?;
+        }
+
+        Commands::Open { number, directory } => last::open(number, &directory)?,
+
+        Commands::Assign {
+            targets,
+            to,
+            auto: _,
+            pattern,
+            directory,
+            write,
+            commit,
+        } => {
+            if let (Some(to), true) = (&to, targets.is_empty()) {
+                anyhow::bail!(tr!("Name the TODOs to assign to {}, or use --auto", to));
+            }
+            assign::assign(
+                &targets,
+                to.as_deref(),
+                &pattern,
+                &directory,
+                write.mode(),
+                commit,
+            )?
+        }
+
+        Commands::Compare {
+            old,
+            new,
+            fail_on_new,
+        } => {
+            if compare::compare(&old, &new)? && fail_on_new {
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+
+        Commands::At {
+            reference,
+            branches,
+            pattern,
+            paths,
+            directory,
+        } => match (reference, branches) {
+            (_, Some(glob)) => {
+                at::search_branches(&glob, &pattern, &paths.pathspecs(), &directory)?
+            }
+            (reference, None) => at::search_at(
+                &reference.unwrap_or_default(),
+                &pattern,
+                &paths.pathspecs(),
+                &directory,
+            )?,
+        },
+
+        Commands::Config { action } => match action {
+            ConfigAction::Check { directory } => {
+                if config_check::check(&directory)? {
+                    return Ok(ExitCode::FAILURE);
+                }
+            }
+        },
+
+        Commands::State { action } => match action {
+            StateAction::Path { directory } => state::print_path(&directory)?,
+            StateAction::Show { json, directory } => state::show(&directory, json)?,
+            StateAction::Prune { dry_run, directory } => state::prune(&directory, dry_run)?,
+        },
+
+        Commands::Stats {
+            resolution_time,
+            burndown,
+            by_author,
+            since,
+            until,
+            pattern,
+            directory,
+        } => stats::stats(
+            &pattern,
+            &directory,
+            resolution_time,
+            burndown,
+            by_author,
+            since,
+            until,
+        )?,
+
+        Commands::Metrics {
+            listen,
+            textfile,
+            pattern,
+            file_type,
+            directory,
+        } => {
+            let options = metrics::MetricsOptions {
+                patterns: pattern,
+                file_type,
+                directory,
+            };
+            match textfile {
+                Some(path) => metrics::write_textfile(&path, &options)?,
+                None => metrics::serve_metrics(listen.as_deref().unwrap_or(":9300"), &options)?,
+            }
+        }
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Which of the current matches `current` shows
+struct MatchFilters {
+    show_snoozed: bool,
+    untracked: bool,
+    /// Search the other worktrees too
+    worktrees: bool,
+    /// `--files-from`: a list of the only files to report matches in
+    files_from: Option<PathBuf>,
+    /// What to do with matches in string literals
+    strings: syntax::Strings,
+    /// `--where` conditions, all of which must hold
+    metadata: Vec<metadata::Filter>,
+    lines: LineFilters,
+    /// `fask query`'s expression
+    query: Option<query::Query>,
+}
+
+/// What `current` notes next to each match, from git blame
+struct MatchNotes {
+    /// Who last touched the line and when
+    age: bool,
+    /// Who could own a TODO without an owner tag
+    suggested_owner: bool,
+}
+
+fn search_current_files(
+    pattern: &str,
+    context: ContextLines,
+    file_type: Option<String>,
+    directory: PathBuf,
+    filters: &MatchFilters,
+    notes: &MatchNotes,
+    output: &OutputArgs,
+) -> Result<()> {
+    let started = Instant::now();
+
+    let search = timing::start(search::phase_name());
+    let mut matches = match &filters.files_from {
+        Some(source) => {
+            let files = search::read_file_list(source, &directory)?;
+            collect_listed_matches(pattern, file_type.as_deref(), &files, &directory)?
+        }
+        None => collect_current_matches(pattern, file_type.as_deref(), &directory)?,
+    };
+    let worktrees = match filters.worktrees {
+        true => worktree::others(&directory)?,
+        false => Vec::new(),
+    };
+    if !worktrees.is_empty() {
+        // The same directory in each of them, e.g. "src" in every checkout
+        let root = state::repo_root(&directory);
+        let within = directory
+            .canonicalize()
+            .ok()
+            .and_then(|d| {
+                let root = root.canonicalize().ok()?;
+                d.strip_prefix(root).ok().map(Path::to_path_buf)
+            })
+            .unwrap_or_default();
+        for worktree in &worktrees {
+            let searched = worktree.path.join(&within);
+            if !searched.is_dir() {
+                continue;
+            }
+            let mut found = collect_current_matches(pattern, file_type.as_deref(), &searched)?;
+            tracing::debug!(worktree = %worktree.path.display(), matches = found.len(), "searched worktree");
+            for m in &mut found {
+                m.worktree = Some(worktree.clone());
+            }
+            matches.extend(found);
+        }
+    }
+    drop(search);
+
+    matches.retain(|m| filters.metadata.iter().all(|f| f.matches(&m.metadata)));
+    matches.retain(|m| filters.strings.keeps(m.kind));
+    matches.retain(|m| filters.lines.keeps(&m.line_content));
+    if let Some(query) = &filters.query {
+        let today = Local::now().date_naive();
+        let blamed: HashMap<(String, usize), blame::BlameInfo> = if query.uses_blame() {
+            let _blame = timing::start("blame");
+            blame_matches(&matches)
+                .into_iter()
+                .map(|((file, n), info)| ((file.to_string(), n), info))
+                .collect()
+        } else {
+            HashMap::new()
+        };
+        let root = state::repo_root(&directory);
+        matches.retain(|m| {
+            let keyword = m.keyword();
+            let info = blamed.get(&(m.file.clone(), m.line_number));
+            query.matches(&query::Subject {
+                keyword,
+                owner: annotate::owner(&m.line_content, keyword),
+                path: &m.relative_file(&root),
+                line: &m.line_content,
+                author: info
+                    .map(|info| info.author.as_str())
+                    .filter(|&author| author != blame::NOT_COMMITTED),
+                age_days: info.map(|info| (today - info.date).num_days()),
+                metadata: &m.metadata,
+            })
+        });
+    }
+
+    if !filters.untracked {
+        let root = state::repo_root(&directory);
+        let mut tracked = HashMap::new();
+        for tree in std::iter::once(root.clone()).chain(worktrees.iter().map(|w| w.path.clone())) {
+            let files = tracked_files(&tree)?;
+            tracked.insert(tree, files);
+        }
+        let before = matches.len();
+        matches.retain(|m| {
+            tracked
+                .get(m.root(&root))
+                .is_some_and(|files| files.contains(&m.relative_file(&root)))
+        });
+        tracing::debug!(dropped = before - matches.len(), "dropped untracked files");
+    }
+
+    let state = state::State::load(&directory)?;
+    if !state.bookmarks.is_empty() || !state.labels.is_empty() {
+        for m in &mut matches {
+            let id = m.id(&state.root);
+            m.bookmarked = state.bookmarks.contains_key(&id);
+            m.labels = state.labels_of(&id);
+        }
+    }
+
+    // Snoozed TODOs stay out of the way until their date passes
+    let mut hidden = 0;
+    if !filters.show_snoozed {
+        let snoozed = state.snoozed_ids(Local::now().date_naive());
+        let before = matches.len();
+        matches.retain(|m| !snoozed.contains(&m.id(&state.root)));
+        hidden = before - matches.len();
+        tracing::debug!(hidden, "dropped snoozed matches");
+    }
+
+    // ripgrep's threads finish files in any order
+    if output.stable {
+        matches.sort_by(|a, b| {
+            (&a.file, a.line_number, a.column).cmp(&(&b.file, b.line_number, b.column))
+        });
+    }
+
+    if notes.suggested_owner && !output.files_with_matches {
+        let _blame = timing::start("suggest owners");
+        suggest_owners(&mut matches);
+    }
+
+    // Only the text output has room for the note; the table has a column for it
+    let ages = if (notes.age && output.format == OutputFormat::Text
+        || output.format == OutputFormat::Table)
+        && !output.files_with_matches
+    {
+        let _blame = timing::start("blame");
+        blame_matches(&matches)
+    } else {
+        HashMap::new()
+    };
+    let annotations = MatchAnnotations {
+        ages,
+        strings: filters.strings == syntax::Strings::Label,
+    };
+
+    // For `fask last` and `fask open N`; failing to remember isn't worth failing the search over
+    if let Err(e) = last::save(&directory, pattern, context, &matches) {
+        tracing::debug!("failed to save the results: {:#}", e);
+    }
+
+    let _rendering = timing::start("rendering");
+    print_current(
+        &matches,
+        pattern,
+        context,
+        &annotations,
+        hidden,
+        output,
+        started,
+    )
+}
+
+/// Notes `current` puts next to matches in the text output
+#[derive(Default)]
+struct MatchAnnotations<'a> {
+    /// Who last touched each match and when, by (file, line number)
+    ages: HashMap<(&'a str, usize), blame::BlameInfo>,
+    /// Whether to note the matches in string literals (--strings label)
+    strings: bool,
+}
+
+/// Print the results of `current` in the chosen format, noting how many snoozed ones it hid
+fn print_current(
+    matches: &[CurrentMatch],
+    pattern: &str,
+    context: ContextLines,
+    annotations: &MatchAnnotations,
+    hidden: usize,
+    output: &OutputArgs,
+    started: Instant,
+) -> Result<()> {
+    if output.files_with_matches {
+        print_file_list(matches.iter().map(|m| m.file.as_str()), output.null);
+        return Ok(());
+    }
+
+    match output.format {
+        OutputFormat::Text => {}
+        OutputFormat::Picker => {
+            print_picker_records(
+                matches
+                    .iter()
+                    .map(|m| (m.file.as_str(), m.line_number, m.line_content.as_str())),
+                output.null,
+            );
+            return Ok(());
+        }
+        OutputFormat::RgJson => {
+            // Paths from ripgrep already include the search directory
+            let json_matches: Vec<rg_json::JsonMatch> = matches
+                .iter()
+                .map(|m| rg_json::JsonMatch {
+                    file: &m.file,
+                    line_number: m.line_number,
+                    spans: m.spans.clone(),
+                })
+                .collect();
+            rg_json::print_matches(&json_matches, context, Path::new(""), started.elapsed());
+            return Ok(());
+        }
+        OutputFormat::Json => {
+            for m in matches {
+                println!("{}", serde_json::to_string(m)?);
+            }
+            return Ok(());
+        }
+        OutputFormat::Table => {
+            let rows: Vec<table::Row> = matches
+                .iter()
+                .map(|m| table::Row {
+                    file: &m.file,
+                    line_number: m.line_number,
+                    // An open Markdown task counts as a TODO
+                    keyword: match markdown::is_task_in(&m.file, &m.line_content) {
+                        true => "TODO",
+                        false => m
+                            .spans
+                            .first()
+                            .and_then(|&(start, end)| m.line_content.get(start..end))
+                            .unwrap_or(pattern),
+                    },
+                    owner: annotate::owner(&m.line_content, pattern)
+                        .or(m.suggested_owner.as_deref()),
+                    age: annotations
+                        .ages
+                        .get(&(m.file.as_str(), m.line_number))
+                        .map(|info| match info.author == blame::NOT_COMMITTED {
+                            true => tr!("not committed"),
+                            false => dates::shown(info.date),
+                        }),
+                    text: &m.line_content,
+                })
+                .collect();
+            table::print(&rows);
+            return Ok(());
+        }
+        OutputFormat::Vscode | OutputFormat::VscodeJson => {
+            let problems: Vec<vscode::Problem> = matches
+                .iter()
+                .map(|m| vscode::Problem {
+                    file: &m.file,
+                    line_number: m.line_number,
+                    line: &m.line_content,
+                    span: m.spans.first().copied().unwrap_or_default(),
+                    pattern,
+                })
+                .collect();
+            return vscode::print(
+                &problems,
+                Path::new(""),
+                output.format == OutputFormat::VscodeJson,
+            );
+        }
+    }
+
+    // -o output is meant for `sort | uniq -c` and the like, so it gets no banner either
+    if !output.only_matching {
+        println!(
+            "{}\n",
+            tr!("Searching for '{}' in current files...", pattern)
+        );
+    }
+
+    if matches.is_empty() {
+        if !output.only_matching {
+            println!("{}", tr!("No matches found."));
+        }
+    } else {
+        if output.only_matching {
+            print_only_matching(
+                matches.iter().map(|m| {
+                    (
+                        m.file.as_str(),
+                        m.line_number,
+                        m.line_content.as_str(),
+                        &m.spans[..],
+                    )
+                }),
+                Path::new(""),
+                output.heading(false),
+            );
+        } else {
+            print_current_matches(matches, context, annotations, output);
+        }
+    }
+
+    if hidden > 0 {
+        let note = tr!("{} snoozed match(es) hidden (use --show-snoozed).", hidden);
+        if output.only_matching {
+            eprintln!("{}", note);
+        } else {
+            println!("\n{}", note);
+        }
+    }
+
+    Ok(())
+}
+
+/// Wrap each span of the pattern in ripgrep's match color. `offset` is where `text`
+/// starts within the line the spans refer to.
+fn highlight(text: &str, spans: &[(usize, usize)], offset: usize) -> String {
+    let mut out = String::new();
+    let mut pos = 0;
+    for &(start, end) in spans {
+        let start = start.saturating_sub(offset).clamp(pos, text.len());
+        let end = end.saturating_sub(offset).clamp(start, text.len());
+        if start == end {
+            continue;
+        }
+        out.push_str(&text[pos..start]);
+        out.push_str(&style::paint(Element::Match, &text[start..end]));
+        pos = end;
+    }
+    out.push_str(&text[pos..]);
+    out
+}
+
+/// Fit a matching line to the terminal and highlight the pattern in each piece
+fn render_match_line(
+    line: &str,
+    spans: &[(usize, usize)],
+    width: Option<usize>,
+    long_lines: LongLines,
+) -> Vec<String> {
+    let pieces = fit_line(line, width, long_lines);
+
+    // A truncated line is a prefix of the original plus the ellipsis
+    if long_lines == LongLines::Truncate && pieces.len() == 1 && pieces[0] != line {
+        let ellipsis = style::symbol("…", "...");
+        let body = pieces[0].strip_suffix(ellipsis).unwrap_or(&pieces[0]);
+        return vec![format!("{}{}", highlight(body, spans, 0), ellipsis)];
+    }
+
+    let mut offset = 0;
+    pieces
+        .iter()
+        .map(|piece| {
+            let rendered = highlight(piece, spans, offset);
+            offset += piece.len();
+            rendered
+        })
+        .collect()
+}
+
+/// Blame every match, one git call per file, keyed by (file, line number)
+fn blame_matches(matches: &[CurrentMatch]) -> HashMap<(&str, usize), blame::BlameInfo> {
+    let mut by_file: HashMap<&str, Vec<usize>> = HashMap::new();
+    for m in matches {
+        by_file.entry(&m.file).or_default().push(m.line_number);
+    }
+    by_file
+        .into_par_iter()
+        .flat_map_iter(|(file, line_numbers)| {
+            blame::blame_lines(file, &line_numbers)
+                .into_iter()
+                .map(move |(n, info)| ((file, n), info))
+        })
+        .collect()
+}
+
+/// Fill in `suggested_owner` for the matches without an owner tag, one git blame per file
+fn suggest_owners(matches: &mut [CurrentMatch]) {
+    let mut by_file: HashMap<&str, Vec<usize>> = HashMap::new();
+    for m in matches.iter() {
+        if annotate::owner(&m.line_content, m.keyword()).is_none() {
+            by_file.entry(&m.file).or_default().push(m.line_number);
+        }
+    }
+    let suggestions: HashMap<(String, usize), String> = by_file
+        .into_par_iter()
+        .flat_map_iter(|(file, line_numbers)| {
+            assign::suggest_owners(file, &line_numbers)
+                .into_iter()
+                .map(move |(n, owner)| ((file.to_string(), n), owner))
+        })
+        .collect();
+    for m in matches {
+        m.suggested_owner = suggestions.get(&(m.file.clone(), m.line_number)).cloned();
+    }
+}
+
+/// " (last touched by X, N months ago)" for a blamed line, dimmed
+fn age_note(info: &blame::BlameInfo) -> String {
+    if info.author == blame::NOT_COMMITTED {
+        return format!(
+            " {}",
+            style::paint(Element::Context, tr!("(not committed yet)"))
+        );
+    }
+    format!(
+        " {}",
+        style::paint(
+            Element::Context,
+            format!(
+                "(last touched by {}, {})",
+                info.author,
+                blame::ago(info.date, Local::now().date_naive())
+            )
+        )
+    )
+}
+
+/// Print current matches the way ripgrep does: "path:line:column:text" for matches and
+/// "path-line-text" for context lines, or the path once as a heading with `--heading`.
+/// Matches get notes on who last touched them, who could own them and, with `--strings
+/// label`, whether they are in a string. On a terminal each match is numbered, for
+/// `fask open N`.
+fn print_current_matches(
+    matches: &[CurrentMatch],
+    context: ContextLines,
+    annotations: &MatchAnnotations,
+    output: &OutputArgs,
+) {
+    let heading = output.heading(false);
+    let terminal_width = terminal_width();
+    let number_width = if std::io::stdout().is_terminal() && !style::plain() {
+        matches.len().to_string().len() + 1
+    } else {
+        0
+    };
+
+    // Group by file, keeping ripgrep's file order
+    let mut files: Vec<(&str, Vec<(usize, &CurrentMatch)>)> = Vec::new();
+    for (idx, m) in matches.iter().enumerate() {
+        match files.last_mut() {
+            Some((file, file_matches)) if *file == m.file => file_matches.push((idx + 1, m)),
+            _ => files.push((&m.file, vec![(idx + 1, m)])),
+        }
+    }
+
+    for (file_idx, (file, file_matches)) in files.iter().enumerate() {
+        // Paths from ripgrep already include the search directory
+        let mut lines = read_file_lines(file, Path::new("")).unwrap_or_default();
+        if notebook::is_notebook(file) {
+            lines = notebook::display_lines(lines);
+        } else if lfs::is_pointer(Path::new(file)) {
+            // Matches came from the content behind it; the stub is no context
+            lines.clear();
+        }
+
+        // Every line to print, by line and then match number: a match, or None for a context
+        // line. A compact notebook has all its cells, and so all its matches, on one line.
+        let match_lines: std::collections::HashSet<usize> =
+            file_matches.iter().map(|(_, m)| m.line_number).collect();
+        let mut printed: std::collections::BTreeMap<
+            (usize, usize),
+            Option<(usize, &CurrentMatch)>,
+        > = std::collections::BTreeMap::new();
+        for &(number, m) in file_matches {
+            let (start, end) = context.around(m.line_number, lines.len());
+            for i in (start..=end).filter(|i| !match_lines.contains(i)) {
+                printed.entry((i, 0)).or_insert(None);
+            }
+            printed.insert((m.line_number, number), Some((number, m)));
+        }
+
+        if heading {
+            if file_idx > 0 {
+                println!();
+            }
+            let terminator = if output.null { "\0" } else { "" };
+            println!(
+                "{}{}",
+                style::paint(Element::Path, hyperlink::file(file, Path::new(""))),
+                terminator
+            );
+        } else if file_idx > 0 && !context.is_empty() {
+            println!("--");
+        }
+
+        let mut previous: Option<usize> = None;
+        for ((line_number, _), numbered) in printed {
+            if previous.is_some_and(|p| line_number > p + 1) {
+                println!("--");
+            }
+            previous = Some(line_number);
+
+            let m = numbered.map(|(_, m)| m);
+            let number = match numbered {
+                Some((n, _)) if number_width > 0 => {
+                    format!(
+                        "{} ",
+                        style::paint(
+                            Element::Context,
+                            format!("{:>width$}", n, width = number_width - 1)
+                        )
+                    )
+                }
+                _ => " ".repeat(number_width),
+            };
+
+            let text = match m {
+                Some(m) => m.line_content.as_str(),
+                None => lines.get(line_number - 1).map_or("", |l| l.as_str()),
+            };
+
+            // "path:" (or "path\0" with --null) when the path is not a heading
+            let separator = if m.is_some() { ':' } else { '-' };
+            let path_prefix = if heading {
+                String::new()
+            } else if output.null {
+                format!(
+                    "{}\0",
+                    style::paint(Element::Path, hyperlink::file(file, Path::new("")))
+                )
+            } else {
+                format!(
+                    "{}{}",
+                    style::paint(Element::Path, hyperlink::file(file, Path::new(""))),
+                    separator
+                )
+            };
+            let location = match m {
+                Some(m) => format!(
+                    "{}:{}:",
+                    style::paint(Element::LineNumber, line_number),
+                    m.column
+                ),
+                None => format!("{}-", style::paint(Element::LineNumber, line_number)),
+            };
+
+            let prefix_width = number_width
+                + if heading { 0 } else { file.len() + 1 }
+                + line_number.to_string().len()
+                + m.map_or(1, |m| m.column.to_string().len() + 2);
+            let content_width = terminal_width.map(|w| w.saturating_sub(prefix_width));
+
+            let (width, long_lines) = output.fit(text, content_width, output.long_lines);
+            let pieces = match m {
+                Some(m) => render_match_line(text, &m.spans, width, long_lines),
+                None => fit_line(text, width, long_lines),
+            };
+            let mut note = m
+                .and_then(|m| annotations.ages.get(&(m.file.as_str(), line_number)))
+                .map(age_note)
+                .unwrap_or_default();
+            if let Some(owner) = m.and_then(|m| m.suggested_owner.as_ref()) {
+                note.push_str(&format!(
+                    " {}",
+                    style::paint(Element::Context, tr!("(suggested owner: {})", owner))
+                ));
+            }
+            if annotations.strings && m.is_some_and(|m| m.kind == syntax::Kind::String) {
+                note.push_str(&format!(
+                    " {}",
+                    style::paint(Element::Warning, tr!("(in a string)"))
+                ));
+            }
+            if let Some(cell) = m.and_then(|m| m.cell) {
+                note.push_str(&format!(
+                    " {}",
+                    style::paint(
+                        Element::Context,
+                        tr!("(cell {}, line {})", cell.index, cell.line)
+                    )
+                ));
+            }
+            if let Some(m) = m.filter(|m| !m.labels.is_empty()) {
+                note.push_str(&format!(
+                    " {}",
+                    style::paint(Element::Label, tr!("(labels: {})", m.labels.join(", ")))
+                ));
+            }
+            if let Some(worktree) = m.and_then(|m| m.worktree.as_ref()) {
+                note.push_str(&format!(
+                    " {}",
+                    style::paint(Element::Label, tr!("(worktree: {})", worktree.name()))
+                ));
+            }
+            if m.is_some_and(|m| m.bookmarked) {
+                note.push_str(&format!(
+                    " {}",
+                    style::paint(
+                        Element::Warning,
+                        tr!("{}bookmarked", style::symbol("★ ", ""))
+                    )
+                ));
+            }
+            let last = pieces.len() - 1;
+            for (piece_idx, piece) in pieces.iter().enumerate() {
+                let note = if piece_idx == last { note.as_str() } else { "" };
+                if piece_idx == 0 {
+                    println!("{}{}{}{}{}", number, path_prefix, location, piece, note);
+                } else {
+                    // Wrapped continuations line up under the text
+                    println!("{:width$}{}{}", "", piece, note, width = prefix_width);
+                }
+            }
+        }
+    }
+}
+
+/// Represents a match found in the current files
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+struct CurrentMatch {
+    file: String,
+    line_number: usize,
+    column: usize,
+    line_content: String,
+    /// Byte ranges of the pattern within `line_content`
+    #[serde(skip)]
+    spans: Vec<(usize, usize)>,
+    /// Fields of a `[key=value, ...]` block after the first match
+    #[serde(default, skip_serializing_if = "metadata::Metadata::is_empty")]
+    metadata: metadata::Metadata,
+    /// What the pattern's named groups captured in the first match, e.g. "owner" for
+    /// `TODO\((?P<owner>\w+)\)`
+    #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+    captures: serde_json::Map<String, serde_json::Value>,
+    /// Who to assign an unowned TODO to, with `--suggest-owner`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    suggested_owner: Option<String>,
+    /// Pinned with `fask bookmark`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    bookmarked: bool,
+    /// Given with `fask label`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    labels: Vec<String>,
+    /// Cell and line within it, in a Jupyter notebook (`line_number` is the file's line)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    cell: Option<notebook::Cell>,
+    /// Whether the first match is in a comment or in a string literal
+    #[serde(default)]
+    kind: syntax::Kind,
+    /// The other worktree the match is in, with `--worktrees`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    worktree: Option<worktree::Worktree>,
+}
+
+/// Split a string into arguments the way a shell would: on whitespace, except inside
+/// single or double quotes, with backslash escaping the next character
+fn shell_words(text: &str) -> Result<Vec<String>> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut quote: Option<char> = None;
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('\''), c) => word.get_or_insert_with(String::new).push(c),
+            (_, '\\') => {
+                let escaped = chars.next().with_context(|| tr!("Trailing backslash"))?;
+                word.get_or_insert_with(String::new).push(escaped);
+            }
+            (Some(_), c) => word.get_or_insert_with(String::new).push(c),
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                word.get_or_insert_with(String::new);
+            }
+            (None, c) if c.is_whitespace() => words.extend(word.take()),
+            (None, c) => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    if let Some(q) = quote {
+        anyhow::bail!(tr!("Unclosed {} quote", q));
+    }
+    words.extend(word);
+    Ok(words)
+}
+
+impl CurrentMatch {
+    /// Top of the working tree the match is in: `root`, or that of its other worktree
+    fn root<'a>(&'a self, root: &'a Path) -> &'a Path {
+        self.worktree.as_ref().map_or(root, |w| w.path.as_path())
+    }
+
+    /// File path relative to the repository root (of its worktree), the same form git
+    /// history uses
+    fn relative_file(&self, root: &Path) -> String {
+        let path = Path::new(&self.file);
+        let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        let root = self.root(root);
+        let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+        slash_path(
+            &canonical
+                .strip_prefix(&root)
+                .unwrap_or(path)
+                .to_string_lossy(),
+        )
+    }
+
+    fn id(&self, root: &Path) -> String {
+        todo_id(&self.relative_file(root), &self.line_content)
+    }
 
-        /// Directory to search in (default: current directory)
-        #[arg(short = 'D', long, default_value = ".")]
-        directory: PathBuf,
-    },
+    /// The text the pattern matched first, e.g. "FIXME" for `TODO|FIXME`, which the tag
+    /// and metadata follow
+    fn keyword(&self) -> &str {
+        self.spans
+            .first()
+            .and_then(|&(start, end)| self.line_content.get(start..end))
+            .unwrap_or_default()
+    }
 }
 
-fn main() -> Result<()> {
-    let cli = Cli::parse();
+/// The TODO an ID (as shown by `fask triage`) or a "file:line" location points at
+fn find_todo<'a>(
+    matches: &'a [CurrentMatch],
+    target: &str,
+    root: &Path,
+) -> Option<&'a CurrentMatch> {
+    if let Some((file, line)) = target.rsplit_once(':') {
+        if let Ok(line) = line.parse::<usize>() {
+            let file = file.strip_prefix("./").unwrap_or(file);
+            return matches.iter().find(|m| {
+                m.line_number == line
+                    && (m.file.strip_prefix("./").unwrap_or(&m.file) == file
+                        || m.relative_file(root) == file)
+            });
+        }
+    }
+    matches.iter().find(|m| m.id(root) == target)
+}
 
-    match cli.command {
-        Commands::Current {
-            pattern,
-            context,
-            file_type,
-            directory,
-        } => search_current_files(&pattern, context, file_type, directory)?,
+/// Paths of all files tracked by git, relative to the repository root
+fn tracked_files(root: &Path) -> Result<std::collections::HashSet<String>> {
+    let output = Command::new("git")
+        .arg("ls-files")
+        .arg("-z")
+        .current_dir(root)
+        .output()
+        .with_context(|| tr!("Failed to execute git ls-files"))?;
 
-        Commands::Since {
-            date,
-            pattern,
-            context,
-            directory,
-        } => search_since_date(&date, &pattern, context, directory)?,
+    if !output.status.success() {
+        anyhow::bail!(tr!("git ls-files failed. Is this a git repository?"));
     }
 
-    Ok(())
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .split('\0')
+        .filter(|f| !f.is_empty())
+        .map(String::from)
+        .collect())
 }
 
-fn search_current_files(
+/// Text of each named group of the match starting at `start`, in the pattern's order.
+/// Groups that didn't take part in the match are left out.
+fn captures(
+    re: &regex::Regex,
+    line: &str,
+    start: usize,
+) -> serde_json::Map<String, serde_json::Value> {
+    let Some(found) = re.captures_at(line, start) else {
+        return serde_json::Map::new();
+    };
+    re.capture_names()
+        .flatten()
+        .filter_map(|name| Some((name.to_string(), found.name(name)?.as_str().into())))
+        .collect()
+}
+
+/// Search the current files with the chosen backend
+fn collect_current_matches(
     pattern: &str,
-    context: usize,
-    file_type: Option<String>,
-    directory: PathBuf,
-) -> Result<()> {
-    println!("Searching for '{}' in current files...\n", pattern);
+    file_type: Option<&str>,
+    directory: &Path,
+) -> Result<Vec<CurrentMatch>> {
+    // A running `fask daemon` for the same search already has them
+    let matches = match daemon::indexed(pattern, file_type, directory) {
+        Some(matches) => matches,
+        None => search_matches(pattern, file_type, &[directory.to_path_buf()])?,
+    };
+    keep_labeled(matches, directory)
+}
 
-    let mut cmd = Command::new("rg");
-    cmd.arg(pattern)
-        .arg(format!("-C{}", context))
-        .arg("--color=always")
-        .arg("--line-number")
-        .arg("--column");
+/// Search just the listed `files` (relative to the current directory), ignored or not,
+/// leaving out the ones `file_type` doesn't match
+fn collect_listed_matches(
+    pattern: &str,
+    file_type: Option<&str>,
+    files: &[PathBuf],
+    directory: &Path,
+) -> Result<Vec<CurrentMatch>> {
+    let glob = file_type.map(search::type_glob);
+    let files: Vec<PathBuf> = files
+        .iter()
+        .filter(|file| {
+            let path = slash_path(&file.to_string_lossy());
+            glob.as_deref()
+                .is_none_or(|g| search::glob_matches(g, path.trim_start_matches("./")))
+        })
+        .cloned()
+        .collect();
+    // Without paths the backends would search the current directory
+    if files.is_empty() {
+        return Ok(Vec::new());
+    }
+    keep_labeled(search_matches(pattern, None, &files)?, directory)
+}
 
-    if let Some(ft) = file_type {
-        cmd.arg("-g").arg(ft);
+/// With --label, only the matches that have the labels
+fn keep_labeled(mut matches: Vec<CurrentMatch>, directory: &Path) -> Result<Vec<CurrentMatch>> {
+    let labels = required_labels();
+    if !labels.is_empty() {
+        let state = state::State::load(directory)?;
+        matches.retain(|m| state.has_labels(&m.id(&state.root), labels));
+        tracing::debug!(matches = matches.len(), "kept the labeled matches");
     }
+    Ok(matches)
+}
 
-    cmd.arg(directory);
+/// Search `paths` for the pattern: directories the way ripgrep does, files as they are.
+/// For a directory, this is what `fask daemon` keeps.
+fn search_matches(
+    pattern: &str,
+    file_type: Option<&str>,
+    paths: &[PathBuf],
+) -> Result<Vec<CurrentMatch>> {
+    // A `[types]` name stands for its globs
+    let file_type = file_type.map(search::type_glob);
+    let file_type = file_type.as_deref();
+    let mut hits = search::backend().search(pattern, file_type, paths)?;
+    // Checked once per file with hits, which is all a minified file or LFS pointer costs
+    let mut left_out: HashMap<String, bool> = HashMap::new();
+    hits.retain(|hit| {
+        !*left_out
+            .entry(hit.file.clone())
+            .or_insert_with(|| skipped::minified(&hit.file) || lfs::skip_pointer(&hit.file))
+    });
+    hits.extend(lfs::smudged_hits(pattern, file_type, paths)?);
+    let mut hits = notebook::search_cells(hits, pattern)?;
+    let mut seen: std::collections::HashSet<(String, usize)> = hits
+        .iter()
+        .map(|hit| (hit.file.clone(), hit.line_number))
+        .collect();
+    let tasks = markdown::tasks(pattern, file_type, paths)?;
+    if !tasks.is_empty() {
+        for task in tasks {
+            if seen.insert((task.file.clone(), task.line_number)) {
+                hits.push(task);
+            }
+        }
+        // Keep each file's matches together, in line order, where the file first came up
+        let mut first_seen: HashMap<String, usize> = HashMap::new();
+        for (idx, hit) in hits.iter().enumerate() {
+            first_seen.entry(hit.file.clone()).or_insert(idx);
+        }
+        hits.sort_by_key(|hit| (first_seen[&hit.file], hit.line_number));
+    }
 
-    let output = cmd
-        .output()
-        .context("Failed to execute ripgrep. Is 'rg' installed?")?;
+    // Backends don't report groups, so run the pattern again where it has named ones
+    let named_groups = regex::Regex::new(pattern)
+        .ok()
+        .filter(|re| re.capture_names().flatten().next().is_some());
 
-    if output.status.success() && !output.stdout.is_empty() {
-        print!("{}", String::from_utf8_lossy(&output.stdout));
-    } else {
-        println!("No matches found.");
-    }
+    let matches: Vec<CurrentMatch> = hits
+        .into_iter()
+        .map(|hit| {
+            let metadata = hit
+                .spans
+                .first()
+                .map(|&(_, end)| metadata::parse(&hit.line[end..]))
+                .unwrap_or_default();
+            let captures = match (&named_groups, hit.spans.first()) {
+                (Some(re), Some(&(start, _))) => captures(re, &hit.line, start),
+                _ => serde_json::Map::new(),
+            };
+            let kind = syntax::classify(
+                &hit.file,
+                &hit.line,
+                hit.spans.first().map_or(0, |&(start, _)| start),
+            );
+            tracing::trace!(file = hit.file, line = hit.line_number, "match");
+            CurrentMatch {
+                column: hit.spans.first().map_or(1, |&(start, _)| start + 1),
+                file: hit.file,
+                line_number: hit.line_number,
+                line_content: hit.line,
+                spans: hit.spans,
+                metadata,
+                captures,
+                suggested_owner: None,
+                bookmarked: false,
+                labels: Vec::new(),
+                kind,
+                cell: hit.cell,
+                worktree: None,
+            }
+        })
+        .collect();
 
-    Ok(())
+    tracing::debug!(
+        pattern,
+        paths = paths.len(),
+        matches = matches.len(),
+        "searched the current files"
+    );
+    Ok(matches)
 }
 
 /// Represents a match found in git history
@@ -119,6 +2827,8 @@ struct GitMatch {
     line_content: String,
     commit_date: NaiveDate,
     commit_hash: String,
+    /// False for a file a sparse checkout leaves out of the working tree, found in the index
+    materialized: bool,
 }
 
 /// Represents a line that was added in a commit (from diff parsing)
@@ -130,14 +2840,68 @@ struct AddedLine {
     commit_hash: String,
 }
 
+/// Call `f` with each line of the reader, decoded lossily and without its line ending.
+/// One buffer is reused throughout, so memory stays bounded by the longest line.
+fn for_each_line(mut reader: impl BufRead, mut f: impl FnMut(&str)) -> Result<()> {
+    let mut buf = Vec::new();
+    loop {
+        buf.clear();
+        if reader.read_until(b'\n', &mut buf)? == 0 {
+            return Ok(());
+        }
+        let line = String::from_utf8_lossy(&buf);
+        f(line.trim_end_matches('\n').trim_end_matches('\r'));
+    }
+}
+
+/// Run a `git log` command and parse its stdout as it arrives instead of collecting it all
+/// first; `git log -p` over a big history can print hundreds of megabytes.
+/// `input` is written to git's stdin first, e.g. revisions for `--stdin`.
+fn stream_git_log<T>(
+    cmd: &mut Command,
+    input: Option<&str>,
+    parse: impl FnOnce(&mut dyn BufRead) -> Result<T>,
+) -> Result<T> {
+    let mut child = cmd
+        .stdin(if input.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| tr!("Failed to execute git log"))?;
+    // git reads all of --stdin before printing anything, so this can't deadlock
+    if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+        stdin
+            .write_all(input.as_bytes())
+            .with_context(|| tr!("Failed to write to git log"))?;
+    }
+    let stdout = child
+        .stdout
+        .take()
+        .with_context(|| tr!("Failed to read git log output"))?;
+    let parsed = parse(&mut BufReader::new(stdout));
+
+    let status = child
+        .wait()
+        .with_context(|| tr!("Failed to wait for git log"))?;
+    if !status.success() {
+        anyhow::bail!(tr!("git log failed. Is this a git repository?"));
+    }
+    parsed
+}
+
 /// Parse git log -p output to find lines that were added containing the pattern
-fn parse_git_log_diff(output: &str, pattern: &str) -> Vec<AddedLine> {
+fn parse_git_log_diff(output: impl BufRead, pattern: &str) -> Result<Vec<AddedLine>> {
+    let tasks = markdown::counts_tasks(pattern);
     let mut results = Vec::new();
     let mut current_hash = String::new();
     let mut current_date: Option<NaiveDate> = None;
     let mut current_file: Option<String> = None;
 
-    for line in output.lines() {
+    for_each_line(output, |line| {
         // Commit line: "commit <hash>"
         if let Some(hash) = line.strip_prefix("commit ") {
             current_hash = hash.trim().to_string();
@@ -159,8 +2923,11 @@ fn parse_git_log_diff(output: &str, pattern: &str) -> Vec<AddedLine> {
         // Added line in diff (starts with + but not +++)
         else if line.starts_with('+') && !line.starts_with("+++") {
             let content = &line[1..]; // Remove the leading +
-            if content.contains(pattern) {
-                if let (Some(date), Some(file)) = (current_date, &current_file) {
+            if let (Some(date), Some(file)) = (current_date, &current_file) {
+                if unicode::contains(content, pattern)
+                    || (tasks && markdown::is_task_in(file, content))
+                {
+                    tracing::trace!(commit = %current_hash, file, content, "added line");
                     results.push(AddedLine {
                         file: file.clone(),
                         content: content.to_string(),
@@ -170,9 +2937,9 @@ fn parse_git_log_diff(output: &str, pattern: &str) -> Vec<AddedLine> {
                 }
             }
         }
-    }
+    })?;
 
-    results
+    Ok(results)
 }
 
 /// Find where an added line currently exists in a file (if it still exists)
@@ -184,90 +2951,247 @@ fn find_line_in_current_file(
     directory: &Path,
 ) -> Option<(usize, String)> {
     let file_path = directory.join(file);
-    let file_content = std::fs::read_to_string(&file_path).ok()?;
+    let file_content = skipped::read_to_string(&file_path)?;
+    find_line_in(&file_content, content, pattern)
+}
 
+/// Find an added line in the given file contents, as `find_line_in_current_file` does
+fn find_line_in(file_content: &str, content: &str, pattern: &str) -> Option<(usize, String)> {
     let content_trimmed = content.trim();
+    let content_folded = unicode::fold(content_trimmed);
+    let tasks = markdown::counts_tasks(pattern);
 
     for (idx, line) in file_content.lines().enumerate() {
         let line_trimmed = line.trim();
 
-        // The line must contain the pattern we're searching for
-        if !line.contains(pattern) {
+        // The line must contain the pattern we're searching for, or still be an open task;
+        // a checked one is resolved
+        let task = tasks && markdown::is_open_task(line);
+        if !task && !unicode::contains(line, pattern) {
             continue;
         }
 
         // Check if this line matches the added content
-        // Either exact match or the content is contained in the line (handles minor changes)
-        if line_trimmed == content_trimmed || line_trimmed.contains(content_trimmed) {
+        // Either exact match or the content is contained in the line (handles minor changes).
+        // Re-encoded or re-cased text (NFD on macOS, other case) still counts.
+        if line_trimmed == content_trimmed
+            || line_trimmed.contains(content_trimmed)
+            || unicode::fold(line_trimmed).contains(&content_folded)
+        {
             return Some((idx + 1, line.to_string())); // 1-based line number
         }
     }
     None
 }
 
-/// Read file contents to get context lines
-fn read_file_lines(file: &str, directory: &Path) -> Result<Vec<String>> {
-    let file_path = directory.join(file);
-    let content = std::fs::read_to_string(&file_path)
-        .with_context(|| format!("Failed to read file: {}", file_path.display()))?;
-    Ok(content.lines().map(|s| s.to_string()).collect())
+/// Read file contents to get context lines; unreadable files are noted as skipped
+fn read_file_lines(file: &str, directory: &Path) -> Option<Vec<String>> {
+    let content = skipped::read_to_string(&directory.join(file))?;
+    Some(content.lines().map(|s| s.to_string()).collect())
+}
+
+/// Width of the terminal stdout is attached to, if it is one
+fn terminal_width() -> Option<usize> {
+    // --plain keeps each line whole, as when piped
+    if style::plain() {
+        return None;
+    }
+    terminal_size::terminal_size_of(std::io::stdout())
+        .map(|(terminal_size::Width(w), _)| w as usize)
+}
+
+/// Split a line into the pieces that fit in `width` columns.
+/// Without a width (not a terminal) or with `LongLines::Off` the line is returned as is.
+fn fit_line(line: &str, width: Option<usize>, long_lines: LongLines) -> Vec<String> {
+    let width = match width {
+        Some(w) if w > 1 && long_lines != LongLines::Off => w,
+        _ => return vec![line.to_string()],
+    };
+
+    let chars: Vec<char> = line.chars().collect();
+    if chars.len() <= width {
+        return vec![line.to_string()];
+    }
+
+    match long_lines {
+        LongLines::Truncate => {
+            let ellipsis = style::symbol("…", "...");
+            let kept = width.saturating_sub(ellipsis.chars().count()).max(1);
+            let mut truncated: String = chars[..kept].iter().collect();
+            truncated.push_str(ellipsis);
+            vec![truncated]
+        }
+        _ => chars
+            .chunks(width)
+            .map(|chunk| chunk.iter().collect())
+            .collect(),
+    }
 }
 
-/// Print matches with context
+/// Print matches with context, in the order given
 fn print_matches_with_context(
     matches: &[GitMatch],
-    context: usize,
+    pattern: &str,
+    context: ContextLines,
     directory: &Path,
+    output: &OutputArgs,
 ) -> Result<()> {
-    // Sort all matches by date (oldest first)
-    let mut sorted_matches: Vec<&GitMatch> = matches.iter().collect();
-    sorted_matches.sort_by_key(|m| m.commit_date);
+    let terminal_width = terminal_width();
+    let heading = output.heading(true);
 
     let mut first_match = true;
-    for m in sorted_matches {
+    for m in matches {
         if !first_match {
-            println!();
+            if heading {
+                println!();
+            } else if !context.is_empty() {
+                // Same group separator ripgrep uses between context blocks
+                println!("--");
+            }
         }
         first_match = false;
 
-        let lines = match read_file_lines(&m.file, directory) {
-            Ok(l) => l,
-            Err(_) => {
+        // Files outside a sparse checkout have no lines to show around the match
+        let lines = match m.materialized {
+            true => read_file_lines(&m.file, directory),
+            false => None,
+        };
+        let lines = match lines {
+            Some(l) => l,
+            None => {
                 // Print basic info if we can't read the file
                 println!(
-                    "\x1b[35m{}\x1b[0m:\x1b[32m{}\x1b[0m: {} (added \x1b[36m{}\x1b[0m in \x1b[33m{}\x1b[0m)",
-                    m.file,
-                    m.line_number,
-                    m.line_content.trim(),
-                    m.commit_date,
-                    &m.commit_hash[..8.min(m.commit_hash.len())]
+                    "{}",
+                    tr!(
+                        "{}:{}: {} (added {} in {}){}",
+                        style::paint(Element::Path, hyperlink::file(&m.file, directory)),
+                        style::paint(Element::LineNumber, m.line_number),
+                        m.line_content.trim(),
+                        style::paint(Element::Date, dates::shown(m.commit_date)),
+                        style::paint(Element::Commit, short_commit(&m.commit_hash, directory)),
+                        match m.materialized {
+                            true => String::new(),
+                            false => format!(
+                                " {}",
+                                style::paint(Element::Context, tr!("(not materialized)"))
+                            ),
+                        }
+                    )
                 );
                 continue;
             }
         };
 
-        let start = m.line_number.saturating_sub(context).max(1);
-        let end = (m.line_number + context).min(lines.len());
+        let (start, end) = context.around(m.line_number, lines.len());
+
+        // Only the pattern is highlighted, like ripgrep does
+        let spans = lines
+            .get(m.line_number - 1)
+            .map(|line| unicode::spans(line, pattern))
+            .unwrap_or_default();
+        let column = spans.first().map_or(1, |&(start, _)| start + 1);
+
+        if !heading {
+            // "file:line:column: text" for the match and "file-line- text" for context, like rg
+            for i in start..=end {
+                if i > lines.len() {
+                    break;
+                }
+                // Wrapping would break the one-record-per-line layout, so only truncate
+                let mut prefix_width = m.file.len() + i.to_string().len() + 3;
+                if i == m.line_number {
+                    prefix_width += column.to_string().len() + 1;
+                }
+                let content_width = match output.long_lines {
+                    LongLines::Off => None,
+                    _ => terminal_width.map(|w| w.saturating_sub(prefix_width)),
+                };
+                let (content_width, _) =
+                    output.fit(&lines[i - 1], content_width, LongLines::Truncate);
+                if i == m.line_number {
+                    let piece = render_match_line(
+                        &lines[i - 1],
+                        &spans,
+                        content_width,
+                        LongLines::Truncate,
+                    )
+                    .remove(0);
+                    println!(
+                        "{}",
+                        tr!(
+                            "{}:{}:{}: {} (added {} in {})",
+                            style::paint(Element::Path, hyperlink::file(&m.file, directory)),
+                            style::paint(Element::LineNumber, i),
+                            column,
+                            piece,
+                            style::paint(Element::Date, dates::shown(m.commit_date)),
+                            style::paint(Element::Commit, short_commit(&m.commit_hash, directory))
+                        )
+                    );
+                } else {
+                    let piece =
+                        fit_line(&lines[i - 1], content_width, LongLines::Truncate).remove(0);
+                    println!(
+                        "{}",
+                        style::paint(Element::Context, format!("{}-{}- {}", m.file, i, piece))
+                    );
+                }
+            }
+            continue;
+        }
 
         // Print file header with commit info
         println!(
-            "\x1b[35m{}\x1b[0m (added \x1b[36m{}\x1b[0m in \x1b[33m{}\x1b[0m)",
-            m.file,
-            m.commit_date,
-            &m.commit_hash[..8.min(m.commit_hash.len())]
+            "{}",
+            tr!(
+                "{} (added {} in {})",
+                style::paint(Element::Path, hyperlink::file(&m.file, directory)),
+                style::paint(Element::Date, dates::shown(m.commit_date)),
+                style::paint(Element::Commit, short_commit(&m.commit_hash, directory))
+            )
         );
 
+        // Size the gutter for the largest line number so the block stays aligned
+        let number_width = end.to_string().len().max(4);
+        let gutter_width = number_width + 2; // "1234: "
+        let content_width = terminal_width.map(|w| w.saturating_sub(gutter_width));
+
         for i in start..=end {
             if i > lines.len() {
                 break;
             }
-            let line_content = &lines[i - 1];
-            if i == m.line_number {
-                // Highlight the matching line
-                println!("\x1b[32m{:>4}\x1b[0m: \x1b[1m{}\x1b[0m", i, line_content);
+            let (width, long_lines) = output.fit(&lines[i - 1], content_width, output.long_lines);
+            let pieces = if i == m.line_number {
+                render_match_line(&lines[i - 1], &spans, width, long_lines)
             } else {
-                // Context line
-                println!("\x1b[2m{:>4}: {}\x1b[0m", i, line_content);
+                fit_line(&lines[i - 1], width, long_lines)
+            };
+            for (piece_idx, piece) in pieces.iter().enumerate() {
+                // Wrapped continuations get a blank gutter
+                let number = if piece_idx == 0 {
+                    i.to_string()
+                } else {
+                    String::new()
+                };
+                if i == m.line_number {
+                    println!(
+                        "{}: {}",
+                        style::paint(
+                            Element::LineNumber,
+                            format!("{:>width$}", number, width = number_width)
+                        ),
+                        piece
+                    );
+                } else {
+                    // Context line
+                    println!(
+                        "{}",
+                        style::paint(
+                            Element::Context,
+                            format!("{:>width$}: {}", number, piece, width = number_width)
+                        )
+                    );
+                }
             }
         }
     }
@@ -275,86 +3199,708 @@ fn print_matches_with_context(
     Ok(())
 }
 
-fn search_since_date(date: &str, pattern: &str, context: usize, directory: PathBuf) -> Result<()> {
-    // Validate and parse date
-    let _since_date = NaiveDate::parse_from_str(date, "%Y-%m-%d")
-        .context("Invalid date format. Use YYYY-MM-DD (e.g., 2025-12-01)")?;
+/// Print the text of every span alone, as "path:line:column:text" (or "line:column:text"
+/// under a file heading) like `rg -o`. Nothing is colored unless stdout is a terminal, so
+/// it pipes cleanly into cut, sort and uniq. Paths are relative to `base`.
+fn print_only_matching<'a>(
+    matches: impl Iterator<Item = (&'a str, usize, &'a str, &'a [(usize, usize)])>,
+    base: &Path,
+    heading: bool,
+) {
+    let color = std::io::stdout().is_terminal();
+    let paint = |element: Element, text: String| match color {
+        true => style::paint(element, text),
+        false => text,
+    };
+    let mut previous_file: Option<&str> = None;
+    for (file, line_number, line, spans) in matches {
+        if heading && previous_file != Some(file) {
+            if previous_file.is_some() {
+                println!();
+            }
+            println!("{}", paint(Element::Path, hyperlink::file(file, base)));
+        }
+        previous_file = Some(file);
 
-    println!(
-        "Searching for '{}' in lines added since {}...\n",
-        pattern, date
+        for &(start, end) in spans {
+            let Some(text) = line.get(start..end) else {
+                continue;
+            };
+            if heading {
+                println!(
+                    "{}:{}:{}",
+                    paint(Element::LineNumber, line_number.to_string()),
+                    start + 1,
+                    text
+                );
+            } else {
+                println!(
+                    "{}:{}:{}:{}",
+                    paint(Element::Path, hyperlink::file(file, base)),
+                    paint(Element::LineNumber, line_number.to_string()),
+                    start + 1,
+                    text
+                );
+            }
+        }
+    }
+}
+
+/// Print one "file<TAB>line<TAB>text" record per match, with no color or banners
+fn print_picker_records<'a>(records: impl Iterator<Item = (&'a str, usize, &'a str)>, null: bool) {
+    let terminator = if null { '\0' } else { '\n' };
+    for (file, line_number, text) in records {
+        print!("{}\t{}\t{}{}", file, line_number, text, terminator);
+    }
+}
+
+/// Print each file name once, in the order first seen
+fn print_file_list<'a>(files: impl Iterator<Item = &'a str>, null: bool) {
+    let terminator = if null { '\0' } else { '\n' };
+    let mut seen = std::collections::HashSet::new();
+    for file in files {
+        if seen.insert(file) {
+            print!("{}{}", file, terminator);
+        }
+    }
+}
+
+/// `--git-args`, added to the git log calls that walk the history
+static GIT_LOG_ARGS: OnceLock<Vec<String>> = OnceLock::new();
+
+/// `--label`: the labels a TODO needs for any command to see it
+static LABELS: OnceLock<Vec<String>> = OnceLock::new();
+
+fn required_labels() -> &'static [String] {
+    LABELS.get().map_or(&[], Vec::as_slice)
+}
+
+fn git_log_args() -> &'static [String] {
+    GIT_LOG_ARGS.get().map_or(&[], Vec::as_slice)
+}
+
+/// Run `git log -S` over the repository and collect the added lines containing the pattern.
+/// `since` bounds the scan by date and `range` by revisions (e.g. "base..HEAD");
+/// with neither the whole history is scanned. Non-empty `pathspecs` limit it to those files.
+fn git_log_added_lines(
+    pattern: &str,
+    since: Option<&str>,
+    range: Option<&str>,
+    pathspecs: &[String],
+    directory: &Path,
+) -> Result<Vec<AddedLine>> {
+    // List the commits that add or remove the pattern first; -S (pickaxe) is optimized for this
+    let mut cmd = Command::new("git");
+    cmd.arg("log").args(git_log_args());
+
+    if let Some(date) = since {
+        cmd.arg(format!("--since={}", date));
+    }
+
+    if let Some(range) = range {
+        cmd.arg(range);
+    }
+
+    cmd.args(unicode::pickaxe_args(&markdown::history_pattern(pattern)))
+        .arg("--format=%H %ad")
+        .arg("--date=short")
+        .current_dir(directory);
+
+    if !pathspecs.is_empty() {
+        cmd.arg("--").args(pathspecs);
+    }
+
+    // "<hash> <date>" per commit, newest first
+    let git_log = timing::start("git log");
+    let commits: Vec<(String, NaiveDate)> = stream_git_log(&mut cmd, None, |output| {
+        let mut commits = Vec::new();
+        for_each_line(output, |line| {
+            if let Some((hash, date)) = line.split_once(' ') {
+                if let Ok(date) = NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+                    commits.push((hash.to_string(), date));
+                }
+            }
+        })?;
+        Ok(commits)
+    })?;
+
+    drop(git_log);
+    tracing::debug!(
+        commits = commits.len(),
+        "listed commits changing '{}'",
+        pattern
+    );
+
+    // Only the diffs of commits not seen in an earlier run need parsing
+    let _parsing = timing::start("diff parsing");
+    let root = state::repo_root(directory);
+    let mut cache = cache::DiffCache::load(&root, directory, pattern, pathspecs);
+    let missing: Vec<&(String, NaiveDate)> = commits
+        .iter()
+        .filter(|(hash, _)| !cache.commits.contains_key(hash))
+        .collect();
+    tracing::debug!(
+        cached = commits.len() - missing.len(),
+        to_parse = missing.len(),
+        cache = %cache.path().display(),
+        "diff cache"
     );
 
-    // Use git log -S with -p to get the actual diffs
-    // This is fast because -S (pickaxe) is optimized, and we get exact info about what was added
+    if !missing.is_empty() {
+        let mut cmd = Command::new("git");
+        cmd.arg("log")
+            .arg("--no-walk=unsorted")
+            .arg("--stdin") // the list can be longer than a command line allows
+            .args(unicode::pickaxe_args(&markdown::history_pattern(pattern)))
+            .arg("-p") // Show patches (diffs)
+            .arg("--format=commit %H%nDate: %ad")
+            .arg("--date=short")
+            .arg("--diff-filter=AM") // Only additions and modifications
+            .current_dir(directory);
+
+        if !pathspecs.is_empty() {
+            cmd.arg("--").args(pathspecs);
+        }
+
+        let input: String = missing
+            .iter()
+            .map(|(hash, _)| format!("{}\n", hash))
+            .collect();
+        // Parse the diff output to find lines that were actually added
+        let parsed = stream_git_log(&mut cmd, Some(&input), |output| {
+            parse_git_log_diff(output, pattern)
+        })?;
+
+        let mut by_commit: HashMap<String, Vec<cache::CachedLine>> = HashMap::new();
+        for added in parsed {
+            by_commit
+                .entry(added.commit_hash)
+                .or_default()
+                .push(cache::CachedLine {
+                    file: added.file,
+                    content: added.content,
+                });
+        }
+
+        for (hash, date) in missing {
+            let lines = by_commit.remove(hash).unwrap_or_default();
+            tracing::debug!(commit = %hash, %date, added = lines.len(), "parsed diff");
+            cache
+                .commits
+                .insert(hash.clone(), cache::CachedCommit { date: *date, lines });
+        }
+        // The cache only saves time, so failing to write it isn't worth failing the search
+        if let Err(e) = cache.save() {
+            tracing::debug!("couldn't save the diff cache: {:#}", e);
+        }
+    }
+
+    Ok(commits
+        .iter()
+        .filter_map(|(hash, _)| Some((hash, cache.commits.get(hash)?)))
+        .flat_map(|(hash, commit)| {
+            commit.lines.iter().map(move |line| AddedLine {
+                file: line.file.clone(),
+                content: line.content.clone(),
+                commit_date: commit.date,
+                commit_hash: hash.clone(),
+            })
+        })
+        .collect())
+}
+
+/// A line of a commit message containing the pattern
+#[derive(Debug)]
+struct MessageMatch {
+    commit_hash: String,
+    commit_date: NaiveDate,
+    line: String,
+}
+
+/// Find commit messages since the date that mention the pattern, one match per message line
+fn git_log_message_matches(
+    pattern: &str,
+    history: &History,
+    directory: &Path,
+) -> Result<Vec<MessageMatch>> {
+    // Records are separated by \x1e and fields by \x1f, neither of which appears in messages
     let log_output = Command::new("git")
         .arg("log")
-        .arg(format!("--since={}", date))
-        .arg("-S")
-        .arg(pattern)
-        .arg("-p") // Show patches (diffs)
-        .arg("--format=commit %H%nDate: %ad")
+        .args(git_log_args())
+        .args(history.since().map(|date| format!("--since={}", date)))
+        .args(history.range())
+        .arg("--fixed-strings")
+        .args(unicode::alternatives(pattern).map(|p| format!("--grep={}", p)))
+        .arg("--format=%H%x1f%ad%x1f%B%x1e")
         .arg("--date=short")
-        .arg("--diff-filter=AM") // Only additions and modifications
-        .current_dir(&directory)
+        .current_dir(directory)
         .output()
-        .context("Failed to execute git log")?;
+        .with_context(|| tr!("Failed to execute git log"))?;
 
     if !log_output.status.success() {
-        anyhow::bail!("git log failed. Is this a git repository?");
+        anyhow::bail!(tr!("git log failed. Is this a git repository?"));
     }
 
     let output_str = String::from_utf8_lossy(&log_output.stdout);
+    let mut results = Vec::new();
+    for record in output_str.split('\x1e') {
+        let mut fields = record.trim_start_matches('\n').splitn(3, '\x1f');
+        let (Some(hash), Some(date), Some(body)) = (fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        let Ok(commit_date) = NaiveDate::parse_from_str(date, "%Y-%m-%d") else {
+            continue;
+        };
+        for line in body.lines().filter(|l| unicode::contains(l, pattern)) {
+            results.push(MessageMatch {
+                commit_hash: hash.to_string(),
+                commit_date,
+                line: line.trim().to_string(),
+            });
+        }
+    }
 
-    // Parse the diff output to find lines that were actually added
-    let added_lines = parse_git_log_diff(&output_str, pattern);
+    // Oldest first, like the code matches
+    results.sort_by_key(|m| m.commit_date);
+    Ok(results)
+}
 
-    if added_lines.is_empty() {
-        println!("No '{}' additions found since {}.", pattern, date);
-        return Ok(());
+/// First 8 characters of a commit hash, linked to the commit on the forge
+fn short_commit(hash: &str, directory: &Path) -> String {
+    hyperlink::commit(hash, &hash[..8.min(hash.len())], directory)
+}
+
+/// Print commit message matches under their own heading so they aren't mistaken for code
+fn print_message_matches(matches: &[MessageMatch], directory: &Path) {
+    println!(
+        "\n{}\n",
+        tr!("Found {} match(es) in commit messages:", matches.len())
+    );
+    for m in matches {
+        println!(
+            "{}",
+            tr!(
+                "{} (message, {}): {}",
+                style::paint(Element::Commit, short_commit(&m.commit_hash, directory)),
+                style::paint(Element::Date, dates::shown(m.commit_date)),
+                m.line
+            )
+        );
+    }
+}
+
+/// Which part of the history `since` searches
+enum History {
+    /// Commits after a date
+    Since(String),
+    /// Commits in a revision range, bounded by topology rather than dates, which is what
+    /// matters on a rebased branch
+    Range(String),
+}
+
+impl History {
+    /// From --date, --since-ref or --range, whichever was given. Refs are checked up front,
+    /// since a failing `git log` can't tell a bad ref from a missing repository.
+    fn new(
+        date: Option<NaiveDate>,
+        since_ref: Option<String>,
+        range: Option<String>,
+        directory: &Path,
+    ) -> Result<History> {
+        if let Some(reference) = since_ref {
+            at::resolve_commit(&reference, directory)?;
+            return Ok(History::Range(format!("{}..HEAD", reference)));
+        }
+        if let Some(range) = range {
+            let (from, to) = range
+                .split_once("...")
+                .or_else(|| range.split_once(".."))
+                .with_context(|| tr!("Range '{}' isn't of the form A..B", range))?;
+            for reference in [from, to] {
+                if !reference.is_empty() {
+                    at::resolve_commit(reference, directory)?;
+                }
+            }
+            return Ok(History::Range(range));
+        }
+        let date =
+            date.with_context(|| tr!("One of --date, --since-ref or --range is required"))?;
+        Ok(History::Since(date.to_string()))
+    }
+
+    fn since(&self) -> Option<&str> {
+        match self {
+            History::Since(date) => Some(date),
+            History::Range(_) => None,
+        }
+    }
+
+    fn range(&self) -> Option<&str> {
+        match self {
+            History::Since(_) => None,
+            History::Range(range) => Some(range),
+        }
+    }
+}
+
+impl std::fmt::Display for History {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            History::Since(text) | History::Range(text) => f.write_str(text),
+        }
+    }
+}
+
+/// Chart the additions in the searched history per week or month
+fn since_histogram(
+    history: &History,
+    pattern: &str,
+    directory: &Path,
+    bucket: histogram::Bucket,
+    paths: &PathFilters,
+    lines: &LineFilters,
+    output: &OutputArgs,
+) -> Result<()> {
+    let mut added_lines = git_log_added_lines(
+        pattern,
+        history.since(),
+        history.range(),
+        &paths.pathspecs(),
+        directory,
+    )?;
+    added_lines.retain(|a| lines.keeps(&a.content));
+    let counts = histogram::bucket_counts(added_lines.iter().map(|a| a.commit_date), bucket);
+    let json = output.format == OutputFormat::Json;
+    if counts.is_empty() && !json {
+        println!(
+            "{}",
+            tr!("No '{}' additions found since {}.", pattern, history)
+        );
+    }
+    histogram::print_histogram(&counts, json)
+}
+
+/// What `since` searches for and how it reports it
+struct SinceOptions<'a> {
+    history: &'a History,
+    pattern: &'a str,
+    context: ContextLines,
+    directory: PathBuf,
+    show_snoozed: bool,
+    include_messages: bool,
+    /// Text output under a header per week or month
+    group_by: Option<histogram::Bucket>,
+    /// Limits the history walk to matching files
+    pathspecs: Vec<String>,
+    lines: &'a LineFilters,
+    output: &'a OutputArgs,
+}
+
+fn search_since_date(options: SinceOptions) -> Result<()> {
+    let SinceOptions {
+        history,
+        pattern,
+        context,
+        directory,
+        show_snoozed,
+        include_messages,
+        group_by,
+        pathspecs,
+        lines,
+        output,
+    } = options;
+    let _span = tracing::debug_span!("since", %history, pattern).entered();
+
+    let started = Instant::now();
+
+    // Machine-readable output and -o are meant to be piped, so they get no banners or
+    // summaries
+    let text =
+        output.format == OutputFormat::Text && !output.files_with_matches && !output.only_matching;
+
+    if text {
+        println!(
+            "{}\n",
+            tr!(
+                "Searching for '{}' in lines added since {}...",
+                pattern,
+                history
+            )
+        );
+    }
+
+    let mut added_lines = git_log_added_lines(
+        pattern,
+        history.since(),
+        history.range(),
+        &pathspecs,
+        &directory,
+    )?;
+    added_lines.retain(|a| lines.keeps(&a.content));
+
+    // Commit messages are only reported in the text output, where they can be labeled
+    let message_matches = if include_messages && text {
+        git_log_message_matches(pattern, history, &directory)?
+    } else {
+        Vec::new()
+    };
+
+    if added_lines.is_empty() && text {
+        println!(
+            "{}",
+            tr!("No '{}' additions found since {}.", pattern, history)
+        );
+        if message_matches.is_empty() {
+            return Ok(());
+        }
     }
 
     // Now find where these lines currently exist in the files (if they still exist)
     // Process in parallel for speed
+    let relocation = timing::start("relocation");
+    // Files outside a sparse checkout's cone aren't gone, just not in the working tree
+    let unmaterialized =
+        sparse::unmaterialized(added_lines.iter().map(|a| a.file.as_str()), &directory)?;
     let all_matches: Vec<GitMatch> = added_lines
         .par_iter()
         .filter_map(|added| {
+            let _span = tracing::debug_span!("relocate", file = %added.file).entered();
+
             // Check if the file still exists and find the line
             let file_path = directory.join(&added.file);
-            if !file_path.exists() {
+            let staged = unmaterialized.get(&added.file);
+            if staged.is_none() && !file_path.exists() {
+                tracing::debug!(commit = %added.commit_hash, "file is gone");
                 return None;
             }
 
             // Find where this content is now in the file
-            find_line_in_current_file(&added.file, &added.content, pattern, &directory).map(
-                |(line_number, current_line)| GitMatch {
-                    file: added.file.clone(),
-                    line_number,
-                    line_content: current_line,
-                    commit_date: added.commit_date,
-                    commit_hash: added.commit_hash.clone(),
-                },
-            )
+            let found = match staged {
+                Some(staged) => find_line_in(staged, &added.content, pattern),
+                None => find_line_in_current_file(&added.file, &added.content, pattern, &directory),
+            };
+            match &found {
+                Some((line_number, _)) => tracing::trace!(line = line_number, "found"),
+                None => tracing::debug!(
+                    commit = %added.commit_hash,
+                    content = added.content.trim(),
+                    "added line is no longer in the file"
+                ),
+            }
+            found.map(|(line_number, current_line)| GitMatch {
+                file: added.file.clone(),
+                line_number,
+                line_content: current_line,
+                commit_date: added.commit_date,
+                commit_hash: added.commit_hash.clone(),
+                materialized: staged.is_none(),
+            })
         })
         .collect();
 
     // Deduplicate matches (same file + line number)
     let mut seen = std::collections::HashSet::new();
-    let unique_matches: Vec<GitMatch> = all_matches
+    let mut unique_matches: Vec<GitMatch> = all_matches
         .into_iter()
         .filter(|m| seen.insert((m.file.clone(), m.line_number)))
         .collect();
+    drop(relocation);
 
-    if unique_matches.is_empty() {
-        println!(
-            "No '{}' found in lines added since {} (lines may have been removed).",
-            pattern, date
-        );
+    let labels = required_labels();
+    if !labels.is_empty() {
+        let state = state::State::load(&directory)?;
+        unique_matches.retain(|m| state.has_labels(&todo_id(&m.file, &m.line_content), labels));
+    }
+
+    // Snoozed TODOs stay out of the way until their date passes
+    let mut hidden = 0;
+    if !show_snoozed {
+        let snoozed = state::State::load(&directory)?.snoozed_ids(Local::now().date_naive());
+        let before = unique_matches.len();
+        unique_matches.retain(|m| !snoozed.contains(&todo_id(&m.file, &m.line_content)));
+        hidden = before - unique_matches.len();
+        tracing::debug!(hidden, "dropped snoozed matches");
+    }
+
+    // Oldest first, or by location when the output has to be the same from run to run
+    if output.stable {
+        unique_matches.sort_by(|a, b| {
+            (&a.file, a.line_number, a.commit_date, &a.commit_hash).cmp(&(
+                &b.file,
+                b.line_number,
+                b.commit_date,
+                &b.commit_hash,
+            ))
+        });
+    } else {
+        unique_matches.sort_by_key(|m| m.commit_date);
+    }
+
+    let _rendering = timing::start("rendering");
+
+    if output.files_with_matches {
+        print_file_list(unique_matches.iter().map(|m| m.file.as_str()), output.null);
         return Ok(());
     }
 
-    println!("Found {} match(es):\n", unique_matches.len());
-    print_matches_with_context(&unique_matches, context, &directory)?;
+    match output.format {
+        OutputFormat::Text => {}
+        OutputFormat::Picker => {
+            print_picker_records(
+                unique_matches
+                    .iter()
+                    .map(|m| (m.file.as_str(), m.line_number, m.line_content.as_str())),
+                output.null,
+            );
+            return Ok(());
+        }
+        OutputFormat::RgJson => {
+            let json_matches: Vec<rg_json::JsonMatch> = unique_matches
+                .iter()
+                .map(|m| rg_json::JsonMatch {
+                    file: &m.file,
+                    line_number: m.line_number,
+                    spans: unicode::spans(&m.line_content, pattern),
+                })
+                .collect();
+            rg_json::print_matches(&json_matches, context, &directory, started.elapsed());
+            return Ok(());
+        }
+        OutputFormat::Json => {
+            for m in &unique_matches {
+                let mut record = serde_json::json!({
+                    "file": m.file,
+                    "line_number": m.line_number,
+                    "line_content": m.line_content,
+                    "commit_date": m.commit_date,
+                    "commit_age": dates::relative(m.commit_date),
+                    "commit_hash": m.commit_hash,
+                    "metadata": metadata::metadata(&m.line_content, pattern),
+                });
+                if !m.materialized {
+                    record["materialized"] = false.into();
+                }
+                println!("{}", record);
+            }
+            return Ok(());
+        }
+        OutputFormat::Table => {
+            let rows: Vec<table::Row> = unique_matches
+                .iter()
+                .map(|m| table::Row {
+                    file: &m.file,
+                    line_number: m.line_number,
+                    keyword: match markdown::is_task_in(&m.file, &m.line_content) {
+                        true => "TODO",
+                        false => unicode::spans(&m.line_content, pattern)
+                            .first()
+                            .and_then(|&(start, end)| m.line_content.get(start..end))
+                            .unwrap_or(pattern),
+                    },
+                    owner: annotate::owner(&m.line_content, pattern),
+                    age: Some(dates::shown(m.commit_date)),
+                    text: &m.line_content,
+                })
+                .collect();
+            table::print(&rows);
+            return Ok(());
+        }
+        OutputFormat::Vscode | OutputFormat::VscodeJson => {
+            let problems: Vec<vscode::Problem> = unique_matches
+                .iter()
+                .map(|m| vscode::Problem {
+                    file: &m.file,
+                    line_number: m.line_number,
+                    line: &m.line_content,
+                    span: unicode::spans(&m.line_content, pattern)
+                        .first()
+                        .copied()
+                        .unwrap_or_default(),
+                    pattern,
+                })
+                .collect();
+            return vscode::print(
+                &problems,
+                &directory,
+                output.format == OutputFormat::VscodeJson,
+            );
+        }
+    }
+
+    if unique_matches.is_empty() {
+        if !added_lines.is_empty() && text {
+            println!(
+                "{}",
+                tr!(
+                    "No '{}' found in lines added since {} (lines may have been removed).",
+                    pattern,
+                    history
+                )
+            );
+        }
+    } else {
+        if text {
+            println!("{}\n", tr!("Found {} match(es):", unique_matches.len()));
+        }
+        if output.only_matching {
+            let spans: Vec<Vec<(usize, usize)>> = unique_matches
+                .iter()
+                .map(|m| unicode::spans(&m.line_content, pattern))
+                .collect();
+            print_only_matching(
+                unique_matches.iter().zip(&spans).map(|(m, spans)| {
+                    (
+                        m.file.as_str(),
+                        m.line_number,
+                        m.line_content.as_str(),
+                        &spans[..],
+                    )
+                }),
+                &directory,
+                // Piped, like rg -o: one self-contained line per match
+                output.heading(std::io::stdout().is_terminal()),
+            );
+        } else if let Some(bucket) = group_by {
+            // Newest period first, each in the order the matches are listed otherwise
+            let mut groups: std::collections::BTreeMap<String, Vec<GitMatch>> =
+                std::collections::BTreeMap::new();
+            for m in &unique_matches {
+                groups
+                    .entry(bucket.of(m.commit_date))
+                    .or_default()
+                    .push(m.clone());
+            }
+            for (idx, (period, matches)) in groups.iter().rev().enumerate() {
+                if idx > 0 {
+                    println!();
+                }
+                println!(
+                    "{}\n",
+                    style::paint(
+                        Element::Heading,
+                        tr!("{} ({} match(es))", period, matches.len())
+                    )
+                );
+                print_matches_with_context(matches, pattern, context, &directory, output)?;
+            }
+        } else {
+            print_matches_with_context(&unique_matches, pattern, context, &directory, output)?;
+        }
+    }
+
+    if !message_matches.is_empty() {
+        print_message_matches(&message_matches, &directory);
+    }
+
+    if hidden > 0 {
+        let note = tr!("{} snoozed match(es) hidden (use --show-snoozed).", hidden);
+        if output.only_matching {
+            eprintln!("{}", note);
+        } else {
+            println!("\n{}", note);
+        }
+    }
 
     Ok(())
 }
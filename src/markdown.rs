@@ -0,0 +1,67 @@
+use anyhow::Result;
+use std::borrow::Cow;
+use std::path::PathBuf;
+
+use crate::{search, unicode};
+
+/// Open task-list items in Markdown, `- [ ] ...`; checked ones (`- [x]`) are done
+const OPEN_TASK: &str = r"^\s*[-*+] \[ \]";
+
+/// Whether a pattern finds TODO, so open Markdown tasks count as matches of it:
+/// "TODO", "todo" or a regex such as "TODO|FIXME"
+pub fn counts_tasks(pattern: &str) -> bool {
+    unicode::alternatives(pattern).any(|p| {
+        unicode::same(p, "TODO")
+            || regex::Regex::new(&format!("^(?:{})$", p)).is_ok_and(|re| re.is_match("TODO"))
+    })
+}
+
+/// Whether `line` is an open task-list item, the way `OPEN_TASK` finds them
+pub fn is_open_task(line: &str) -> bool {
+    line.trim_start()
+        .strip_prefix(['-', '*', '+'])
+        .is_some_and(|rest| rest.starts_with(" [ ]"))
+}
+
+/// Whether `line` of `file` is an open task of a Markdown file
+pub fn is_task_in(file: &str, line: &str) -> bool {
+    is_open_task(line) && search::glob_matches("*.md", file)
+}
+
+/// The text git's pickaxe looks for in the history: with tasks counted, "[ ]" as well,
+/// so checking an item off is a change to the pattern
+pub fn history_pattern(pattern: &str) -> Cow<'_, str> {
+    match counts_tasks(pattern) {
+        true => Cow::Owned(format!("{}\n[ ]", pattern)),
+        false => Cow::Borrowed(pattern),
+    }
+}
+
+/// Open Markdown tasks for a pattern that finds TODO, so docs share the backlog with the code.
+/// The match starts at the list marker, past any indentation.
+pub fn tasks(
+    pattern: &str,
+    file_type: Option<&str>,
+    paths: &[PathBuf],
+) -> Result<Vec<search::Hit>> {
+    if !counts_tasks(pattern) || file_type.is_some_and(|ft| !search::glob_matches(ft, "x.md")) {
+        return Ok(Vec::new());
+    }
+    // Listed files are searched whatever their name, so leave out the ones that aren't Markdown
+    let paths: Vec<PathBuf> = paths
+        .iter()
+        .filter(|p| p.is_dir() || search::glob_matches("*.md", &p.to_string_lossy()))
+        .cloned()
+        .collect();
+    if paths.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut hits = search::backend().search(OPEN_TASK, Some("*.md"), &paths)?;
+    for hit in &mut hits {
+        let indent = hit.line.len() - hit.line.trim_start().len();
+        for span in &mut hit.spans {
+            span.0 = span.0.max(indent);
+        }
+    }
+    Ok(hits)
+}
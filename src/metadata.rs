@@ -0,0 +1,164 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+use crate::i18n::tr;
+
+/// A metadata value, typed by what it looks like
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MetaValue {
+    Bool(bool),
+    Int(i64),
+    Date(NaiveDate),
+    Text(String),
+}
+
+/// Parsed `[key=value, ...]` fields of a TODO
+pub type Metadata = BTreeMap<String, MetaValue>;
+
+impl MetaValue {
+    /// "true"/"false", integers and YYYY-MM-DD dates get their own types; anything else is text
+    pub fn parse(raw: &str) -> MetaValue {
+        let raw = raw.trim();
+        if let Ok(b) = raw.parse() {
+            MetaValue::Bool(b)
+        } else if let Ok(n) = raw.parse() {
+            MetaValue::Int(n)
+        } else if let Ok(d) = NaiveDate::parse_from_str(raw, "%Y-%m-%d") {
+            MetaValue::Date(d)
+        } else {
+            MetaValue::Text(raw.to_string())
+        }
+    }
+
+    /// Values of the same type order naturally; anything else only compares equal
+    fn compare(&self, other: &MetaValue) -> Option<Ordering> {
+        match (self, other) {
+            (MetaValue::Int(a), MetaValue::Int(b)) => Some(a.cmp(b)),
+            (MetaValue::Date(a), MetaValue::Date(b)) => Some(a.cmp(b)),
+            (MetaValue::Text(a), MetaValue::Text(b)) => Some(a.cmp(b)),
+            (a, b) if a == b => Some(Ordering::Equal),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for MetaValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            MetaValue::Bool(b) => write!(f, "{}", b),
+            MetaValue::Int(n) => write!(f, "{}", n),
+            MetaValue::Date(d) => write!(f, "{}", d),
+            MetaValue::Text(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+/// The "(tag)" and "[key=value]" groups right after the keyword, in either order:
+/// "TODO(bob)[due=2025-06-01]:" -> (Some("bob"), Some("due=2025-06-01"))
+pub fn groups(rest: &str) -> (Option<&str>, Option<&str>) {
+    let (mut tag, mut block) = (None, None);
+    let mut rest = rest;
+    loop {
+        if let (None, Some(inside)) = (tag, rest.strip_prefix('(')) {
+            let Some((group, after)) = inside.split_once(')') else {
+                break;
+            };
+            tag = Some(group);
+            rest = after;
+        } else if let (None, Some(inside)) = (block, rest.strip_prefix('[')) {
+            let Some((group, after)) = inside.split_once(']') else {
+                break;
+            };
+            block = Some(group);
+            rest = after;
+        } else {
+            break;
+        }
+    }
+    (tag, block)
+}
+
+/// Raw "key=value" pairs of the metadata block after the keyword; a bare key means "true"
+pub fn fields(rest: &str) -> Vec<(&str, &str)> {
+    let Some(block) = groups(rest).1 else {
+        return Vec::new();
+    };
+    block
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(|item| match item.split_once('=') {
+            Some((key, value)) => (key.trim(), value.trim()),
+            None => (item, "true"),
+        })
+        .collect()
+}
+
+/// Typed metadata of the first occurrence of the pattern on the line
+pub fn metadata(line: &str, pattern: &str) -> Metadata {
+    match line.find(pattern) {
+        Some(idx) => parse(&line[idx + pattern.len()..]),
+        None => Metadata::new(),
+    }
+}
+
+/// Typed metadata from the text right after the keyword
+pub fn parse(rest: &str) -> Metadata {
+    fields(rest)
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), MetaValue::parse(value)))
+        .collect()
+}
+
+/// A `--where` condition on metadata: "key", "key=value", "key!=value", "key<value" or "key>value"
+#[derive(Debug, Clone)]
+pub struct Filter {
+    key: String,
+    condition: Option<(Ordering, bool, MetaValue)>,
+}
+
+impl Filter {
+    pub fn parse(s: &str) -> Result<Filter, String> {
+        // (operator, ordering it tests, whether the test is negated)
+        for (op, ordering, negated) in [
+            ("!=", Ordering::Equal, true),
+            ("=", Ordering::Equal, false),
+            ("<", Ordering::Less, false),
+            (">", Ordering::Greater, false),
+        ] {
+            if let Some((key, value)) = s.split_once(op) {
+                let key = key.trim();
+                if key.is_empty() {
+                    return Err(tr!("missing key in '{}'", s));
+                }
+                return Ok(Filter {
+                    key: key.to_string(),
+                    condition: Some((ordering, negated, MetaValue::parse(value))),
+                });
+            }
+        }
+        Ok(Filter {
+            key: s.trim().to_string(),
+            condition: None,
+        })
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub fn matches(&self, metadata: &Metadata) -> bool {
+        let value = metadata.get(&self.key);
+        match (&self.condition, value) {
+            (None, value) => value.is_some(),
+            (Some((_, true, _)), None) => true,
+            (Some(_), None) => false,
+            (Some((ordering, negated, expected)), Some(value)) => {
+                (value.compare(expected) == Some(*ordering)) != *negated
+            }
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+
+use crate::collect_current_matches;
+use crate::i18n::tr;
+use crate::server::{parse_listen_addr, read_request, write_response};
+
+/// What the exporter counts on every scrape
+pub struct MetricsOptions {
+    pub patterns: Vec<String>,
+    pub file_type: Option<String>,
+    pub directory: PathBuf,
+}
+
+/// Top-level directory of a match relative to the search root ("." for files at the root)
+fn top_level_dir(file: &str, directory: &Path) -> String {
+    let path = Path::new(file);
+    let relative = path.strip_prefix(directory).unwrap_or(path);
+    let mut components = relative.components();
+    match (components.next(), components.next()) {
+        (Some(first), Some(_)) => first.as_os_str().to_string_lossy().to_string(),
+        _ => ".".to_string(),
+    }
+}
+
+/// Escape a label value per the Prometheus text exposition format
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Scan the current files and render the gauges in the Prometheus text format
+pub fn render_metrics(options: &MetricsOptions) -> Result<String> {
+    let mut counts: BTreeMap<(String, String), usize> = BTreeMap::new();
+
+    for pattern in &options.patterns {
+        let matches =
+            collect_current_matches(pattern, options.file_type.as_deref(), &options.directory)?;
+        for m in matches {
+            let dir = top_level_dir(&m.file, &options.directory);
+            *counts.entry((pattern.clone(), dir)).or_default() += 1;
+        }
+    }
+
+    let mut out = String::new();
+    out.push_str("# HELP fask_todos_total Number of matches in the current files.\n");
+    out.push_str("# TYPE fask_todos_total gauge\n");
+    for ((keyword, dir), count) in &counts {
+        out.push_str(&format!(
+            "fask_todos_total{{keyword=\"{}\",dir=\"{}\"}} {}\n",
+            escape_label(keyword),
+            escape_label(dir),
+            count
+        ));
+    }
+
+    Ok(out)
+}
+
+/// Write the metrics for node_exporter's textfile collector.
+/// The file is written next to the target and renamed so the collector never sees a partial file.
+pub fn write_textfile(path: &Path, options: &MetricsOptions) -> Result<()> {
+    let body = render_metrics(options)?;
+    let tmp_path = path.with_extension("prom.tmp");
+    std::fs::write(&tmp_path, body)
+        .with_context(|| tr!("Failed to write {}", tmp_path.display()))?;
+    std::fs::rename(&tmp_path, path).with_context(|| tr!("Failed to write {}", path.display()))?;
+    Ok(())
+}
+
+/// Serve `/metrics` for Prometheus to scrape
+pub fn serve_metrics(listen: &str, options: &MetricsOptions) -> Result<()> {
+    let addr = parse_listen_addr(listen);
+    let listener = TcpListener::bind(&addr).with_context(|| tr!("Failed to listen on {}", addr))?;
+
+    println!("{}", tr!("Serving metrics on http://{}/metrics", addr));
+
+    for stream in listener.incoming() {
+        let mut stream = match stream {
+            Ok(s) => s,
+            Err(_) => continue,
+        };
+
+        let result = read_request(&stream).and_then(|(_, path)| match path.as_str() {
+            "/metrics" => {
+                let body = render_metrics(options)?;
+                write_response(&mut stream, "200 OK", "text/plain; version=0.0.4", &body)
+            }
+            _ => write_response(&mut stream, "404 Not Found", "text/plain", "Not found"),
+        });
+
+        if let Err(e) = result {
+            eprintln!("{}", tr!("Request failed: {}", format!("{:#}", e)));
+        }
+    }
+
+    Ok(())
+}
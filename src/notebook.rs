@@ -0,0 +1,134 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+use crate::i18n::tr;
+use crate::search::{hit, pattern_regex, Hit};
+
+/// Where a match sits in a Jupyter notebook: the cell (counting from 1, as in the
+/// notebook) and the line within its source
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Cell {
+    pub index: usize,
+    pub line: usize,
+}
+
+pub fn is_notebook(file: &str) -> bool {
+    file.ends_with(".ipynb")
+}
+
+/// The source lines of each cell, without their line endings
+fn cell_sources(notebook: &Value) -> Vec<Vec<String>> {
+    let Some(cells) = notebook["cells"].as_array() else {
+        return Vec::new();
+    };
+    cells
+        .iter()
+        .map(|cell| {
+            let source = match &cell["source"] {
+                Value::Array(parts) => parts.iter().filter_map(Value::as_str).collect(),
+                Value::String(s) => s.clone(),
+                _ => String::new(),
+            };
+            source.lines().map(String::from).collect()
+        })
+        .collect()
+}
+
+/// Line of the raw file each cell's source starts on: the line after `"source": [` when
+/// the notebook is pretty-printed (one source line per file line, as Jupyter saves it),
+/// otherwise the line of the key itself
+fn source_lines(raw: &str) -> Vec<(usize, bool)> {
+    raw.lines()
+        .enumerate()
+        .filter_map(|(idx, line)| {
+            let line = line.trim();
+            let rest = line.strip_prefix("\"source\":")?;
+            Some(match rest.trim() {
+                "[" => (idx + 2, true),
+                _ => (idx + 1, false),
+            })
+        })
+        .collect()
+}
+
+/// Search the source of a notebook's cells, leaving out outputs and the JSON around them.
+/// Hits carry the line of the raw file (so blame and editors land on it) and the cell.
+pub fn search(file: &str, re: &Regex) -> Result<Vec<Hit>> {
+    let raw =
+        std::fs::read_to_string(file).with_context(|| tr!("Failed to read notebook: {}", file))?;
+    let notebook: Value =
+        serde_json::from_str(&raw).with_context(|| tr!("Failed to parse notebook: {}", file))?;
+    let starts = source_lines(&raw);
+
+    let mut hits = Vec::new();
+    for (cell_idx, lines) in cell_sources(&notebook).iter().enumerate() {
+        let start = starts.get(cell_idx).copied();
+        for (line_idx, line) in lines.iter().enumerate() {
+            let raw_line = match start {
+                Some((first, true)) => first + line_idx,
+                Some((first, false)) => first,
+                None => 1,
+            };
+            if let Some(mut found) = hit(file, raw_line, line, re) {
+                found.cell = Some(Cell {
+                    index: cell_idx + 1,
+                    line: line_idx + 1,
+                });
+                hits.push(found);
+            }
+        }
+    }
+    Ok(hits)
+}
+
+/// Lines of a pretty-printed notebook as they read in the cells: JSON string elements
+/// decoded, everything else as is. For the context around a match.
+pub fn display_lines(raw_lines: Vec<String>) -> Vec<String> {
+    raw_lines
+        .into_iter()
+        .map(|line| {
+            let element = line.trim().trim_end_matches(',');
+            match serde_json::from_str::<String>(element) {
+                Ok(text) if element.starts_with('"') => text.trim_end_matches('\n').to_string(),
+                _ => line,
+            }
+        })
+        .collect()
+}
+
+/// Replace the backend's hits in notebooks, which are lines of the raw JSON, with hits in
+/// their cells. Notebooks that don't parse keep the raw hits.
+pub fn search_cells(hits: Vec<Hit>, pattern: &str) -> Result<Vec<Hit>> {
+    if !hits.iter().any(|h| is_notebook(&h.file)) {
+        return Ok(hits);
+    }
+    let re = pattern_regex(pattern)?;
+    // Whether each notebook parsed (and its cells were searched) or is searched as text
+    let mut parsed: HashMap<String, bool> = HashMap::new();
+    let mut resolved = Vec::new();
+    for h in hits {
+        if !is_notebook(&h.file) {
+            resolved.push(h);
+            continue;
+        }
+        match parsed.get(&h.file) {
+            Some(true) => {}
+            Some(false) => resolved.push(h),
+            None => match search(&h.file, &re) {
+                Ok(cell_hits) => {
+                    parsed.insert(h.file.clone(), true);
+                    resolved.extend(cell_hits);
+                }
+                Err(e) => {
+                    tracing::debug!("{:#}, searching it as text", e);
+                    parsed.insert(h.file.clone(), false);
+                    resolved.push(h);
+                }
+            },
+        }
+    }
+    Ok(resolved)
+}
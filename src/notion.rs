@@ -0,0 +1,139 @@
+use anyhow::{Context, Result};
+use clap::Args;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+use crate::export::ExportRow;
+use crate::i18n::tr;
+
+const API: &str = "https://api.notion.com/v1";
+const VERSION: &str = "2022-06-28";
+
+/// Which Notion database `export --format notion` writes to
+#[derive(Args)]
+#[command(next_help_heading = "Notion")]
+pub struct NotionArgs {
+    /// Database to upsert into (default: $NOTION_DATABASE_ID)
+    #[arg(long)]
+    pub notion_database: Option<String>,
+}
+
+/// Talks to the Notion API with an integration token from $NOTION_TOKEN
+struct Notion {
+    token: String,
+    database: String,
+}
+
+/// Plain text of a rich_text or title property
+fn plain_text(property: &Value) -> String {
+    let parts = property["rich_text"]
+        .as_array()
+        .or_else(|| property["title"].as_array());
+    parts
+        .into_iter()
+        .flatten()
+        .filter_map(|part| part["plain_text"].as_str())
+        .collect()
+}
+
+/// Database properties for a row. The database needs: Name (title), ID, File and Owner
+/// (text), Severity (select), Age and Score (number)
+fn properties(row: &ExportRow) -> Value {
+    let text = |s: &str| json!({ "rich_text": [{ "text": { "content": s } }] });
+    json!({
+        "Name": { "title": [{ "text": { "content": row.text } }] },
+        "ID": text(&row.id),
+        "File": text(&format!("{}:{}", row.file, row.line_number)),
+        "Owner": text(row.owner.as_deref().unwrap_or("")),
+        "Severity": { "select": { "name": row.severity } },
+        "Age": { "number": row.age_days },
+        "Score": { "number": row.score },
+    })
+}
+
+impl Notion {
+    fn request(&self, method: &str, path: &str) -> ureq::Request {
+        let api = std::env::var("NOTION_API_URL").unwrap_or_else(|_| API.to_string());
+        ureq::request(method, &format!("{}{}", api.trim_end_matches('/'), path))
+            .set("Authorization", &format!("Bearer {}", self.token))
+            .set("Notion-Version", VERSION)
+            .set("User-Agent", "fask")
+    }
+
+    /// Page ID of every row in the database, keyed by its ID property
+    fn existing_pages(&self) -> Result<HashMap<String, String>> {
+        let mut pages = HashMap::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let mut body = json!({ "page_size": 100 });
+            if let Some(cursor) = &cursor {
+                body["start_cursor"] = json!(cursor);
+            }
+            let response: Value = self
+                .request("POST", &format!("/databases/{}/query", self.database))
+                .send_json(body)
+                .with_context(|| tr!("Failed to query Notion database"))?
+                .into_json()?;
+
+            for page in response["results"].as_array().into_iter().flatten() {
+                let id = plain_text(&page["properties"]["ID"]);
+                if let (false, Some(page_id)) = (id.is_empty(), page["id"].as_str()) {
+                    pages.insert(id, page_id.to_string());
+                }
+            }
+
+            match response["next_cursor"].as_str() {
+                Some(next) if response["has_more"] == true => cursor = Some(next.to_string()),
+                _ => break,
+            }
+        }
+        Ok(pages)
+    }
+}
+
+/// Create or update one database row per TODO, matched by the stable TODO ID
+pub fn upsert(rows: &[ExportRow], args: &NotionArgs) -> Result<()> {
+    let token = std::env::var("NOTION_TOKEN")
+        .with_context(|| tr!("Set NOTION_TOKEN to export to Notion"))?;
+    let database = args
+        .notion_database
+        .clone()
+        .or_else(|| std::env::var("NOTION_DATABASE_ID").ok())
+        .with_context(|| tr!("Pass --notion-database or set NOTION_DATABASE_ID"))?;
+    let notion = Notion { token, database };
+
+    let pages = notion.existing_pages()?;
+    let (mut created, mut updated) = (0, 0);
+    for row in rows {
+        match pages.get(&row.id) {
+            Some(page_id) => {
+                notion
+                    .request("PATCH", &format!("/pages/{}", page_id))
+                    .send_json(json!({ "properties": properties(row) }))
+                    .with_context(|| tr!("Failed to update Notion page for {}", row.id))?;
+                updated += 1;
+            }
+            None => {
+                notion
+                    .request("POST", "/pages")
+                    .send_json(json!({
+                        "parent": { "database_id": notion.database },
+                        "properties": properties(row),
+                    }))
+                    .with_context(|| tr!("Failed to create Notion page for {}", row.id))?;
+                created += 1;
+            }
+        }
+    }
+
+    println!(
+        "{}",
+        tr!(
+            "Exported {} TODO(s) to Notion: {} created, {} updated.",
+            rows.len(),
+            created,
+            updated
+        )
+    );
+    Ok(())
+}
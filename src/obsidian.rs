@@ -0,0 +1,118 @@
+use anyhow::{Context, Result};
+use clap::{Args, ValueEnum};
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::export::ExportRow;
+use crate::i18n::tr;
+
+/// What each note in the vault collects
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum NoteGrouping {
+    /// One note per source file
+    File,
+    /// One note per owner
+    Owner,
+}
+
+/// Where `export --format obsidian` writes its notes
+#[derive(Args)]
+#[command(next_help_heading = "Obsidian")]
+pub struct ObsidianArgs {
+    /// Folder to write the notes into, e.g. a folder inside the vault
+    #[arg(long, default_value = "TODOs")]
+    pub vault_dir: PathBuf,
+
+    /// One note per file or per owner
+    #[arg(long, value_enum, default_value = "file")]
+    pub notes_by: NoteGrouping,
+}
+
+const UNOWNED: &str = "unowned";
+
+/// Note name for a file path or owner; notes live side by side, so paths are flattened
+fn note_name(name: &str) -> String {
+    name.replace(['/', '\\', ':'], "-")
+}
+
+fn owner_note(row: &ExportRow) -> String {
+    note_name(row.owner.as_deref().unwrap_or(UNOWNED))
+}
+
+/// IDs of tasks ticked off in an existing note, so re-exporting keeps them ticked
+fn checked_ids(note: &Path) -> HashSet<String> {
+    let Ok(content) = std::fs::read_to_string(note) else {
+        return HashSet::new();
+    };
+    content
+        .lines()
+        .filter(|line| line.trim_start().starts_with("- [x]"))
+        .filter_map(|line| line.rsplit_once(" ^").map(|(_, id)| id.trim().to_string()))
+        .collect()
+}
+
+/// A task line: checkbox, the TODO text, links to the other grouping, and a block ID
+fn task_line(row: &ExportRow, grouping: NoteGrouping, checked: bool) -> String {
+    let location = match grouping {
+        NoteGrouping::File => format!("line {}, [[{}]]", row.line_number, owner_note(row)),
+        NoteGrouping::Owner => format!("[[{}]] line {}", note_name(&row.file), row.line_number),
+    };
+    format!(
+        "- [{}] {} ({}) ^{}\n",
+        if checked { "x" } else { " " },
+        row.text,
+        location,
+        row.id
+    )
+}
+
+/// Write one Markdown note per file or owner with a task per TODO, plus an index note
+pub fn write_vault(rows: &[ExportRow], args: &ObsidianArgs) -> Result<()> {
+    std::fs::create_dir_all(&args.vault_dir)
+        .with_context(|| tr!("Failed to create {}", args.vault_dir.display()))?;
+
+    let mut notes: BTreeMap<String, Vec<&ExportRow>> = BTreeMap::new();
+    for row in rows {
+        let name = match args.notes_by {
+            NoteGrouping::File => note_name(&row.file),
+            NoteGrouping::Owner => owner_note(row),
+        };
+        notes.entry(name).or_default().push(row);
+    }
+
+    for (name, rows) in &notes {
+        let path = args.vault_dir.join(format!("{}.md", name));
+        let checked = checked_ids(&path);
+
+        let mut content = match args.notes_by {
+            NoteGrouping::File => format!("# {}\n\nSource: `{}`\n\n", name, rows[0].file),
+            NoteGrouping::Owner => format!("# {}\n\n", name),
+        };
+        for row in rows {
+            content.push_str(&task_line(row, args.notes_by, checked.contains(&row.id)));
+        }
+        content.push_str("\nBack to [[TODOs]]\n");
+
+        std::fs::write(&path, content)
+            .with_context(|| tr!("Failed to write note: {}", path.display()))?;
+    }
+
+    let mut index = String::from("# TODOs\n\n");
+    for (name, rows) in &notes {
+        index.push_str(&format!("- [[{}]] ({})\n", name, rows.len()));
+    }
+    let index_path = args.vault_dir.join("TODOs.md");
+    std::fs::write(&index_path, index)
+        .with_context(|| tr!("Failed to write note: {}", index_path.display()))?;
+
+    println!(
+        "{}",
+        tr!(
+            "Wrote {} note(s) with {} task(s) to {}.",
+            notes.len(),
+            rows.len(),
+            args.vault_dir.display()
+        )
+    );
+    Ok(())
+}
@@ -0,0 +1,81 @@
+use anyhow::{Context, Result};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::Path;
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+use crate::i18n::tr;
+
+/// Reads objects out of the repository's object database through one long-running
+/// `git cat-file --batch`, instead of a `git show` process per file
+pub struct ObjectReader {
+    child: Child,
+    stdin: Option<ChildStdin>,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl ObjectReader {
+    pub fn new(root: &Path) -> Result<Self> {
+        let mut child = Command::new("git")
+            .args(["cat-file", "--batch"])
+            .current_dir(root)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .with_context(|| tr!("Failed to execute git cat-file"))?;
+        let stdin = child.stdin.take();
+        let stdout = BufReader::new(
+            child
+                .stdout
+                .take()
+                .with_context(|| tr!("Failed to read git cat-file output"))?,
+        );
+        Ok(ObjectReader {
+            child,
+            stdin,
+            stdout,
+        })
+    }
+
+    /// Contents of a blob named like "HEAD:src/main.rs", or None if there is no such blob
+    pub fn read_blob(&mut self, object: &str) -> Result<Option<Vec<u8>>> {
+        let stdin = self
+            .stdin
+            .as_mut()
+            .with_context(|| tr!("git cat-file is closed"))?;
+        writeln!(stdin, "{}", object)?;
+        stdin.flush()?;
+
+        // "<oid> <type> <size>", or "<object> missing"
+        let mut header = String::new();
+        self.stdout.read_line(&mut header)?;
+        let fields: Vec<&str> = header.split_whitespace().collect();
+        let [_, kind, size] = fields[..] else {
+            return Ok(None);
+        };
+        let size: usize = size
+            .parse()
+            .with_context(|| tr!("Bad git cat-file header: {}", header.trim()))?;
+
+        // The contents are followed by a newline
+        let mut contents = vec![0; size + 1];
+        self.stdout.read_exact(&mut contents)?;
+        contents.truncate(size);
+        Ok((kind == "blob").then_some(contents))
+    }
+
+    /// Text of a file at a revision, decoded lossily
+    pub fn read_file(&mut self, rev: &str, file: &str) -> Result<Option<String>> {
+        Ok(self
+            .read_blob(&format!("{}:{}", rev, file))?
+            .map(|blob| String::from_utf8_lossy(&blob).into_owned()))
+    }
+}
+
+impl Drop for ObjectReader {
+    fn drop(&mut self) {
+        // Closing stdin ends the batch
+        self.stdin.take();
+        let _ = self.child.wait();
+    }
+}
@@ -0,0 +1,213 @@
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Command;
+
+use crate::hyperlink;
+use crate::i18n::tr;
+use crate::odb::ObjectReader;
+use crate::queue::{self, Forge, Operation, Queue};
+use crate::state::repo_root;
+use crate::style::{self, Element};
+use crate::{find_line_in, git_log_added_lines, github, gitlab};
+
+/// A TODO added in the PR range that is still present at HEAD
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct PrTodo {
+    pub commit: String,
+    /// Path relative to the repository root
+    pub file: String,
+    pub line_number: usize,
+    pub line: String,
+}
+
+/// Hidden marker fask puts in its review comments so reruns can recognize them
+const MARKER_PREFIX: &str = "<!-- fask:";
+
+/// Text of the review comment for a TODO, ending in the marker carrying its ID
+pub fn comment_body(pattern: &str, todo: &PrTodo, id: &str) -> String {
+    format!(
+        "New `{}` added in this pull request:\n\n> {}\n\n{}{} -->",
+        pattern,
+        todo.line.trim(),
+        MARKER_PREFIX,
+        id
+    )
+}
+
+/// TODO ID from the marker of a comment fask posted
+pub fn marker_id(body: &str) -> Option<&str> {
+    let start = body.find(MARKER_PREFIX)? + MARKER_PREFIX.len();
+    let end = body[start..].find(" -->")?;
+    Some(&body[start..start + end])
+}
+
+/// Whether `origin` lives on GitLab: a host with "gitlab" in its name, or any host once
+/// $GITLAB_URL is set
+fn is_gitlab(root: &Path) -> bool {
+    let Ok(url) = git(&["remote", "get-url", "origin"], root) else {
+        return false;
+    };
+    let host = gitlab::remote_host(&url).unwrap_or_default();
+    host.contains("gitlab") || std::env::var_os("GITLAB_URL").is_some()
+}
+
+/// Run git with the given arguments and return trimmed stdout, failing on a non-zero exit
+pub fn git(args: &[&str], directory: &Path) -> Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(directory)
+        .output()
+        .with_context(|| tr!("Failed to execute git"))?;
+    if !output.status.success() {
+        anyhow::bail!(tr!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Report TODOs added between the merge base with `base` and HEAD, grouped by commit.
+/// With `comment` they are also posted as review comments on the branch's pull request
+/// (GitHub) or merge request (GitLab).
+pub fn search_pr(pattern: &str, base: &str, directory: &Path, comment: bool) -> Result<()> {
+    let root = repo_root(directory);
+    let merge_base = git(&["merge-base", base, "HEAD"], &root)
+        .with_context(|| tr!("Can't find a merge base with '{}'. Try --base.", base))?;
+    let range = format!("{}..HEAD", merge_base);
+
+    println!(
+        "{}",
+        tr!(
+            "Searching for '{}' added since {} ({})...\n",
+            pattern,
+            base,
+            &merge_base[..8.min(merge_base.len())]
+        )
+    );
+
+    // "<hash>\x1f<author>\x1f<subject>" per commit, oldest first
+    let log = git(
+        &["log", "--reverse", "--format=%H%x1f%aN%x1f%s", &range],
+        &root,
+    )?;
+    let commits: Vec<(&str, &str, &str)> = log
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, '\x1f');
+            Some((fields.next()?, fields.next()?, fields.next()?))
+        })
+        .collect();
+
+    // Only lines that are still there at HEAD count; later commits may have removed them.
+    // Read HEAD's blobs rather than the working tree, which review comments can't point at.
+    let mut objects = ObjectReader::new(&root)?;
+    let mut at_head: HashMap<String, Option<String>> = HashMap::new();
+    let mut todos = Vec::new();
+    for a in git_log_added_lines(pattern, None, Some(&range), &[], &root)? {
+        if !at_head.contains_key(&a.file) {
+            let contents = objects.read_file("HEAD", &a.file)?;
+            at_head.insert(a.file.clone(), contents);
+        }
+        let Some((line_number, line)) = at_head[&a.file]
+            .as_deref()
+            .and_then(|contents| find_line_in(contents, &a.content, pattern))
+        else {
+            continue;
+        };
+        todos.push(PrTodo {
+            commit: a.commit_hash,
+            file: a.file,
+            line_number,
+            line,
+        });
+    }
+
+    let mut printed = 0;
+    for (hash, author, subject) in &commits {
+        let lines: Vec<&PrTodo> = todos.iter().filter(|t| t.commit == *hash).collect();
+        if lines.is_empty() {
+            continue;
+        }
+
+        if printed > 0 {
+            println!();
+        }
+        println!(
+            "{} {}: {}",
+            style::paint(
+                Element::Commit,
+                hyperlink::commit(hash, &hash[..8.min(hash.len())], &root)
+            ),
+            author,
+            subject
+        );
+        for t in &lines {
+            println!(
+                "  {}:{}: {}",
+                style::paint(Element::Path, hyperlink::file(&t.file, &root)),
+                style::paint(Element::LineNumber, t.line_number),
+                t.line.trim()
+            );
+        }
+        printed += 1;
+    }
+
+    if todos.is_empty() {
+        println!(
+            "{}",
+            tr!("No '{}' added in {} commit(s).", pattern, commits.len())
+        );
+    } else {
+        println!(
+            "{}",
+            tr!(
+                "\nFound {} '{}'(s) added in {} commit(s).",
+                todos.len(),
+                pattern,
+                commits.len()
+            )
+        );
+    }
+
+    // GitLab also resolves discussions whose TODO is gone, so it runs even with none left
+    let forge = if is_gitlab(&root) {
+        Forge::GitLab
+    } else {
+        Forge::GitHub
+    };
+    let posted = match forge {
+        _ if !comment => Ok(()),
+        Forge::GitLab => gitlab::post_discussions(&todos, pattern, &root),
+        Forge::GitHub if todos.is_empty() => Ok(()),
+        Forge::GitHub => github::post_review_comments(&todos, pattern, &root),
+    };
+    // A rate limit or an outage shouldn't lose the comments: they wait for `fask flush`
+    if let Err(e) = posted {
+        if !queue::is_transient(&e) {
+            return Err(e);
+        }
+        let mut queue = Queue::load(&root)?;
+        queue.push(
+            Operation::ReviewComments {
+                forge,
+                pattern: pattern.to_string(),
+                todos,
+            },
+            &e,
+        );
+        queue.save()?;
+        println!(
+            "{}",
+            tr!(
+                "{} {}\nQueued the comments; `fask flush` posts them.",
+                style::paint(Element::Warning, tr!("Couldn't post the comments:")),
+                format!("{:#}", e)
+            )
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use anyhow::Result;
+use clap::parser::ValueSource;
+use clap::CommandFactory;
+use std::ffi::OsString;
+
+use crate::config::{self, Config};
+use crate::i18n::tr;
+use crate::{argv, Cli};
+
+/// The command line with the options of the `--profile` it names filled in from
+/// `.fask.toml`. Options given on the command line win over the profile's, except the
+/// repeatable ones, which get the profile's values added. Options the command doesn't have,
+/// or values it doesn't accept (a --format it has no such output for), are left out.
+pub fn apply(mut args: Vec<OsString>) -> Result<Vec<OsString>> {
+    let command = Cli::command();
+    // Bad arguments and --help are for the real parse to report
+    let Ok(matches) = command.clone().try_get_matches_from(&args) else {
+        return Ok(args);
+    };
+    let Some(name) = matches.get_one::<String>("profile") else {
+        return Ok(args);
+    };
+    let Some((subcommand_name, subcommand)) = matches.subcommand() else {
+        return Ok(args);
+    };
+    let Some(definition) = command.find_subcommand(subcommand_name) else {
+        return Ok(args);
+    };
+
+    let config = Config::load(&config::command_directory(&matches))?;
+    let Some(profile) = config.profiles.get(name) else {
+        let known: Vec<&str> = config.profiles.keys().map(String::as_str).collect();
+        anyhow::bail!(tr!(
+            "No profile '{}' in .fask.toml (defined: {})",
+            name,
+            if known.is_empty() {
+                "none".to_string()
+            } else {
+                known.join(", ")
+            }
+        ));
+    };
+
+    let mut extra: Vec<OsString> = Vec::new();
+    let mut add = |id: &str, values: &[String], repeatable: bool| {
+        let Some(arg) = definition.get_arguments().find(|a| a.get_id() == id) else {
+            return;
+        };
+        let Some(long) = arg.get_long() else {
+            return;
+        };
+        if !repeatable && subcommand.value_source(id) == Some(ValueSource::CommandLine) {
+            return;
+        }
+        let possible = arg.get_possible_values();
+        for value in values {
+            if possible.is_empty() || possible.iter().any(|p| p.matches(value, false)) {
+                extra.push(format!("--{}={}", long, value).into());
+            }
+        }
+    };
+    add("pattern", profile.pattern.as_slice(), false);
+    add("file_type", profile.r#type.as_slice(), false);
+    add("exclude", &profile.exclude, true);
+    add("filters", &profile.r#where, true);
+    add("format", profile.format.as_slice(), false);
+    argv::insert_after_subcommand(&mut args, extra);
+    Ok(args)
+}
@@ -0,0 +1,272 @@
+use fask::metadata::{self, Metadata};
+use regex::Regex;
+use std::cmp::Ordering;
+
+use crate::i18n::tr;
+use crate::search::glob_matches;
+
+/// A `fask query` expression: terms joined with AND, OR and NOT, and grouped with parentheses
+#[derive(Debug, Clone)]
+pub enum Query {
+    And(Box<Query>, Box<Query>),
+    Or(Box<Query>, Box<Query>),
+    Not(Box<Query>),
+    Term(Term),
+}
+
+#[derive(Debug, Clone)]
+pub enum Term {
+    /// The text the pattern matched, e.g. FIXME, ignoring case
+    Keyword(String),
+    /// The owner tag, e.g. alice in "TODO(alice)"
+    Owner(String),
+    /// A glob over the path from the top of the repository
+    Path(String),
+    /// A regex over the whole line
+    Text(Regex),
+    /// Part of the name of who last touched the line, ignoring case
+    Author(String),
+    /// Days since the line was last touched, compared to a number of days
+    Age(Ordering, bool, i64),
+    /// Any other key, as a `--where` condition on the metadata
+    Field(metadata::Filter),
+}
+
+/// What a query is evaluated over: one match
+pub struct Subject<'a> {
+    pub keyword: &'a str,
+    pub owner: Option<&'a str>,
+    pub path: &'a str,
+    pub line: &'a str,
+    /// From git blame, so through `.mailmap`; None for lines that aren't committed
+    pub author: Option<&'a str>,
+    /// None when the line has no blame, e.g. outside git
+    pub age_days: Option<i64>,
+    pub metadata: &'a Metadata,
+}
+
+impl Query {
+    /// Whether the query looks at authors or ages, which take a git blame per file
+    pub fn uses_blame(&self) -> bool {
+        match self {
+            Query::And(a, b) | Query::Or(a, b) => a.uses_blame() || b.uses_blame(),
+            Query::Not(q) => q.uses_blame(),
+            Query::Term(term) => matches!(term, Term::Author(_) | Term::Age(..)),
+        }
+    }
+
+    pub fn matches(&self, subject: &Subject) -> bool {
+        match self {
+            Query::And(a, b) => a.matches(subject) && b.matches(subject),
+            Query::Or(a, b) => a.matches(subject) || b.matches(subject),
+            Query::Not(q) => !q.matches(subject),
+            Query::Term(term) => term.matches(subject),
+        }
+    }
+}
+
+impl Term {
+    fn matches(&self, subject: &Subject) -> bool {
+        match self {
+            Term::Keyword(keyword) => subject.keyword.eq_ignore_ascii_case(keyword),
+            Term::Owner(owner) => subject.owner == Some(owner.as_str()),
+            Term::Path(glob) => glob_matches(glob, subject.path),
+            Term::Author(name) => subject
+                .author
+                .is_some_and(|author| author.to_lowercase().contains(&name.to_lowercase())),
+            Term::Text(re) => re.is_match(subject.line),
+            Term::Age(ordering, or_equal, days) => subject.age_days.is_some_and(|age| {
+                let found = age.cmp(days);
+                found == *ordering || (*or_equal && found == Ordering::Equal)
+            }),
+            Term::Field(filter) => filter.matches(subject.metadata),
+        }
+    }
+}
+
+/// "90d", "12w", "6m" or "1y" (or a bare number of days) as days
+fn parse_days(text: &str) -> Result<i64, String> {
+    let (number, unit) = match text.find(|c: char| !c.is_ascii_digit()) {
+        Some(idx) => text.split_at(idx),
+        None => (text, "d"),
+    };
+    let number: i64 = number
+        .parse()
+        .map_err(|_| tr!("bad age '{}' (like 90d, 12w, 6m or 1y)", text))?;
+    let days = match unit {
+        "d" => 1,
+        "w" => 7,
+        "m" => 30,
+        "y" => 365,
+        _ => return Err(tr!("bad age unit in '{}' (d, w, m or y)", text)),
+    };
+    number
+        .checked_mul(days)
+        .ok_or_else(|| tr!("bad age '{}' (like 90d, 12w, 6m or 1y)", text))
+}
+
+fn parse_term(text: &str) -> Result<Term, String> {
+    if let Some(rest) = text.strip_prefix("age") {
+        for (op, ordering, or_equal) in [
+            (">=", Ordering::Greater, true),
+            ("<=", Ordering::Less, true),
+            (">", Ordering::Greater, false),
+            ("<", Ordering::Less, false),
+            ("=", Ordering::Equal, false),
+            (":", Ordering::Equal, false),
+        ] {
+            if let Some(value) = rest.strip_prefix(op) {
+                return Ok(Term::Age(ordering, or_equal, parse_days(value)?));
+            }
+        }
+    }
+    let Some((key, value)) = text.split_once(':') else {
+        // Everything else is a metadata condition, e.g. "due<2025-07-01" or just "ticket"
+        return metadata::Filter::parse(text).map(Term::Field);
+    };
+    if value.is_empty() {
+        return Err(tr!("missing value in '{}'", text));
+    }
+    Ok(match key {
+        "keyword" => Term::Keyword(value.to_string()),
+        "owner" => Term::Owner(value.to_string()),
+        "path" => Term::Path(value.to_string()),
+        "author" => Term::Author(value.to_string()),
+        "text" => Term::Text(Regex::new(value).map_err(|e| tr!("bad regex in '{}': {}", text, e))?),
+        _ => Term::Field(metadata::Filter::parse(&format!("{}={}", key, value))?),
+    })
+}
+
+/// Split on whitespace, with grouping parentheses as tokens of their own. A '(' opens a group
+/// only at the start of a token and a ')' closes one unless it pairs with a '(' inside the
+/// token, so `text:(a|b)` keeps its regex. Double quotes keep anything, spaces and
+/// parentheses included, in the token: `text:"fix (me|later)"`; `\"` is a literal quote.
+fn tokenize(text: &str) -> Result<Vec<String>, String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    // Parentheses opened inside the current token, which its ')'s close first
+    let mut depth = 0;
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some('\\') if chars.clone().next() == Some('"') => {
+                        current.push('"');
+                        chars.next();
+                    }
+                    Some(c) => current.push(c),
+                    None => return Err(tr!("missing closing '\"'")),
+                }
+            },
+            '(' if current.is_empty() => tokens.push(c.to_string()),
+            '(' => {
+                depth += 1;
+                current.push(c);
+            }
+            ')' if depth > 0 => {
+                depth -= 1;
+                current.push(c);
+            }
+            c if c.is_whitespace() || c == ')' => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+                depth = 0;
+                if c == ')' {
+                    tokens.push(c.to_string());
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<String>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.position).map(String::as_str)
+    }
+
+    fn next(&mut self) -> Option<String> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn is_operator(token: &str, operator: &str) -> bool {
+        token.eq_ignore_ascii_case(operator)
+    }
+
+    /// or := and ("OR" and)*
+    fn or(&mut self) -> Result<Query, String> {
+        let mut query = self.and()?;
+        while self.peek().is_some_and(|t| Self::is_operator(t, "OR")) {
+            self.next();
+            query = Query::Or(Box::new(query), Box::new(self.and()?));
+        }
+        Ok(query)
+    }
+
+    /// and := not ("AND"? not)*, so "a b" means "a AND b"
+    fn and(&mut self) -> Result<Query, String> {
+        let mut query = self.not()?;
+        while let Some(token) = self.peek() {
+            if Self::is_operator(token, "OR") || token == ")" {
+                break;
+            }
+            if Self::is_operator(token, "AND") {
+                self.next();
+            }
+            query = Query::And(Box::new(query), Box::new(self.not()?));
+        }
+        Ok(query)
+    }
+
+    /// not := "NOT" not | "(" or ")" | term
+    fn not(&mut self) -> Result<Query, String> {
+        match self.next() {
+            Some(token) if Self::is_operator(&token, "NOT") => {
+                Ok(Query::Not(Box::new(self.not()?)))
+            }
+            Some(token) if token == "(" => {
+                let query = self.or()?;
+                match self.next().as_deref() {
+                    Some(")") => Ok(query),
+                    _ => Err(tr!("missing ')'")),
+                }
+            }
+            Some(token) if token == ")" => Err(tr!("unexpected ')'")),
+            Some(token) if Self::is_operator(&token, "AND") || Self::is_operator(&token, "OR") => {
+                Err(tr!("expected a term before '{}'", token))
+            }
+            Some(token) => parse_term(&token).map(Query::Term),
+            None => Err(tr!("expected a term at the end")),
+        }
+    }
+}
+
+/// Parse a query like "keyword:FIXME AND owner:alice AND age>90d AND path:src/**"
+pub fn parse(text: &str) -> Result<Query, String> {
+    let mut parser = Parser {
+        tokens: tokenize(text)?,
+        position: 0,
+    };
+    if parser.tokens.is_empty() {
+        return Err(tr!("empty query"));
+    }
+    let query = parser.or()?;
+    match parser.peek() {
+        Some(token) => Err(tr!("unexpected '{}'", token)),
+        None => Ok(query),
+    }
+}
@@ -0,0 +1,252 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Local, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::file_issues::{self, QueuedIssue};
+use crate::i18n::tr;
+use crate::pr::PrTodo;
+use crate::state::{repo_root, state_dir};
+use crate::style::{self, Element};
+use crate::{github, gitlab};
+
+/// Minutes to wait before the first retry, doubled after every failure up to the maximum
+const FIRST_BACKOFF_MINUTES: i64 = 1;
+const MAX_BACKOFF_MINUTES: i64 = 6 * 60;
+
+/// Where review comments go
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Forge {
+    GitHub,
+    GitLab,
+}
+
+/// Something a tracker or forge couldn't take, kept to try again
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum Operation {
+    FileIssue(Box<QueuedIssue>),
+    /// Review comments for a branch; fask's markers keep a retry from posting any twice
+    ReviewComments {
+        forge: Forge,
+        pattern: String,
+        todos: Vec<PrTodo>,
+    },
+}
+
+impl Operation {
+    fn describe(&self) -> String {
+        match self {
+            Operation::FileIssue(issue) => tr!("file an issue for {}", issue.file),
+            Operation::ReviewComments { forge, todos, .. } => match forge {
+                Forge::GitHub => tr!("comment on the pull request ({} TODO(s))", todos.len()),
+                Forge::GitLab => tr!("comment on the merge request ({} TODO(s))", todos.len()),
+            },
+        }
+    }
+
+    fn run(&self, root: &Path) -> Result<()> {
+        match self {
+            Operation::FileIssue(issue) => {
+                let key = file_issues::file_queued(issue, root)?;
+                println!(
+                    "{}",
+                    tr!(
+                        "{}: filed as {}",
+                        style::paint(Element::Path, &issue.file),
+                        key
+                    )
+                );
+            }
+            Operation::ReviewComments {
+                forge: Forge::GitHub,
+                pattern,
+                todos,
+            } => github::post_review_comments(todos, pattern, root)?,
+            Operation::ReviewComments {
+                forge: Forge::GitLab,
+                pattern,
+                todos,
+            } => gitlab::post_discussions(todos, pattern, root)?,
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Entry {
+    pub operation: Operation,
+    pub attempts: u32,
+    /// Not retried by `fask flush` before this, unless forced
+    pub next_attempt: DateTime<Utc>,
+    pub last_error: String,
+}
+
+/// Operations waiting for the network or a rate limit, in `queue.json` next to the state
+#[derive(Default, Serialize, Deserialize)]
+pub struct Queue {
+    #[serde(default)]
+    pub entries: Vec<Entry>,
+
+    #[serde(skip)]
+    path: PathBuf,
+}
+
+/// Whether an error is worth retrying later: the request never got an answer, or the
+/// answer was a rate limit (429) or a server error (5xx)
+pub fn is_transient(error: &anyhow::Error) -> bool {
+    error
+        .chain()
+        .any(|cause| match cause.downcast_ref::<ureq::Error>() {
+            Some(ureq::Error::Transport(_)) => true,
+            Some(ureq::Error::Status(code, _)) => *code == 429 || *code >= 500,
+            None => false,
+        })
+}
+
+/// Whether the service answered that it won't take the request, so retrying can't help
+fn is_rejected(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        matches!(
+            cause.downcast_ref::<ureq::Error>(),
+            Some(ureq::Error::Status(code, _)) if (400..500).contains(code) && *code != 429
+        )
+    })
+}
+
+/// How long to wait after the given number of failed attempts
+fn backoff(attempts: u32) -> Duration {
+    let minutes = FIRST_BACKOFF_MINUTES
+        .saturating_mul(1 << attempts.saturating_sub(1).min(16))
+        .min(MAX_BACKOFF_MINUTES);
+    Duration::minutes(minutes)
+}
+
+impl Queue {
+    /// The queue of the repository containing `directory`, empty if there is none
+    pub fn load(directory: &Path) -> Result<Queue> {
+        let path = state_dir(&repo_root(directory)).join("queue.json");
+        let mut queue = if path.exists() {
+            let content = std::fs::read_to_string(&path)
+                .with_context(|| tr!("Failed to read queue: {}", path.display()))?;
+            serde_json::from_str(&content)
+                .with_context(|| tr!("Failed to parse queue: {}", path.display()))?
+        } else {
+            Queue::default()
+        };
+        queue.path = path;
+        Ok(queue)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        if self.entries.is_empty() {
+            if self.path.exists() {
+                std::fs::remove_file(&self.path)
+                    .with_context(|| tr!("Failed to remove {}", self.path.display()))?;
+            }
+            return Ok(());
+        }
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| tr!("Failed to create {}", parent.display()))?;
+        }
+        let content = serde_json::to_string_pretty(self)?;
+        std::fs::write(&self.path, content)
+            .with_context(|| tr!("Failed to write queue: {}", self.path.display()))
+    }
+
+    /// Queue an operation that just failed with `error`
+    pub fn push(&mut self, operation: Operation, error: &anyhow::Error) {
+        self.entries.push(Entry {
+            operation,
+            attempts: 1,
+            next_attempt: Utc::now() + backoff(1),
+            last_error: format!("{:#}", error),
+        });
+    }
+
+    /// IDs of the TODOs with an issue waiting to be filed
+    pub fn queued_issue_ids(&self) -> impl Iterator<Item = &str> {
+        self.entries
+            .iter()
+            .filter_map(|entry| match &entry.operation {
+                Operation::FileIssue(issue) => Some(issue.id.as_str()),
+                _ => None,
+            })
+    }
+}
+
+/// Retry the queued operations that are due (all of them with `force`). What the service
+/// rejects is reported and dropped; anything else, like another outage or a missing token,
+/// stays queued with a longer wait.
+pub fn flush(directory: &Path, force: bool) -> Result<()> {
+    let root = repo_root(directory);
+    let mut queue = Queue::load(directory)?;
+    if queue.entries.is_empty() {
+        println!("{}", tr!("Nothing queued."));
+        return Ok(());
+    }
+
+    let now = Utc::now();
+    let (mut done, mut failed, mut waiting) = (0, 0, 0);
+    let mut idx = 0;
+    while idx < queue.entries.len() {
+        let entry = &mut queue.entries[idx];
+        if !force && entry.next_attempt > now {
+            waiting += 1;
+            idx += 1;
+            continue;
+        }
+        match entry.operation.run(&root) {
+            Ok(()) => {
+                queue.entries.remove(idx);
+                done += 1;
+            }
+            Err(e) if !is_rejected(&e) => {
+                entry.attempts += 1;
+                entry.next_attempt = Utc::now() + backoff(entry.attempts);
+                entry.last_error = format!("{:#}", e);
+                println!(
+                    "{}",
+                    tr!(
+                        "{} {}: {} (attempt {}, next after {})",
+                        style::paint(Element::Warning, tr!("Still failing:")),
+                        entry.operation.describe(),
+                        entry.last_error,
+                        entry.attempts,
+                        entry
+                            .next_attempt
+                            .with_timezone(&Local)
+                            .format("%Y-%m-%d %H:%M")
+                    )
+                );
+                idx += 1;
+            }
+            Err(e) => {
+                println!(
+                    "{} {}: {:#}",
+                    style::paint(Element::Error, tr!("Dropped:")),
+                    entry.operation.describe(),
+                    e
+                );
+                queue.entries.remove(idx);
+                failed += 1;
+            }
+        }
+        // Save as we go, so an interrupted flush doesn't repeat what went through
+        queue.save()?;
+    }
+
+    println!(
+        "{}",
+        tr!(
+            "\n{} done, {} dropped, {} still queued ({} not due yet; --force retries them now).",
+            done,
+            failed,
+            queue.entries.len(),
+            waiting
+        )
+    );
+    Ok(())
+}
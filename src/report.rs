@@ -0,0 +1,62 @@
+use serde_json::Value;
+
+use crate::i18n::tr;
+use crate::metadata::{Filter, MetaValue, Metadata};
+use crate::unicode;
+
+/// Read a `current --format json` report: one object per line, or a JSON array
+pub fn parse(report: &str) -> Result<Vec<Value>, String> {
+    if report.trim_start().starts_with('[') {
+        return serde_json::from_str(report).map_err(|e| e.to_string());
+    }
+    report
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(idx, line)| serde_json::from_str(line).map_err(|e| tr!("line {}: {}", idx + 1, e)))
+        .collect()
+}
+
+/// The metadata of a report record, typed again the way it was parsed from the line
+pub fn metadata(record: &Value) -> Metadata {
+    let Some(fields) = record["metadata"].as_object() else {
+        return Metadata::new();
+    };
+    fields
+        .iter()
+        .map(|(key, value)| {
+            let raw = match value {
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            (key.clone(), MetaValue::parse(&raw))
+        })
+        .collect()
+}
+
+/// The records whose metadata meets every condition (as `--where` takes them, e.g.
+/// "owner=alice" or "due<2025-07-01") and whose text contains `text`, if given, in any
+/// Unicode spelling. Fails on a condition that doesn't parse.
+pub fn filter<'a>(
+    records: &'a [Value],
+    conditions: &[&str],
+    text: Option<&str>,
+) -> Result<Vec<&'a Value>, String> {
+    let filters = conditions
+        .iter()
+        .map(|c| Filter::parse(c))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(records
+        .iter()
+        .filter(|record| {
+            let meta = metadata(record);
+            filters.iter().all(|f| f.matches(&meta))
+        })
+        .filter(|record| {
+            text.is_none_or(|text| {
+                let line = record["line_content"].as_str().unwrap_or_default();
+                unicode::fold(line).contains(&unicode::fold(text))
+            })
+        })
+        .collect())
+}
@@ -0,0 +1,180 @@
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::{read_file_lines, ContextLines};
+
+/// A matching line to report, with the byte ranges of the pattern in it
+pub struct JsonMatch<'a> {
+    pub file: &'a str,
+    pub line_number: usize,
+    pub spans: Vec<(usize, usize)>,
+}
+
+/// Running totals, shaped like ripgrep's `stats` object
+#[derive(Default)]
+struct Stats {
+    searches: u64,
+    searches_with_match: u64,
+    bytes_searched: u64,
+    bytes_printed: u64,
+    matched_lines: u64,
+    matches: u64,
+}
+
+impl Stats {
+    fn add(&mut self, other: &Stats) {
+        self.searches += other.searches;
+        self.searches_with_match += other.searches_with_match;
+        self.bytes_searched += other.bytes_searched;
+        self.bytes_printed += other.bytes_printed;
+        self.matched_lines += other.matched_lines;
+        self.matches += other.matches;
+    }
+
+    fn to_json(&self, elapsed: Duration) -> Value {
+        json!({
+            "elapsed": duration_json(elapsed),
+            "searches": self.searches,
+            "searches_with_match": self.searches_with_match,
+            "bytes_searched": self.bytes_searched,
+            "bytes_printed": self.bytes_printed,
+            "matched_lines": self.matched_lines,
+            "matches": self.matches,
+        })
+    }
+}
+
+fn duration_json(d: Duration) -> Value {
+    json!({
+        "secs": d.as_secs(),
+        "nanos": d.subsec_nanos(),
+        "human": format!("{:.6}s", d.as_secs_f64()),
+    })
+}
+
+/// ripgrep's "submatches" array for the given byte ranges of a line
+fn submatches(line: &str, spans: &[(usize, usize)]) -> Vec<Value> {
+    spans
+        .iter()
+        .filter_map(|&(start, end)| {
+            Some(json!({
+                "match": { "text": line.get(start..end)? },
+                "start": start,
+                "end": end,
+            }))
+        })
+        .collect()
+}
+
+/// Print one message per line and count the bytes for the stats
+fn emit(message: Value, stats: &mut Stats) {
+    let line = message.to_string();
+    stats.bytes_printed += line.len() as u64 + 1;
+    println!("{}", line);
+}
+
+/// Print matches as ripgrep `--json` messages (begin, match, context, end, summary),
+/// grouped per file, so tools built on rg's output can read them unchanged
+pub fn print_matches(
+    matches: &[JsonMatch],
+    context: ContextLines,
+    directory: &Path,
+    elapsed: Duration,
+) {
+    // ripgrep reports files in path order with matches in line order
+    let mut by_file: BTreeMap<&str, Vec<&JsonMatch>> = BTreeMap::new();
+    for m in matches {
+        by_file.entry(m.file).or_default().push(m);
+    }
+
+    let mut total = Stats::default();
+
+    for (file, mut match_lines) in by_file {
+        match_lines.sort_unstable_by_key(|m| m.line_number);
+
+        let Some(lines) = read_file_lines(file, directory) else {
+            continue;
+        };
+
+        let mut stats = Stats {
+            searches: 1,
+            searches_with_match: 1,
+            bytes_searched: lines.iter().map(|l| l.len() as u64 + 1).sum(),
+            ..Stats::default()
+        };
+
+        // Byte offset of each line start, for "absolute_offset"
+        let mut offsets = Vec::with_capacity(lines.len());
+        let mut offset = 0;
+        for line in &lines {
+            offsets.push(offset);
+            offset += line.len() + 1;
+        }
+
+        let path = json!({ "text": file });
+        emit(
+            json!({ "type": "begin", "data": { "path": path } }),
+            &mut stats,
+        );
+
+        // Every line to print, with the spans for matches; overlapping context is printed once
+        let mut printed: BTreeMap<usize, Option<&[(usize, usize)]>> = BTreeMap::new();
+        for m in &match_lines {
+            let (start, end) = context.around(m.line_number, lines.len());
+            for i in start..=end {
+                printed.entry(i).or_insert(None);
+            }
+            printed.insert(m.line_number, Some(&m.spans));
+        }
+
+        for (line_number, spans) in printed {
+            let Some(text) = lines.get(line_number - 1) else {
+                continue;
+            };
+            let is_match = spans.is_some();
+            let submatches = submatches(text, spans.unwrap_or_default());
+            if is_match {
+                stats.matched_lines += 1;
+                stats.matches += submatches.len() as u64;
+            }
+            emit(
+                json!({
+                    "type": if is_match { "match" } else { "context" },
+                    "data": {
+                        "path": path,
+                        "lines": { "text": format!("{}\n", text) },
+                        "line_number": line_number,
+                        "absolute_offset": offsets[line_number - 1],
+                        "submatches": submatches,
+                    }
+                }),
+                &mut stats,
+            );
+        }
+
+        emit(
+            json!({
+                "type": "end",
+                "data": {
+                    "path": path,
+                    "binary_offset": null,
+                    "stats": stats.to_json(Duration::ZERO),
+                }
+            }),
+            &mut stats,
+        );
+
+        total.add(&stats);
+    }
+
+    let summary = json!({
+        "type": "summary",
+        "data": {
+            "elapsed_total": duration_json(elapsed),
+            "stats": total.to_json(elapsed),
+        }
+    });
+    println!("{}", summary);
+}
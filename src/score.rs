@@ -0,0 +1,90 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Command;
+
+/// The `[score]` section: how much each factor counts towards a TODO's score
+#[derive(Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct ScoreConfig {
+    /// Weight of age, which counts fully at a year old
+    pub age: f64,
+    /// Weight of severity: high counts fully, medium half, low not at all
+    pub severity: f64,
+    /// Weight of how often the file changed lately, relative to the busiest file
+    pub churn: f64,
+    /// Weight of having no owner
+    pub unowned: f64,
+    /// How far back churn looks, in days
+    pub churn_days: u32,
+}
+
+impl Default for ScoreConfig {
+    fn default() -> Self {
+        ScoreConfig {
+            age: 1.0,
+            severity: 2.0,
+            churn: 1.0,
+            unowned: 0.5,
+            churn_days: 90,
+        }
+    }
+}
+
+/// What a score is computed from
+pub struct ScoreInputs<'a> {
+    pub age_days: i64,
+    pub severity: &'a str,
+    /// Commits touching the file within `churn_days`
+    pub churn: usize,
+    /// Commits touching the busiest file, to scale churn against
+    pub max_churn: usize,
+    pub owned: bool,
+}
+
+impl ScoreConfig {
+    /// Weighted average of the factors, each scaled to 0..1, as 0..100
+    pub fn score(&self, inputs: &ScoreInputs) -> f64 {
+        let age = (inputs.age_days.max(0) as f64 / 365.0).min(1.0);
+        let severity = match inputs.severity {
+            "high" => 1.0,
+            "medium" => 0.5,
+            _ => 0.0,
+        };
+        let churn = if inputs.max_churn == 0 {
+            0.0
+        } else {
+            inputs.churn as f64 / inputs.max_churn as f64
+        };
+        let unowned = if inputs.owned { 0.0 } else { 1.0 };
+
+        let total = self.age + self.severity + self.churn + self.unowned;
+        if total <= 0.0 {
+            return 0.0;
+        }
+        let weighted =
+            self.age * age + self.severity * severity + self.churn * churn + self.unowned * unowned;
+        (weighted / total * 100.0).round()
+    }
+}
+
+/// Number of commits touching each file (relative to the repository root) in the last `days`
+pub fn file_churn(root: &Path, days: u32) -> HashMap<String, usize> {
+    let output = Command::new("git")
+        .arg("log")
+        .arg(format!("--since={}.days", days))
+        .arg("--format=")
+        .arg("--name-only")
+        .current_dir(root)
+        .output();
+
+    let mut churn = HashMap::new();
+    if let Some(output) = output.ok().filter(|o| o.status.success()) {
+        for file in String::from_utf8_lossy(&output.stdout).lines() {
+            if !file.is_empty() {
+                *churn.entry(file.to_string()).or_insert(0) += 1;
+            }
+        }
+    }
+    churn
+}
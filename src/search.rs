@@ -0,0 +1,663 @@
+use anyhow::{Context, Result};
+use clap::parser::ValueSource;
+use clap::{ArgAction, CommandFactory, ValueEnum};
+use rayon::prelude::*;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::ffi::OsString;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+
+use crate::i18n::tr;
+use crate::notebook::Cell;
+use crate::state::repo_root;
+use crate::{argv, lfs, skipped, unicode, Cli};
+
+/// Which tool finds the pattern in the current files
+#[derive(Clone, Copy, PartialEq, Eq, Debug, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    /// Built in: walks the files git doesn't ignore (or every non-hidden file outside a
+    /// repository) with Rust's regex engine. Slower, but needs nothing installed.
+    Internal,
+    /// ripgrep
+    Rg,
+    /// ugrep
+    Ugrep,
+}
+
+/// One line the pattern matched
+pub struct Hit {
+    /// Path as the backend reports it, starting with the searched path
+    pub file: String,
+    pub line_number: usize,
+    /// The line without its line ending
+    pub line: String,
+    /// Byte ranges of the pattern within `line`
+    pub spans: Vec<(usize, usize)>,
+    /// Where the line is in a notebook's cells, for `.ipynb` files
+    pub cell: Option<Cell>,
+}
+
+/// A way to search the current files for the pattern
+pub trait SearchBackend {
+    /// Every matching line in `paths`. Directories are searched the way ripgrep does, for the
+    /// files `file_type` (a glob like ripgrep's `-g`) matches; files are searched as they are,
+    /// even the ones ignore rules or the glob would leave out.
+    fn search(&self, pattern: &str, file_type: Option<&str>, paths: &[PathBuf])
+        -> Result<Vec<Hit>>;
+}
+
+static BACKEND: OnceLock<Backend> = OnceLock::new();
+
+/// `--rg-args`, added to every ripgrep call
+static RG_ARGS: OnceLock<Vec<String>> = OnceLock::new();
+
+/// The config's `[types]`
+static TYPES: OnceLock<BTreeMap<String, Vec<String>>> = OnceLock::new();
+
+/// The global options besides the pattern, file type and directory that change what a
+/// search finds, for a daemon's matches to be used only by commands searching the same way
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+pub struct Settings {
+    backend: Option<Backend>,
+    rg_args: Vec<String>,
+    types: BTreeMap<String, Vec<String>>,
+    /// None with --include-minified
+    minified_line_length: Option<usize>,
+    lfs_smudge: bool,
+}
+
+/// The settings this run searches with
+pub fn settings() -> Settings {
+    Settings {
+        backend: BACKEND.get().copied(),
+        rg_args: RG_ARGS.get().cloned().unwrap_or_default(),
+        types: TYPES.get().cloned().unwrap_or_default(),
+        minified_line_length: skipped::minified_line_length(),
+        lfs_smudge: lfs::smudging(),
+    }
+}
+
+/// Whether `program` runs at all
+fn installed(program: &str) -> bool {
+    Command::new(program)
+        .arg("--version")
+        .output()
+        .is_ok_and(|o| o.status.success())
+}
+
+/// Pick the backend: the one asked for, else ripgrep or ugrep if installed, else the
+/// built-in one. `rg_args` only work with ripgrep.
+pub fn init(choice: Option<Backend>, rg_args: Vec<String>) -> Result<()> {
+    let backend = choice.unwrap_or_else(|| {
+        if installed("rg") {
+            Backend::Rg
+        } else if installed("ugrep") {
+            Backend::Ugrep
+        } else {
+            Backend::Internal
+        }
+    });
+    tracing::debug!(?backend, ?rg_args, "search backend");
+    if !rg_args.is_empty() && backend != Backend::Rg {
+        let name = backend
+            .to_possible_value()
+            .map(|v| v.get_name().to_string());
+        anyhow::bail!(tr!(
+            "--rg-args needs the rg backend, but {} is in use (pick it with --search-backend rg)",
+            name.unwrap_or_default()
+        ));
+    }
+    let _ = BACKEND.set(backend);
+    let _ = RG_ARGS.set(rg_args);
+    Ok(())
+}
+
+/// Remember the config's `[types]`, so `-t` takes their names as well as globs
+pub fn init_types(types: BTreeMap<String, Vec<String>>) {
+    tracing::debug!(types = ?types.keys().collect::<Vec<_>>(), "file types");
+    let _ = TYPES.set(types);
+}
+
+/// The `[types]` entry a `-t` value names, with "!" if it's "!name"
+fn named_type(file_type: &str) -> Option<(&'static str, &'static [String])> {
+    let (exclude, name) = match file_type.strip_prefix('!') {
+        Some(name) => ("!", name),
+        None => ("", file_type),
+    };
+    let globs = TYPES.get()?.get(name)?;
+    Some((exclude, globs.as_slice()))
+}
+
+/// The globs a `-t` value stands for: those of the `[types]` entry it names, or the value
+/// itself. "!name" excludes them all.
+pub fn type_globs(file_type: &str) -> Vec<String> {
+    match named_type(file_type) {
+        Some((exclude, globs)) => globs.iter().map(|g| format!("{}{}", exclude, g)).collect(),
+        None => vec![file_type.to_string()],
+    }
+}
+
+/// A `-t` value as one glob, with the globs of a `[types]` name as a "{a,b}" alternation
+pub fn type_glob(file_type: &str) -> String {
+    match named_type(file_type) {
+        Some((exclude, [glob])) => format!("{}{}", exclude, glob),
+        Some((exclude, globs)) => format!("{}{{{}}}", exclude, globs.join(",")),
+        None => file_type.to_string(),
+    }
+}
+
+/// A glob's top-level "{a,b}" as separate globs ("!{a,b}" as "!a" and "!b"), for ugrep,
+/// which takes any number of -g but not alternations
+fn alternatives(glob: &str) -> Vec<String> {
+    let (exclude, rest) = match glob.strip_prefix('!') {
+        Some(rest) => ("!", rest),
+        None => ("", glob),
+    };
+    match rest.strip_prefix('{').and_then(|r| r.strip_suffix('}')) {
+        Some(inner) if !inner.contains(['{', '}']) => inner
+            .split(',')
+            .map(|g| format!("{}{}", exclude, g))
+            .collect(),
+        _ => vec![glob.to_string()],
+    }
+}
+
+/// The backend `init` picked (ripgrep if it wasn't called)
+pub fn backend() -> Box<dyn SearchBackend + Sync> {
+    match BACKEND.get().copied().unwrap_or(Backend::Rg) {
+        Backend::Internal => Box::new(Internal),
+        Backend::Rg => Box::new(Ripgrep),
+        Backend::Ugrep => Box::new(Ugrep),
+    }
+}
+
+/// What `--timing` calls the search, after the backend doing it
+pub fn phase_name() -> &'static str {
+    match BACKEND.get().copied().unwrap_or(Backend::Rg) {
+        Backend::Internal => "search (internal)",
+        Backend::Rg => "search (ripgrep)",
+        Backend::Ugrep => "search (ugrep)",
+    }
+}
+
+/// The pattern or any of its other encodings (see `unicode::variants`), as one regex
+pub fn pattern_regex(pattern: &str) -> Result<Regex> {
+    let mut alternatives = vec![format!("(?:{})", pattern)];
+    alternatives.extend(unicode::variants(pattern).iter().map(|v| regex::escape(v)));
+    Regex::new(&alternatives.join("|")).with_context(|| tr!("Invalid pattern '{}'", pattern))
+}
+
+/// Commands that look for --pattern as text, like git's pickaxe and `git grep -F`, rather
+/// than as a regex. They take the lines of a pattern file as text too.
+const TEXT_PATTERN_COMMANDS: &[&str] = &[
+    "since", "trend", "pr", "staged", "stats", "stash", "at", "bisect", "check", "lsp",
+];
+
+/// The patterns in a file, one per line with blank lines and "#" comments left out. As
+/// regexes they are checked, or escaped to match as they are with `literal`.
+fn read_pattern_file(path: &Path, regex: bool, literal: bool) -> Result<Vec<String>> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| tr!("Failed to read pattern file: {}", path.display()))?;
+    let mut patterns = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if !regex {
+            patterns.push(line.to_string());
+        } else if literal {
+            patterns.push(regex::escape(line));
+        } else {
+            Regex::new(line).with_context(|| {
+                tr!(
+                    "Invalid pattern on line {} of {} (--fixed-strings takes the lines literally)",
+                    number + 1,
+                    path.display()
+                )
+            })?;
+            patterns.push(line.to_string());
+        }
+    }
+    if patterns.is_empty() {
+        anyhow::bail!(tr!("No patterns in {}", path.display()));
+    }
+    Ok(patterns)
+}
+
+/// The command line with `--pattern-file` (and `--fixed-strings`) turned into the
+/// command's `--pattern`: one value per line for commands that take several patterns, else
+/// one pattern that matches any of them (lines joined with "|" as a regex, or with
+/// newlines for the commands that search for text, see `unicode::alternatives`).
+pub fn apply_pattern_file(mut args: Vec<OsString>) -> Result<Vec<OsString>> {
+    let command = Cli::command();
+    // Bad arguments and --help are for the real parse to report
+    let Ok(matches) = command.clone().try_get_matches_from(&args) else {
+        return Ok(args);
+    };
+    let Some(path) = matches.get_one::<PathBuf>("pattern_file") else {
+        return Ok(args);
+    };
+    let Some((subcommand_name, subcommand)) = matches.subcommand() else {
+        return Ok(args);
+    };
+    let Some(definition) = command.find_subcommand(subcommand_name) else {
+        return Ok(args);
+    };
+    let Some(arg) = definition
+        .get_arguments()
+        .find(|a| a.get_long() == Some("pattern"))
+    else {
+        anyhow::bail!(tr!(
+            "{} takes no --pattern, so --pattern-file doesn't apply to it",
+            subcommand_name
+        ));
+    };
+    if subcommand.value_source(arg.get_id().as_str()) == Some(ValueSource::CommandLine) {
+        anyhow::bail!(tr!("Give --pattern or --pattern-file, not both"));
+    }
+
+    let regex = !TEXT_PATTERN_COMMANDS.contains(&subcommand_name);
+    let patterns = read_pattern_file(path, regex, matches.get_flag("fixed_strings"))?;
+    let extra: Vec<OsString> = match arg.get_action() {
+        ArgAction::Append => patterns
+            .iter()
+            .map(|p| format!("--pattern={}", p).into())
+            .collect(),
+        // Alternation binds loosest, so the lines need no groups
+        _ if regex => vec![format!("--pattern={}", patterns.join("|")).into()],
+        _ => vec![format!("--pattern={}", patterns.join("\n")).into()],
+    };
+    argv::insert_after_subcommand(&mut args, extra);
+    Ok(args)
+}
+
+/// The files listed in `source` ("-" for stdin), one per line or NUL-separated, in order and
+/// each once. A path is relative to the current directory or, as `git diff --name-only`
+/// prints them, to the top of the repository; listed files that don't exist are left out.
+pub fn read_file_list(source: &Path, directory: &Path) -> Result<Vec<PathBuf>> {
+    let text = if source == Path::new("-") {
+        let mut text = String::new();
+        std::io::stdin()
+            .read_to_string(&mut text)
+            .with_context(|| tr!("Failed to read the file list from stdin"))?;
+        text
+    } else {
+        std::fs::read_to_string(source)
+            .with_context(|| tr!("Failed to read file list: {}", source.display()))?
+    };
+    let separator = if text.contains('\0') { '\0' } else { '\n' };
+    let root = repo_root(directory);
+    let cwd = std::env::current_dir()
+        .and_then(|d| d.canonicalize())
+        .unwrap_or_default();
+    let mut seen = HashSet::new();
+    Ok(text
+        .split(separator)
+        .map(|line| line.trim_end_matches('\r'))
+        .filter(|line| !line.is_empty())
+        .filter_map(|line| {
+            let path = Path::new(line);
+            if path.exists() {
+                return Some(path.to_path_buf());
+            }
+            // From the top of the repository, named from here when it's below here
+            let path = root.join(path).canonicalize().ok()?;
+            Some(
+                path.strip_prefix(&cwd)
+                    .map_or_else(|_| path.clone(), Path::to_path_buf),
+            )
+        })
+        .filter(|path| seen.insert(path.canonicalize().unwrap_or_else(|_| path.clone())))
+        .collect())
+}
+
+/// A hit for `line` if the regex finds anything on it
+pub fn hit(file: &str, line_number: usize, line: &str, re: &Regex) -> Option<Hit> {
+    let spans: Vec<(usize, usize)> = re
+        .find_iter(line)
+        .filter(|m| !m.is_empty())
+        .map(|m| (m.start(), m.end()))
+        .collect();
+    (!spans.is_empty()).then(|| Hit {
+        file: file.to_string(),
+        line_number,
+        line: line.to_string(),
+        spans,
+        cell: None,
+    })
+}
+
+/// Whether a '/'-separated path matches a `-t` glob, where "!glob" excludes as with ripgrep
+pub fn glob_matches(glob: &str, path: &str) -> bool {
+    let (exclude, glob) = match glob.strip_prefix('!') {
+        Some(g) => (true, g),
+        None => (false, glob),
+    };
+    glob_regex(glob).is_ok_and(|re| re.is_match(path) != exclude)
+}
+
+/// Glob in ripgrep's syntax as a regex over a '/'-separated path. A glob without a slash
+/// matches the file name at any depth.
+fn glob_regex(glob: &str) -> Result<Regex> {
+    let mut re = String::from(if glob.trim_start_matches('/').contains('/') {
+        "^"
+    } else {
+        "(?:^|/)"
+    });
+    let mut chars = glob.trim_start_matches('/').chars().peekable();
+    let mut in_alternatives = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                // "**/" is any number of directories, a trailing "**" anything below
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    re.push_str("(?:.*/)?");
+                } else {
+                    re.push_str(".*");
+                }
+            }
+            '*' => re.push_str("[^/]*"),
+            '?' => re.push_str("[^/]"),
+            '[' => {
+                re.push('[');
+                if matches!(chars.peek(), Some('!' | '^')) {
+                    chars.next();
+                    re.push('^');
+                }
+                for c in chars.by_ref() {
+                    if c == ']' {
+                        break;
+                    }
+                    if c == '\\' || c == '[' {
+                        re.push('\\');
+                    }
+                    re.push(c);
+                }
+                re.push(']');
+            }
+            '{' => {
+                in_alternatives = true;
+                re.push_str("(?:");
+            }
+            '}' => {
+                in_alternatives = false;
+                re.push(')');
+            }
+            ',' if in_alternatives => re.push('|'),
+            '\\' => {
+                if let Some(c) = chars.next() {
+                    re.push_str(&regex::escape(&c.to_string()));
+                }
+            }
+            c => re.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    re.push('$');
+    Regex::new(&re).with_context(|| tr!("Invalid glob '{}'", glob))
+}
+
+/// ripgrep, reading its JSON output
+pub struct Ripgrep;
+
+impl SearchBackend for Ripgrep {
+    fn search(
+        &self,
+        pattern: &str,
+        file_type: Option<&str>,
+        paths: &[PathBuf],
+    ) -> Result<Vec<Hit>> {
+        let mut cmd = Command::new("rg");
+        // First, so fask's own options win where they overlap
+        cmd.args(RG_ARGS.get().into_iter().flatten());
+        cmd.arg("-e").arg(pattern).arg("--json");
+        // ripgrep compares bytes, so also look for the other encodings of the same text
+        for variant in unicode::variants(pattern) {
+            cmd.arg("-e").arg(regex::escape(&variant));
+        }
+
+        if let Some(ft) = file_type {
+            cmd.arg("-g").arg(ft);
+        }
+
+        cmd.arg("--").args(paths);
+
+        let output = cmd.output().with_context(|| tr!("Failed to execute ripgrep. Is 'rg' installed? (--search-backend internal needs nothing)"))?;
+
+        // Exit code 1 only means "no matches"; 2 is a real error such as an invalid regex
+        if output.status.code() == Some(2) && output.stdout.is_empty() {
+            anyhow::bail!(tr!(
+                "ripgrep failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+
+        // Unreadable files don't stop ripgrep; it names them on stderr
+        skipped::record_ripgrep_errors(&String::from_utf8_lossy(&output.stderr));
+
+        // One JSON message per line; only "match" messages matter here
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let hits = stdout
+            .lines()
+            .filter_map(|line| {
+                let message: serde_json::Value = serde_json::from_str(line).ok()?;
+                if message["type"] != "match" {
+                    return None;
+                }
+                let data = &message["data"];
+                let Some(file) = data["path"]["text"].as_str() else {
+                    use base64::Engine;
+                    let name = data["path"]["bytes"]
+                        .as_str()
+                        .and_then(|b| base64::engine::general_purpose::STANDARD.decode(b).ok())
+                        .unwrap_or_default();
+                    skipped::record(&String::from_utf8_lossy(&name), "path is not valid UTF-8");
+                    return None;
+                };
+                // ripgrep sends lines that aren't UTF-8 base64-encoded instead
+                let Some(text) = data["lines"]["text"].as_str() else {
+                    skipped::record(file, "not valid UTF-8");
+                    return None;
+                };
+                let line = text.trim_end_matches(['\n', '\r']).to_string();
+                let spans: Vec<(usize, usize)> = data["submatches"]
+                    .as_array()?
+                    .iter()
+                    .filter_map(|sm| {
+                        Some((sm["start"].as_u64()? as usize, sm["end"].as_u64()? as usize))
+                    })
+                    .filter(|&(_, end)| end <= line.len())
+                    .collect();
+                Some(Hit {
+                    file: file.to_string(),
+                    line_number: data["line_number"].as_u64()? as usize,
+                    line,
+                    spans,
+                    cell: None,
+                })
+            })
+            .collect();
+        Ok(hits)
+    }
+}
+
+/// ugrep, reading grep-style "file\0line:text" output. ugrep doesn't report where on the
+/// line the match is, so the spans come from running the pattern again here.
+pub struct Ugrep;
+
+impl SearchBackend for Ugrep {
+    fn search(
+        &self,
+        pattern: &str,
+        file_type: Option<&str>,
+        paths: &[PathBuf],
+    ) -> Result<Vec<Hit>> {
+        let re = pattern_regex(pattern)?;
+        let mut cmd = Command::new("ugrep");
+        // Like ripgrep: recursive, skip binary files and what .gitignore lists
+        cmd.args(["-r", "-I", "-H", "-n", "--null", "--ignore-files"])
+            .arg("-e")
+            .arg(pattern);
+        for variant in unicode::variants(pattern) {
+            cmd.arg("-e").arg(regex::escape(&variant));
+        }
+        for glob in file_type.map(alternatives).unwrap_or_default() {
+            cmd.arg("-g").arg(glob);
+        }
+        cmd.arg("--").args(paths);
+
+        let output = cmd.output().with_context(|| tr!("Failed to execute ugrep. Is 'ugrep' installed? (--search-backend internal needs nothing)"))?;
+        if output.status.code() == Some(2) && output.stdout.is_empty() {
+            anyhow::bail!(tr!(
+                "ugrep failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let hits = stdout
+            .lines()
+            .filter_map(|record| {
+                let (file, rest) = record.split_once('\0')?;
+                let (line_number, line) = rest.split_once(':')?;
+                let line = line.trim_end_matches('\r');
+                hit(file, line_number.parse().ok()?, line, &re)
+            })
+            .collect();
+        Ok(hits)
+    }
+}
+
+/// Search without any external tool
+pub struct Internal;
+
+impl Internal {
+    /// Files to search under `directory`: what git tracks or would track there, or every
+    /// file outside hidden directories when it isn't in a repository
+    pub fn files(directory: &Path) -> Vec<PathBuf> {
+        let listed = Command::new("git")
+            .args([
+                "ls-files",
+                "-z",
+                "--cached",
+                "--others",
+                "--exclude-standard",
+            ])
+            .current_dir(directory)
+            .output()
+            .ok()
+            .filter(|o| o.status.success());
+        if let Some(output) = listed {
+            return String::from_utf8_lossy(&output.stdout)
+                .split('\0')
+                .filter(|f| !f.is_empty())
+                // ripgrep skips hidden files and directories even when they're tracked
+                .filter(|f| !f.split('/').any(|part| part.starts_with('.')))
+                .map(|f| directory.join(f))
+                // Deleted but not yet staged
+                .filter(|f| f.is_file())
+                .collect();
+        }
+
+        let mut files = Vec::new();
+        let mut pending = vec![directory.to_path_buf()];
+        while let Some(dir) = pending.pop() {
+            let Ok(entries) = std::fs::read_dir(&dir) else {
+                skipped::record(&dir.display().to_string(), "can't list directory");
+                continue;
+            };
+            for entry in entries.flatten() {
+                if entry.file_name().to_string_lossy().starts_with('.') {
+                    continue;
+                }
+                let path = entry.path();
+                match entry.file_type() {
+                    Ok(t) if t.is_dir() => pending.push(path),
+                    Ok(t) if t.is_file() => files.push(path),
+                    _ => {}
+                }
+            }
+        }
+        files.sort();
+        files
+    }
+
+    /// The files of `paths` and those under its directories that `file_type` matches, as a
+    /// search of them goes through them
+    pub fn files_in(paths: &[PathBuf], file_type: Option<&str>) -> Result<Vec<PathBuf>> {
+        // "!glob" excludes, as with ripgrep
+        let glob = file_type
+            .map(|g| -> Result<(bool, Regex)> {
+                let (exclude, g) = match g.strip_prefix('!') {
+                    Some(g) => (true, g),
+                    None => (false, g),
+                };
+                Ok((exclude, glob_regex(g)?))
+            })
+            .transpose()?;
+
+        let mut files = Vec::new();
+        for path in paths {
+            if !path.is_dir() {
+                files.push(path.clone());
+                continue;
+            }
+            files.extend(Self::files(path).into_iter().filter(|file| {
+                let Some((exclude, glob)) = &glob else {
+                    return true;
+                };
+                let relative = file.strip_prefix(path).unwrap_or(file);
+                let relative = relative.to_string_lossy().replace('\\', "/");
+                glob.is_match(&relative) != *exclude
+            }));
+        }
+        Ok(files)
+    }
+}
+
+impl SearchBackend for Internal {
+    fn search(
+        &self,
+        pattern: &str,
+        file_type: Option<&str>,
+        paths: &[PathBuf],
+    ) -> Result<Vec<Hit>> {
+        let re = pattern_regex(pattern)?;
+        let files = Self::files_in(paths, file_type)?;
+
+        let hits = files
+            .par_iter()
+            .flat_map_iter(|path| {
+                let file = path.display().to_string();
+                let bytes = match std::fs::read(path) {
+                    Ok(bytes) => bytes,
+                    Err(e) => {
+                        skipped::record(&file, e);
+                        return Vec::new();
+                    }
+                };
+                // Binary files are left out, as ripgrep does
+                if bytes[..bytes.len().min(8192)].contains(&0) {
+                    return Vec::new();
+                }
+                let Ok(content) = String::from_utf8(bytes) else {
+                    skipped::record(&file, "not valid UTF-8");
+                    return Vec::new();
+                };
+                content
+                    .lines()
+                    .enumerate()
+                    .filter_map(|(idx, line)| hit(&file, idx + 1, line, &re))
+                    .collect()
+            })
+            .collect();
+        Ok(hits)
+    }
+}
@@ -0,0 +1,143 @@
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::PathBuf;
+
+use crate::i18n::tr;
+use crate::{collect_current_matches, git_log_added_lines};
+
+/// The dashboard page, embedded so `--web` needs no extra files on disk
+const DASHBOARD_HTML: &str = include_str!("dashboard.html");
+
+/// What the server searches for on every request
+pub struct ServeOptions {
+    pub pattern: String,
+    pub file_type: Option<String>,
+    pub directory: PathBuf,
+}
+
+/// Number of TODOs added in one month of git history
+#[derive(serde::Serialize)]
+struct TrendPoint {
+    month: String,
+    added: usize,
+}
+
+/// Turn a listen address into something `TcpListener::bind` accepts.
+/// A bare ":PORT" binds all interfaces, like most exporters do.
+pub fn parse_listen_addr(listen: &str) -> String {
+    match listen.strip_prefix(':') {
+        Some(port) => format!("0.0.0.0:{}", port),
+        None => listen.to_string(),
+    }
+}
+
+pub fn serve(listen: &str, web: bool, options: ServeOptions) -> Result<()> {
+    let addr = parse_listen_addr(listen);
+    let listener = TcpListener::bind(&addr).with_context(|| tr!("Failed to listen on {}", addr))?;
+
+    if web {
+        println!("{}", tr!("Serving dashboard on http://{}/", addr));
+    } else {
+        println!("{}", tr!("Serving TODO data on http://{}/api/todos", addr));
+    }
+
+    // Requests are cheap and rare, so handle them one at a time
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(s) => s,
+            Err(_) => continue,
+        };
+        if let Err(e) = handle_connection(stream, web, &options) {
+            eprintln!("{}", tr!("Request failed: {}", format!("{:#}", e)));
+        }
+    }
+
+    Ok(())
+}
+
+/// Read the request line and headers, returning the method and the path without query string
+pub fn read_request(stream: &TcpStream) -> Result<(String, String)> {
+    let mut reader = BufReader::new(stream);
+
+    // Request line: "GET /path?query HTTP/1.1"
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+
+    // Drain the headers, we don't need any of them
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or("").to_string();
+    let target = parts.next().unwrap_or("/");
+    let path = target.split('?').next().unwrap_or("/").to_string();
+
+    Ok((method, path))
+}
+
+fn handle_connection(mut stream: TcpStream, web: bool, options: &ServeOptions) -> Result<()> {
+    let (method, path) = read_request(&stream)?;
+
+    if method != "GET" {
+        return write_response(&mut stream, "405 Method Not Allowed", "text/plain", "");
+    }
+
+    match path.as_str() {
+        "/" if web => write_response(&mut stream, "200 OK", "text/html", DASHBOARD_HTML),
+        "/api/todos" => {
+            let matches = collect_current_matches(
+                &options.pattern,
+                options.file_type.as_deref(),
+                &options.directory,
+            )?;
+            let body = serde_json::to_string(&matches)?;
+            write_response(&mut stream, "200 OK", "application/json", &body)
+        }
+        "/api/trend" => {
+            let body = serde_json::to_string(&trend(options)?)?;
+            write_response(&mut stream, "200 OK", "application/json", &body)
+        }
+        _ => write_response(&mut stream, "404 Not Found", "text/plain", "Not found"),
+    }
+}
+
+/// Count additions of the pattern per month over the whole git history
+fn trend(options: &ServeOptions) -> Result<Vec<TrendPoint>> {
+    let added_lines = git_log_added_lines(&options.pattern, None, None, &[], &options.directory)?;
+
+    let mut by_month: BTreeMap<String, usize> = BTreeMap::new();
+    for added in &added_lines {
+        *by_month
+            .entry(added.commit_date.format("%Y-%m").to_string())
+            .or_default() += 1;
+    }
+
+    Ok(by_month
+        .into_iter()
+        .map(|(month, added)| TrendPoint { month, added })
+        .collect())
+}
+
+pub fn write_response(
+    stream: &mut TcpStream,
+    status: &str,
+    content_type: &str,
+    body: &str,
+) -> Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )?;
+    stream.flush()?;
+    Ok(())
+}
@@ -0,0 +1,151 @@
+use std::collections::BTreeMap;
+use std::sync::{Mutex, OnceLock};
+
+use crate::i18n::tr;
+use crate::style::{self, Element};
+
+/// Files that couldn't be read (or shouldn't be searched) during this run, with the reason,
+/// reported at the end instead of silently leaving their TODOs out
+static SKIPPED: Mutex<BTreeMap<String, String>> = Mutex::new(BTreeMap::new());
+
+/// Average line length (in bytes) above which a file counts as minified or generated;
+/// None with --include-minified
+static MINIFIED_LINE_LENGTH: OnceLock<Option<usize>> = OnceLock::new();
+
+/// Minified files left out during this run, with their average line length
+static MINIFIED: Mutex<BTreeMap<String, usize>> = Mutex::new(BTreeMap::new());
+
+pub fn init_minified(threshold: Option<usize>) {
+    let _ = MINIFIED_LINE_LENGTH.set(threshold);
+}
+
+/// The line length above which files count as minified; None with --include-minified
+pub fn minified_line_length() -> Option<usize> {
+    MINIFIED_LINE_LENGTH.get().copied().flatten()
+}
+
+/// Whether a file looks minified or bundled (its lines are long on average), noting it as
+/// skipped if so. Notebooks are left alone: only their cells are searched, and outputs such
+/// as images make their lines long.
+pub fn minified(file: &str) -> bool {
+    let Some(threshold) = minified_line_length() else {
+        return false;
+    };
+    if crate::notebook::is_notebook(file) {
+        return false;
+    }
+    let Ok(content) = std::fs::read(file) else {
+        return false;
+    };
+    let lines = content
+        .split(|&b| b == b'\n')
+        .filter(|l| !l.is_empty())
+        .count();
+    let average = content.len() / lines.max(1);
+    if average <= threshold {
+        return false;
+    }
+    tracing::debug!(file, average, "skipped minified file");
+    if let Ok(mut minified) = MINIFIED.lock() {
+        minified.insert(file.to_string(), average);
+    }
+    true
+}
+
+/// Note a file that had to be skipped
+pub fn record(file: &str, reason: impl std::fmt::Display) {
+    tracing::debug!(file, %reason, "skipped file");
+    if let Ok(mut skipped) = SKIPPED.lock() {
+        skipped
+            .entry(file.to_string())
+            .or_insert_with(|| reason.to_string());
+    }
+}
+
+/// Read a file as text, noting it as skipped if that fails
+pub fn read_to_string(path: &std::path::Path) -> Option<String> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => Some(content),
+        Err(e) if e.kind() == std::io::ErrorKind::InvalidData => {
+            record(&path.display().to_string(), "not valid UTF-8");
+            None
+        }
+        Err(e) => {
+            record(&path.display().to_string(), e);
+            None
+        }
+    }
+}
+
+/// Record the files ripgrep complained about on stderr ("rg: <path>: <reason>")
+pub fn record_ripgrep_errors(stderr: &str) {
+    for line in stderr.lines() {
+        if let Some((file, reason)) = line.strip_prefix("rg: ").and_then(|l| l.split_once(": ")) {
+            record(file, reason);
+        }
+    }
+}
+
+/// Print how many files were skipped on stderr, listing them when `details` is set
+pub fn report(details: bool) {
+    report_minified(details);
+    let Ok(skipped) = SKIPPED.lock() else {
+        return;
+    };
+    if skipped.is_empty() {
+        return;
+    }
+    if !details {
+        eprintln!(
+            "\n{}",
+            tr!(
+                "Skipped {} file(s) that couldn't be searched (-v for details).",
+                skipped.len()
+            )
+        );
+        return;
+    }
+    eprintln!(
+        "\n{}",
+        tr!(
+            "Skipped {} file(s) that couldn't be searched:",
+            skipped.len()
+        )
+    );
+    for (file, reason) in skipped.iter() {
+        eprintln!("  {}: {}", style::paint(Element::Path, file), reason);
+    }
+}
+
+fn report_minified(details: bool) {
+    let Ok(minified) = MINIFIED.lock() else {
+        return;
+    };
+    if minified.is_empty() {
+        return;
+    }
+    let summary = if details {
+        tr!(
+            "Skipped {} minified file(s) (--include-minified to search them).",
+            minified.len()
+        )
+    } else {
+        tr!(
+            "Skipped {} minified file(s) (--include-minified to search them, -v for details).",
+            minified.len()
+        )
+    };
+    eprintln!("\n{}", summary);
+    if details {
+        for (file, average) in minified.iter() {
+            eprintln!(
+                "{}",
+                tr!(
+                    "  {}: average line of {} characters",
+                    style::paint(Element::Path, file),
+                    average
+                )
+            );
+        }
+    }
+}
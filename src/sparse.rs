@@ -0,0 +1,66 @@
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::process::Command;
+
+use crate::i18n::tr;
+use crate::odb::ObjectReader;
+use crate::pr::git;
+use crate::state::repo_root;
+
+/// Whether the repository containing `directory` uses a sparse checkout
+fn is_sparse(directory: &Path) -> bool {
+    git(&["config", "--bool", "core.sparseCheckout"], directory).is_ok_and(|v| v == "true")
+}
+
+/// Files a sparse checkout keeps out of the working tree (the skip-worktree bit), relative to
+/// the repository root. Empty without a sparse checkout.
+fn outside_cone(root: &Path) -> Result<HashSet<String>> {
+    if !is_sparse(root) {
+        return Ok(HashSet::new());
+    }
+    let output = Command::new("git")
+        .args(["ls-files", "-t", "-z", "--full-name"])
+        .current_dir(root)
+        .output()?;
+    if !output.status.success() {
+        anyhow::bail!(tr!(
+            "git ls-files failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    // "<tag> <path>" per entry; "S" marks skip-worktree files
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .split('\0')
+        .filter_map(|entry| entry.strip_prefix("S "))
+        .map(str::to_string)
+        .collect())
+}
+
+/// Contents of those of `files` that a sparse checkout leaves out of the working tree, as
+/// staged in the index, so their lines can still be found. Files that are in the working
+/// tree, or not in the index at all, have none.
+pub fn unmaterialized<'a>(
+    files: impl IntoIterator<Item = &'a str>,
+    directory: &Path,
+) -> Result<HashMap<String, String>> {
+    let root = repo_root(directory);
+    let skipped = outside_cone(&root)?;
+    let mut contents = HashMap::new();
+    if skipped.is_empty() {
+        return Ok(contents);
+    }
+    tracing::debug!(files = skipped.len(), "sparse checkout");
+
+    let mut objects = ObjectReader::new(&root)?;
+    for file in files {
+        if !skipped.contains(file) || contents.contains_key(file) || root.join(file).exists() {
+            continue;
+        }
+        // ":<path>" is the blob staged for the path
+        if let Some(text) = objects.read_file("", file)? {
+            contents.insert(file.to_string(), text);
+        }
+    }
+    Ok(contents)
+}
@@ -0,0 +1,124 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::process::Command;
+
+use crate::annotate::has_issue_reference;
+use crate::i18n::tr;
+use crate::style::{self, Element};
+use crate::unicode;
+
+/// A line in the staged diff that adds the pattern
+struct StagedLine {
+    file: String,
+    line_number: usize,
+    content: String,
+}
+
+/// Parse `git diff -U0` output into the added lines containing the pattern,
+/// numbered by their position in the staged version of the file
+fn parse_staged_diff(diff: &str, pattern: &str) -> Vec<StagedLine> {
+    let mut results = Vec::new();
+    let mut current_file: Option<String> = None;
+    let mut next_line = 0;
+
+    for line in diff.lines() {
+        if let Some(rest) = line.strip_prefix("+++ ") {
+            // "/dev/null" for deleted files, which can't add anything
+            current_file = rest.strip_prefix("b/").map(String::from);
+        } else if let Some(hunk) = line.strip_prefix("@@ ") {
+            // "@@ -a,b +c,d @@": added lines are numbered from c
+            next_line = hunk
+                .split_whitespace()
+                .find_map(|part| part.strip_prefix('+'))
+                .and_then(|range| range.split(',').next())
+                .and_then(|start| start.parse().ok())
+                .unwrap_or(0);
+        } else if let Some(content) = line.strip_prefix('+') {
+            if let (true, Some(file)) = (unicode::contains(content, pattern), &current_file) {
+                results.push(StagedLine {
+                    file: file.clone(),
+                    line_number: next_line,
+                    content: content.to_string(),
+                });
+            }
+            next_line += 1;
+        }
+    }
+
+    results
+}
+
+/// Report TODOs added by the staged changes. Returns whether the commit should be blocked:
+/// any new TODO does, or with `allow_annotated` only those without an issue reference.
+pub fn check_staged(pattern: &str, directory: &Path, allow_annotated: bool) -> Result<bool> {
+    let output = Command::new("git")
+        .arg("diff")
+        .arg("--cached")
+        .arg("-U0")
+        .arg("--no-color")
+        .arg("--no-ext-diff")
+        .arg("--diff-filter=AM") // Only additions and modifications
+        .current_dir(directory)
+        .output()
+        .with_context(|| tr!("Failed to execute git diff"))?;
+
+    if !output.status.success() {
+        anyhow::bail!(tr!("git diff failed. Is this a git repository?"));
+    }
+
+    let diff = String::from_utf8_lossy(&output.stdout);
+    let staged = parse_staged_diff(&diff, pattern);
+
+    if staged.is_empty() {
+        println!("{}", tr!("No '{}' added in staged changes.", pattern));
+        return Ok(false);
+    }
+
+    let mut blocking = 0;
+    for s in &staged {
+        let annotated = has_issue_reference(&s.content, pattern);
+        let blocks = !(allow_annotated && annotated);
+        if blocks {
+            blocking += 1;
+        }
+        let marker = if blocks {
+            style::paint(Element::Error, tr!("blocked"))
+        } else {
+            style::paint(Element::Added, tr!("allowed"))
+        };
+        println!(
+            "{}:{}: {} ({})",
+            style::paint(Element::Path, &s.file),
+            style::paint(Element::LineNumber, s.line_number),
+            s.content.trim(),
+            marker
+        );
+    }
+
+    if blocking > 0 {
+        eprintln!(
+            "{}",
+            tr!(
+                "\n{} new '{}'(s) staged{}.",
+                blocking,
+                pattern,
+                if allow_annotated {
+                    " without an issue reference, e.g. TODO(#123)"
+                } else {
+                    ""
+                }
+            )
+        );
+    } else {
+        println!(
+            "{}",
+            tr!(
+                "\n{} new '{}'(s) staged, all with issue references.",
+                staged.len(),
+                pattern
+            )
+        );
+    }
+
+    Ok(blocking > 0)
+}
@@ -0,0 +1,109 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::process::Command;
+
+use crate::dates;
+use crate::hyperlink;
+use crate::i18n::tr;
+use crate::parse_git_log_diff;
+use crate::state::repo_root;
+use crate::style::{self, Element};
+
+/// Run git with the given arguments and return stdout, failing on a non-zero exit
+fn git(args: &[&str], directory: &Path) -> Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(directory)
+        .output()
+        .with_context(|| tr!("Failed to execute git"))?;
+    if !output.status.success() {
+        anyhow::bail!(tr!(
+            "git {} failed: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Search the diffs of every stash entry for added lines containing the pattern
+pub fn search_stashes(pattern: &str, directory: &Path) -> Result<()> {
+    // "<hash>\x1f<stash@{n}>\x1f<subject>" per stash, newest first
+    let list = git(&["stash", "list", "--format=%H%x1f%gd%x1f%gs"], directory)?;
+    let stashes: Vec<(&str, &str, &str)> = list
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, '\x1f');
+            Some((fields.next()?, fields.next()?, fields.next()?))
+        })
+        .collect();
+
+    if stashes.is_empty() {
+        println!("{}", tr!("No stashes."));
+        return Ok(());
+    }
+
+    println!(
+        "{}",
+        tr!(
+            "Searching for '{}' in {} stash(es)...\n",
+            pattern,
+            stashes.len()
+        )
+    );
+
+    // Each stash is a merge commit; diff it against its first parent like `git stash show`
+    let log = git(
+        &[
+            "log",
+            "-g",
+            "--first-parent",
+            "-m",
+            "-p",
+            "--format=commit %H%nDate: %ad",
+            "--date=short",
+            "refs/stash",
+        ],
+        directory,
+    )?;
+    let added_lines = parse_git_log_diff(log.as_bytes(), pattern)?;
+    // Diff paths are relative to the repository root
+    let root = repo_root(directory);
+
+    let mut found = 0;
+    for (hash, selector, subject) in &stashes {
+        let lines: Vec<_> = added_lines
+            .iter()
+            .filter(|a| a.commit_hash == *hash)
+            .collect();
+        let Some(first) = lines.first() else {
+            continue;
+        };
+
+        if found > 0 {
+            println!();
+        }
+        println!(
+            "{} ({}): {}",
+            style::paint(Element::Commit, selector),
+            style::paint(Element::Date, dates::shown(first.commit_date)),
+            subject
+        );
+        for added in &lines {
+            println!(
+                "  {}: {}",
+                style::paint(Element::Path, hyperlink::file(&added.file, &root)),
+                added.content.trim()
+            );
+        }
+        found += lines.len();
+    }
+
+    if found == 0 {
+        println!("{}", tr!("No '{}' found in stashes.", pattern));
+    } else {
+        println!("{}", tr!("\nFound {} match(es) in stashes.", found));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,338 @@
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::i18n::tr;
+use crate::style::{self, Element};
+use crate::todo_id;
+use crate::unicode;
+
+/// What was decided about a TODO during triage
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Decision {
+    Accepted,
+    Filed,
+    Snoozed,
+}
+
+/// A triage decision for one TODO, keyed by its ID in `State::triage`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TriageEntry {
+    pub decision: Decision,
+    pub decided_on: NaiveDate,
+    pub file: String,
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issue: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<NaiveDate>,
+}
+
+impl TriageEntry {
+    /// "accepted", "filed as AB#12" or "snoozed until 2025-07-01"
+    pub fn describe(&self) -> String {
+        match (self.decision, &self.issue, self.until) {
+            (Decision::Filed, Some(issue), _) => tr!("filed as {}", issue),
+            (Decision::Snoozed, _, Some(until)) => tr!("snoozed until {}", until),
+            (Decision::Accepted, _, _) => tr!("accepted"),
+            (Decision::Filed, None, _) => tr!("filed"),
+            (Decision::Snoozed, _, None) => tr!("snoozed"),
+        }
+    }
+}
+
+/// A TODO pinned with `fask bookmark`, keyed by its ID in `State::bookmarks`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub file: String,
+    pub text: String,
+    pub added_on: NaiveDate,
+}
+
+/// Labels given to a TODO with `fask label`, keyed by its ID in `State::labels`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LabelEntry {
+    pub file: String,
+    pub text: String,
+    pub labels: BTreeSet<String>,
+}
+
+/// Everything fask remembers about a repository between runs.
+///
+/// Stored in `<repo>/.fask/state.json` when the repository has a `.fask` directory, otherwise
+/// in `$XDG_DATA_HOME/fask/repos/<name>-<hash>/state.json` so the working tree stays clean.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct State {
+    #[serde(default)]
+    pub triage: BTreeMap<String, TriageEntry>,
+
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub bookmarks: BTreeMap<String, Bookmark>,
+
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub labels: BTreeMap<String, LabelEntry>,
+
+    /// Root of the repository the state belongs to; TODO IDs use paths relative to it
+    #[serde(skip)]
+    pub root: PathBuf,
+
+    /// Where the state is read from and saved to
+    #[serde(skip)]
+    pub path: PathBuf,
+}
+
+/// Root of the git repository containing `directory`, or the directory itself outside git
+pub fn repo_root(directory: &Path) -> PathBuf {
+    let toplevel = Command::new("git")
+        .args(["rev-parse", "--show-toplevel"])
+        .current_dir(directory)
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| PathBuf::from(String::from_utf8_lossy(&o.stdout).trim()));
+
+    toplevel
+        .or_else(|| directory.canonicalize().ok())
+        .unwrap_or_else(|| directory.to_path_buf())
+}
+
+/// Per-user data directory, following the XDG base directory spec
+fn data_home() -> PathBuf {
+    if let Some(dir) = std::env::var_os("XDG_DATA_HOME").filter(|d| !d.is_empty()) {
+        return PathBuf::from(dir);
+    }
+    let home = std::env::var_os("HOME").unwrap_or_default();
+    PathBuf::from(home).join(".local").join("share")
+}
+
+/// Directory of the state file for a repository root
+pub fn state_dir(root: &Path) -> PathBuf {
+    let local = root.join(".fask");
+    if local.is_dir() {
+        return local;
+    }
+
+    data_home().join("fask").join("repos").join(repo_key(root))
+}
+
+/// Location of the state file for a repository root
+fn state_path(root: &Path) -> PathBuf {
+    state_dir(root).join("state.json")
+}
+
+/// Directory name for a repository's files outside of it: its name (for humans) and a hash of
+/// the full path (for uniqueness)
+pub fn repo_key(root: &Path) -> String {
+    let name = root
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "root".to_string());
+    format!("{}-{}", name, todo_id(&root.to_string_lossy(), ""))
+}
+
+impl State {
+    /// Load the state for the repository containing `directory`, or an empty one if
+    /// nothing was saved yet
+    pub fn load(directory: &Path) -> Result<State> {
+        let root = repo_root(directory);
+        let path = state_path(&root);
+
+        let mut state = if path.exists() {
+            let content = std::fs::read_to_string(&path)
+                .with_context(|| tr!("Failed to read state: {}", path.display()))?;
+            serde_json::from_str(&content)
+                .with_context(|| tr!("Failed to parse state: {}", path.display()))?
+        } else {
+            State::default()
+        };
+
+        state.root = root;
+        state.path = path;
+        Ok(state)
+    }
+
+    /// IDs of TODOs snoozed until today or later: a snooze lasts through its date
+    pub fn snoozed_ids(&self, today: NaiveDate) -> HashSet<String> {
+        self.triage
+            .iter()
+            .filter(|(_, entry)| {
+                entry.decision == Decision::Snoozed && entry.until.is_some_and(|u| u >= today)
+            })
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+
+    /// Labels of the TODO with this ID, sorted
+    pub fn labels_of(&self, id: &str) -> Vec<String> {
+        self.labels
+            .get(id)
+            .map(|entry| entry.labels.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Whether the TODO with this ID has every one of `labels`
+    pub fn has_labels(&self, id: &str, labels: &[String]) -> bool {
+        let entry = self.labels.get(id);
+        labels
+            .iter()
+            .all(|label| entry.is_some_and(|e| e.labels.contains(label)))
+    }
+
+    pub fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| tr!("Failed to create {}", parent.display()))?;
+        }
+        let content = serde_json::to_string_pretty(self)?;
+        std::fs::write(&self.path, content)
+            .with_context(|| tr!("Failed to write state: {}", self.path.display()))
+    }
+}
+
+/// Print where the state lives
+pub fn print_path(directory: &Path) -> Result<()> {
+    let state = State::load(directory)?;
+    println!("{}", state.path.display());
+    Ok(())
+}
+
+/// Print a summary of the state, or the raw JSON
+pub fn show(directory: &Path, json: bool) -> Result<()> {
+    let state = State::load(directory)?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&state)?);
+        return Ok(());
+    }
+
+    println!("{}", tr!("State: {}", state.path.display()));
+    println!("{}", tr!("Repository: {}\n", state.root.display()));
+
+    if state.triage.is_empty() {
+        println!("{}", tr!("No triage decisions."));
+    } else {
+        println!("{}", tr!("Triage decisions ({}):", state.triage.len()));
+        for (id, entry) in &state.triage {
+            println!(
+                "  {}  {}: {} ({}, {})",
+                id,
+                style::paint(Element::Path, &entry.file),
+                entry.text,
+                entry.describe(),
+                entry.decided_on
+            );
+        }
+    }
+
+    if !state.bookmarks.is_empty() {
+        println!("{}", tr!("\nBookmarks ({}):", state.bookmarks.len()));
+        for (id, bookmark) in &state.bookmarks {
+            println!(
+                "{}",
+                tr!(
+                    "  {}  {}: {} (since {})",
+                    id,
+                    style::paint(Element::Path, &bookmark.file),
+                    bookmark.text,
+                    bookmark.added_on
+                )
+            );
+        }
+    }
+
+    if !state.labels.is_empty() {
+        println!("{}", tr!("\nLabels ({}):", state.labels.len()));
+        for (id, entry) in &state.labels {
+            let labels: Vec<&str> = entry.labels.iter().map(String::as_str).collect();
+            println!(
+                "  {}  {}: {} [{}]",
+                id,
+                style::paint(Element::Path, &entry.file),
+                entry.text,
+                labels.join(", ")
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether the TODO an entry was recorded for still exists in the repository
+fn still_exists(root: &Path, file: &str, text: &str) -> bool {
+    std::fs::read_to_string(root.join(file))
+        .map(|content| content.lines().any(|line| unicode::same(line.trim(), text)))
+        .unwrap_or(false)
+}
+
+/// Drop entries, bookmarks and labels for TODOs that no longer exist, and snoozes that have expired
+pub fn prune(directory: &Path, dry_run: bool) -> Result<()> {
+    let mut state = State::load(directory)?;
+    let today = chrono::Local::now().date_naive();
+
+    let stale: Vec<String> = state
+        .triage
+        .iter()
+        .filter(|(_, entry)| {
+            let expired =
+                entry.decision == Decision::Snoozed && entry.until.is_some_and(|u| u < today);
+            expired || !still_exists(&state.root, &entry.file, &entry.text)
+        })
+        .map(|(id, _)| id.clone())
+        .collect();
+
+    let gone: Vec<String> = state
+        .bookmarks
+        .iter()
+        .filter(|(_, bookmark)| !still_exists(&state.root, &bookmark.file, &bookmark.text))
+        .map(|(id, _)| id.clone())
+        .collect();
+    let unlabeled: Vec<String> = state
+        .labels
+        .iter()
+        .filter(|(_, entry)| !still_exists(&state.root, &entry.file, &entry.text))
+        .map(|(id, _)| id.clone())
+        .collect();
+
+    for id in &stale {
+        let entry = &state.triage[id];
+        println!("  {}  {}: {}", id, entry.file, entry.text);
+    }
+    for id in &gone {
+        let bookmark = &state.bookmarks[id];
+        println!(
+            "{}",
+            tr!("  {}  {}: {} (bookmark)", id, bookmark.file, bookmark.text)
+        );
+    }
+    for id in &unlabeled {
+        let entry = &state.labels[id];
+        println!(
+            "{}",
+            tr!("  {}  {}: {} (labels)", id, entry.file, entry.text)
+        );
+    }
+
+    let count = stale.len() + gone.len() + unlabeled.len();
+    if dry_run {
+        println!("{}", tr!("Would prune {} entr(ies).", count));
+        return Ok(());
+    }
+
+    for id in &stale {
+        state.triage.remove(id);
+    }
+    for id in &gone {
+        state.bookmarks.remove(id);
+    }
+    for id in &unlabeled {
+        state.labels.remove(id);
+    }
+    state.save()?;
+    println!("{}", tr!("Pruned {} entr(ies).", count));
+
+    Ok(())
+}
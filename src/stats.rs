@@ -0,0 +1,245 @@
+use anyhow::Result;
+use chrono::NaiveDate;
+use clap::ValueEnum;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::history::{lifetimes, Lifetime};
+use crate::i18n::tr;
+use crate::style::{self, Element};
+
+/// How `stats --burndown` prints its series
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum BurndownFormat {
+    /// date,added,resolved,open rows with a header
+    Csv,
+    /// One JSON object per date
+    Json,
+}
+
+/// Open count at the end of a day that something was added or resolved
+#[derive(Serialize)]
+struct BurndownPoint {
+    date: NaiveDate,
+    added: usize,
+    resolved: usize,
+    open: i64,
+}
+
+/// Running open count (added minus resolved), one point per day with changes
+fn burndown(lifetimes: &[Lifetime]) -> Vec<BurndownPoint> {
+    let mut days: BTreeMap<NaiveDate, (usize, usize)> = BTreeMap::new();
+    for l in lifetimes {
+        days.entry(l.added).or_default().0 += 1;
+        if let Some(resolved) = l.resolved {
+            days.entry(resolved).or_default().1 += 1;
+        }
+    }
+    let mut open = 0;
+    days.into_iter()
+        .map(|(date, (added, resolved))| {
+            open += added as i64 - resolved as i64;
+            BurndownPoint {
+                date,
+                added,
+                resolved,
+                open,
+            }
+        })
+        .collect()
+}
+
+/// Print the burn-down series on stdout
+fn print_burndown(lifetimes: &[Lifetime], format: BurndownFormat) -> Result<()> {
+    let points = burndown(lifetimes);
+    match format {
+        BurndownFormat::Csv => {
+            println!("date,added,resolved,open");
+            for p in &points {
+                println!("{},{},{},{}", p.date, p.added, p.resolved, p.open);
+            }
+        }
+        BurndownFormat::Json => {
+            for p in &points {
+                println!("{}", serde_json::to_string(p)?);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Value at the given percentile (0-100) of sorted values, by nearest rank
+fn percentile(sorted: &[i64], p: usize) -> i64 {
+    let rank = (sorted.len() * p).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+/// Top-level directory of a repository-relative path ("." for files at the root)
+fn top_level_dir(file: &str) -> &str {
+    match file.split_once('/') {
+        Some((dir, _)) => dir,
+        None => ".",
+    }
+}
+
+/// Print one row of resolution-time percentiles
+fn print_resolution_row(label: &str, width: usize, days: &mut [i64]) {
+    days.sort_unstable();
+    println!(
+        "{:<width$}  {:>5}  {:>6}  {:>5}  {:>5}  {:>5}",
+        label,
+        days.len(),
+        percentile(days, 50),
+        percentile(days, 75),
+        percentile(days, 90),
+        days[days.len() - 1],
+        width = width
+    );
+}
+
+/// Print resolution-time percentiles in days, overall, per directory and per author
+fn print_resolution_time(resolved: &[&Lifetime]) {
+    let mut by_dir: BTreeMap<&str, Vec<i64>> = BTreeMap::new();
+    let mut by_author: BTreeMap<&str, Vec<i64>> = BTreeMap::new();
+    let mut all = Vec::new();
+    for l in resolved {
+        let Some(days) = l.days_open() else {
+            continue;
+        };
+        all.push(days);
+        by_dir.entry(top_level_dir(&l.file)).or_default().push(days);
+        by_author.entry(&l.added_by).or_default().push(days);
+    }
+
+    let (resolved_label, all_label) = (tr!("RESOLVED"), tr!("all"));
+    let width = by_dir
+        .keys()
+        .chain(by_author.keys())
+        .map(|k| k.chars().count() + 2)
+        .chain([
+            resolved_label.chars().count() + 1,
+            all_label.chars().count(),
+        ])
+        .max()
+        .unwrap_or(9);
+    let header = format!(
+        "{:<width$}  {:>5}  {:>6}  {:>5}  {:>5}  {:>5}",
+        resolved_label,
+        tr!("COUNT"),
+        tr!("MEDIAN"),
+        "P75",
+        "P90",
+        tr!("MAX"),
+        width = width
+    );
+    println!(
+        "{}",
+        tr!("\n{}  (days open)", style::paint(Element::Heading, header))
+    );
+    print_resolution_row(&all_label, width, &mut all);
+
+    println!("{}", tr!("by directory"));
+    for (dir, days) in &mut by_dir {
+        print_resolution_row(&format!("  {}", dir), width, days);
+    }
+    println!("{}", tr!("by author"));
+    for (author, days) in &mut by_author {
+        print_resolution_row(&format!("  {}", author), width, days);
+    }
+}
+
+/// Added, resolved and net TODOs per author within the period, most debt paid off first
+fn print_by_author(lifetimes: &[Lifetime], since: Option<NaiveDate>, until: Option<NaiveDate>) {
+    let in_period = |date: NaiveDate| {
+        since.is_none_or(|since| date >= since) && until.is_none_or(|until| date <= until)
+    };
+    let mut counts: BTreeMap<&str, (i64, i64)> = BTreeMap::new();
+    for l in lifetimes {
+        if in_period(l.added) {
+            counts.entry(&l.added_by).or_default().0 += 1;
+        }
+        if let (Some(date), Some(by)) = (l.resolved, &l.resolved_by) {
+            if in_period(date) {
+                counts.entry(by).or_default().1 += 1;
+            }
+        }
+    }
+
+    let mut rows: Vec<(&str, i64, i64)> = counts
+        .into_iter()
+        .map(|(author, (added, resolved))| (author, added, resolved))
+        .collect();
+    rows.sort_by_key(|&(author, added, resolved)| {
+        (added - resolved, std::cmp::Reverse(resolved), author)
+    });
+
+    let author_label = tr!("AUTHOR");
+    let width = rows
+        .iter()
+        .map(|(author, _, _)| author.chars().count())
+        .chain([author_label.chars().count()])
+        .max()
+        .unwrap_or(6);
+    let header = format!(
+        "{:<width$}  {:>5}  {:>8}  {:>5}",
+        author_label,
+        tr!("ADDED"),
+        tr!("RESOLVED"),
+        tr!("NET"),
+        width = width
+    );
+    println!("\n{}", style::paint(Element::Heading, header));
+    for (author, added, resolved) in rows {
+        println!(
+            "{:<width$}  {:>5}  {:>8}  {:>+5}",
+            author,
+            added,
+            resolved,
+            added - resolved,
+            width = width
+        );
+    }
+}
+
+/// Summarize the history of the pattern: how many were added, resolved and are still open
+pub fn stats(
+    pattern: &str,
+    directory: &Path,
+    resolution_time: bool,
+    burndown: Option<BurndownFormat>,
+    by_author: bool,
+    since: Option<NaiveDate>,
+    until: Option<NaiveDate>,
+) -> Result<()> {
+    let lifetimes = lifetimes(pattern, directory)?;
+    // The series is meant for piping into a chart, so it goes out alone
+    if let Some(format) = burndown {
+        return print_burndown(&lifetimes, format);
+    }
+    let resolved: Vec<&Lifetime> = lifetimes.iter().filter(|l| l.resolved.is_some()).collect();
+
+    println!(
+        "{}",
+        tr!(
+            "{} '{}'(s) added, {} resolved, {} open.",
+            lifetimes.len(),
+            pattern,
+            resolved.len(),
+            lifetimes.len() - resolved.len()
+        )
+    );
+
+    if resolution_time {
+        if resolved.is_empty() {
+            println!("{}", tr!("No resolved '{}'s to time.", pattern));
+        } else {
+            print_resolution_time(&resolved);
+        }
+    }
+    if by_author {
+        print_by_author(&lifetimes, since, until);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,209 @@
+use clap::ValueEnum;
+use std::fmt::Display;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+use crate::i18n::tr;
+
+/// Whether to print plain ASCII (--plain): no colors, dimming or symbols, for screen readers
+/// and terminals that handle them badly
+static PLAIN: AtomicBool = AtomicBool::new(false);
+
+/// The terminal style of each element, indexed by `Element`
+static THEME: OnceLock<Vec<String>> = OnceLock::new();
+
+/// What a piece of output is, which the theme gives its color
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Element {
+    Path,
+    LineNumber,
+    /// The pattern within a matching line
+    Match,
+    Commit,
+    Date,
+    /// Context lines and side notes, dimmed by default
+    Context,
+    Heading,
+    Label,
+    Added,
+    Removed,
+    Warning,
+    Error,
+}
+
+/// Every element with its key in a `[theme]` section
+pub const ELEMENTS: [(Element, &str); 12] = [
+    (Element::Path, "path"),
+    (Element::LineNumber, "line-number"),
+    (Element::Match, "match"),
+    (Element::Commit, "commit"),
+    (Element::Date, "date"),
+    (Element::Context, "context"),
+    (Element::Heading, "heading"),
+    (Element::Label, "label"),
+    (Element::Added, "added"),
+    (Element::Removed, "removed"),
+    (Element::Warning, "warning"),
+    (Element::Error, "error"),
+];
+
+/// Built-in themes, which a `[theme]` section can start from
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Theme {
+    /// 16 colors for dark backgrounds, like ripgrep's
+    Dark,
+    /// 256 colors that stay readable on light backgrounds
+    Light,
+    /// 24-bit colors, for terminals that support them
+    Truecolor,
+}
+
+impl Theme {
+    /// Terminal styles in `ELEMENTS` order
+    fn styles(self) -> [&'static str; 12] {
+        match self {
+            Theme::Dark => [
+                "35", "32", "1;31", "33", "36", "2", "1", "36", "32", "31", "33", "31",
+            ],
+            Theme::Light => [
+                "38;5;90",
+                "38;5;28",
+                "1;38;5;160",
+                "38;5;130",
+                "38;5;24",
+                "38;5;244",
+                "1",
+                "38;5;24",
+                "38;5;28",
+                "38;5;160",
+                "38;5;166",
+                "38;5;160",
+            ],
+            Theme::Truecolor => [
+                "38;2;198;120;221",
+                "38;2;152;195;121",
+                "1;38;2;224;108;117",
+                "38;2;229;192;123",
+                "38;2;86;182;194",
+                "38;2;127;132;142",
+                "1",
+                "38;2;97;175;239",
+                "38;2;152;195;121",
+                "38;2;224;108;117",
+                "38;2;229;192;123",
+                "38;2;224;108;117",
+            ],
+        }
+    }
+}
+
+/// The terminal style for a color in a `[theme]` section: space-separated words out of
+/// a color name ("magenta", "bright-blue"), a 256-color number, "#rrggbb", "bold", "dim",
+/// "italic" and "underline", or "none" for no style
+pub fn parse_color(spec: &str) -> Result<String, String> {
+    const NAMES: [&str; 8] = [
+        "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+    ];
+    let mut codes = Vec::new();
+    for word in spec.split_whitespace() {
+        let code = match word {
+            "none" => continue,
+            "bold" => "1".to_string(),
+            "dim" => "2".to_string(),
+            "italic" => "3".to_string(),
+            "underline" => "4".to_string(),
+            _ => {
+                if let Some(i) = NAMES.iter().position(|n| *n == word) {
+                    (30 + i).to_string()
+                } else if let Some(i) = word
+                    .strip_prefix("bright-")
+                    .and_then(|name| NAMES.iter().position(|n| *n == name))
+                {
+                    (90 + i).to_string()
+                } else if let Ok(n) = word.parse::<u8>() {
+                    format!("38;5;{}", n)
+                } else if let Some(hex) = word
+                    .strip_prefix('#')
+                    .filter(|h| h.len() == 6 && h.chars().all(|c| c.is_ascii_hexdigit()))
+                {
+                    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).unwrap_or(0);
+                    format!("38;2;{};{};{}", channel(0), channel(2), channel(4))
+                } else {
+                    return Err(format!(
+                        "unknown color '{}' (a name like magenta or bright-blue, 0-255, #rrggbb, bold, dim, italic, underline or none)",
+                        word
+                    ));
+                }
+            }
+        };
+        codes.push(code);
+    }
+    Ok(codes.join(";"))
+}
+
+/// Pick the colors: the built-in `theme` (--theme, else the `[theme]` name, else dark) with
+/// the `[theme]` section's colors on top. Anything wrong in the section is reported and left
+/// out, so a typo there doesn't stop every command.
+pub fn init(plain: bool, theme: Option<Theme>, config: &crate::config::ThemeConfig) {
+    PLAIN.store(plain, Ordering::Relaxed);
+    if plain {
+        return;
+    }
+
+    let named = config.name.as_deref().and_then(|name| {
+        let theme = Theme::from_str(name, true).ok();
+        if theme.is_none() {
+            eprintln!("{}", tr!("Ignoring unknown theme '{}' in .fask.toml", name));
+        }
+        theme
+    });
+    let base = theme.or(named).unwrap_or(Theme::Dark);
+    let mut styles: Vec<String> = base.styles().iter().map(|s| s.to_string()).collect();
+    for (key, spec) in &config.colors {
+        let Some(index) = ELEMENTS.iter().position(|(_, k)| k == key) else {
+            eprintln!(
+                "{}",
+                tr!("Ignoring unknown [theme] key '{}' in .fask.toml", key)
+            );
+            continue;
+        };
+        match parse_color(spec) {
+            Ok(style) => styles[index] = style,
+            Err(e) => eprintln!("{}", tr!("Ignoring [theme] {} in .fask.toml: {}", key, e)),
+        }
+    }
+    let _ = THEME.set(styles);
+}
+
+pub fn plain() -> bool {
+    PLAIN.load(Ordering::Relaxed)
+}
+
+/// `text` in the theme's style for `element`, or as is with --plain
+pub fn paint(element: Element, text: impl Display) -> String {
+    if plain() {
+        return text.to_string();
+    }
+    let index = ELEMENTS
+        .iter()
+        .position(|(e, _)| *e == element)
+        .unwrap_or_default();
+    let style = match THEME.get() {
+        Some(styles) => styles[index].as_str(),
+        None => Theme::Dark.styles()[index],
+    };
+    if style.is_empty() {
+        text.to_string()
+    } else {
+        format!("\x1b[{}m{}\x1b[0m", style, text)
+    }
+}
+
+/// `symbol`, or its ASCII stand-in with --plain
+pub fn symbol(symbol: &'static str, ascii: &'static str) -> &'static str {
+    if plain() {
+        ascii
+    } else {
+        symbol
+    }
+}
@@ -0,0 +1,206 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+/// Whether a match is in a string literal, or in a comment (or anywhere else in the code)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Kind {
+    #[default]
+    Comment,
+    String,
+}
+
+/// What `current` does with matches in string literals (--strings)
+#[derive(Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum Strings {
+    /// Show them like any other match
+    #[default]
+    Include,
+    /// Leave them out, e.g. a "TODO" in user-facing text
+    Exclude,
+    /// Show only them
+    Only,
+    /// Show them with an "(in a string)" note
+    Label,
+}
+
+impl Strings {
+    pub fn keeps(self, kind: Kind) -> bool {
+        match self {
+            Strings::Include | Strings::Label => true,
+            Strings::Exclude => kind != Kind::String,
+            Strings::Only => kind == Kind::String,
+        }
+    }
+}
+
+/// How a language writes comments and strings
+struct Syntax {
+    line_comments: &'static [&'static str],
+    block_comment: Option<(&'static str, &'static str)>,
+    quotes: &'static str,
+}
+
+const C_LIKE: Syntax = Syntax {
+    line_comments: &["//"],
+    block_comment: Some(("/*", "*/")),
+    quotes: "\"",
+};
+
+/// The syntax of a file, by its extension. Languages fask doesn't know are read as C-like,
+/// where `'` starts a character rather than a string.
+fn syntax(file: &str) -> Syntax {
+    let name = file.rsplit('/').next().unwrap_or(file).to_lowercase();
+    let extension = name.rsplit_once('.').map_or(name.as_str(), |(_, ext)| ext);
+    match extension {
+        "py" | "ipynb" | "rb" | "sh" | "bash" | "zsh" | "pl" | "r" | "yaml" | "yml" | "toml"
+        | "cmake" | "tf" | "nim" | "ps1" | "makefile" | "dockerfile" => Syntax {
+            line_comments: &["#"],
+            block_comment: None,
+            quotes: "\"'`",
+        },
+        "js" | "jsx" | "ts" | "tsx" | "mjs" | "cjs" | "php" | "go" => Syntax {
+            quotes: "\"'`",
+            ..C_LIKE
+        },
+        "css" | "scss" | "less" => Syntax {
+            line_comments: &[],
+            quotes: "\"'",
+            ..C_LIKE
+        },
+        "sql" | "lua" | "hs" | "elm" => Syntax {
+            line_comments: &["--"],
+            block_comment: None,
+            quotes: "\"'",
+        },
+        "html" | "htm" | "xml" | "vue" | "svelte" => Syntax {
+            line_comments: &[],
+            block_comment: Some(("<!--", "-->")),
+            quotes: "\"'",
+        },
+        "lisp" | "clj" | "el" | "scm" | "ini" | "asm" => Syntax {
+            line_comments: &[";"],
+            block_comment: None,
+            quotes: "\"",
+        },
+        "tex" | "erl" => Syntax {
+            line_comments: &["%"],
+            block_comment: None,
+            quotes: "",
+        },
+        _ => C_LIKE,
+    }
+}
+
+/// Where a scan of a line stands at some byte
+enum State {
+    Code,
+    LineComment,
+    BlockComment,
+    Quote(char),
+}
+
+/// Prose has no strings or comments
+fn is_prose(file: &str) -> bool {
+    file.ends_with(".md") || file.ends_with(".txt") || file.ends_with(".rst")
+}
+
+/// Scan `line` up to byte `at`
+fn state_at(syntax: &Syntax, line: &str, at: usize) -> State {
+    let mut quote: Option<char> = None;
+    let mut in_block = false;
+    let mut chars = line.char_indices();
+    while let Some((idx, c)) = chars.next() {
+        if idx >= at {
+            break;
+        }
+        let rest = &line[idx..];
+        if in_block {
+            if let Some((_, close)) = syntax
+                .block_comment
+                .filter(|(_, close)| rest.starts_with(close))
+            {
+                in_block = false;
+                // Skip the rest of the closing marker
+                for _ in 1..close.len() {
+                    chars.next();
+                }
+            }
+            continue;
+        }
+        match quote {
+            Some(q) => {
+                if c == '\\' {
+                    chars.next();
+                } else if c == q {
+                    quote = None;
+                }
+            }
+            None => {
+                if syntax
+                    .line_comments
+                    .iter()
+                    .any(|start| rest.starts_with(start))
+                {
+                    return State::LineComment;
+                }
+                if let Some((open, _)) = syntax
+                    .block_comment
+                    .filter(|(open, _)| rest.starts_with(open))
+                {
+                    in_block = true;
+                    for _ in 1..open.len() {
+                        chars.next();
+                    }
+                } else if syntax.quotes.contains(c) {
+                    quote = Some(c);
+                }
+            }
+        }
+    }
+    match (quote, in_block) {
+        (Some(q), _) => State::Quote(q),
+        (None, true) => State::BlockComment,
+        (None, false) => State::Code,
+    }
+}
+
+/// Whether the text at byte `at` of `line` is inside a string literal. Only the line is
+/// read, so strings and comments that span lines count from where they show.
+pub fn classify(file: &str, line: &str, at: usize) -> Kind {
+    if is_prose(file) {
+        return Kind::Comment;
+    }
+    match state_at(&syntax(file), line, at) {
+        State::Quote(_) => Kind::String,
+        _ => Kind::Comment,
+    }
+}
+
+/// Where the text from byte `at` of `line` ends: at the quote that closes the string
+/// literal it is in, or the marker that closes its block comment, else with the line
+pub fn text_end(file: &str, line: &str, at: usize) -> usize {
+    if is_prose(file) {
+        return line.len();
+    }
+    let syntax = syntax(file);
+    let rest = &line[at..];
+    let end = match state_at(&syntax, line, at) {
+        State::Quote(q) => {
+            let mut chars = rest.char_indices();
+            let mut end = None;
+            while let Some((idx, c)) = chars.next() {
+                if c == '\\' {
+                    chars.next();
+                } else if c == q {
+                    end = Some(idx);
+                    break;
+                }
+            }
+            end
+        }
+        State::BlockComment => syntax.block_comment.and_then(|(_, close)| rest.find(close)),
+        State::Code | State::LineComment => None,
+    };
+    end.map_or(line.len(), |end| at + end)
+}
@@ -0,0 +1,102 @@
+use crate::i18n::tr;
+use crate::style::{self, Element};
+use crate::terminal_width;
+use crate::top::truncate;
+
+/// A match as a row of `--format table`
+pub struct Row<'a> {
+    pub file: &'a str,
+    pub line_number: usize,
+    /// The text the pattern matched
+    pub keyword: &'a str,
+    pub owner: Option<&'a str>,
+    pub age: Option<String>,
+    pub text: &'a str,
+}
+
+/// The width of a column: its widest cell, or its header
+fn column_width(header: &str, cells: impl Iterator<Item = usize>) -> usize {
+    cells
+        .chain([header.chars().count()])
+        .max()
+        .unwrap_or_default()
+}
+
+/// Print matches as an aligned table of FILE, LINE, KEYWORD, OWNER, AGE and TEXT, each
+/// column as wide as its widest cell and the text cut at the terminal edge
+pub fn print(rows: &[Row]) {
+    if rows.is_empty() {
+        println!("{}", tr!("No matches found."));
+        return;
+    }
+
+    let (file, line, keyword, owner, age, text) = (
+        tr!("FILE"),
+        tr!("LINE"),
+        tr!("KEYWORD"),
+        tr!("OWNER"),
+        tr!("AGE"),
+        tr!("TEXT"),
+    );
+    let file_width = column_width(&file, rows.iter().map(|r| r.file.chars().count()));
+    let line_width = column_width(&line, rows.iter().map(|r| r.line_number.to_string().len()));
+    let keyword_width = column_width(&keyword, rows.iter().map(|r| r.keyword.chars().count()));
+    let owner_width = column_width(
+        &owner,
+        rows.iter()
+            .map(|r| r.owner.map_or(1, |o| o.chars().count())),
+    );
+    let age_width = column_width(
+        &age,
+        rows.iter()
+            .map(|r| r.age.as_deref().map_or(1, |a| a.chars().count())),
+    );
+
+    let header = format!(
+        "{:<fw$}  {:>lw$}  {:<kw$}  {:<ow$}  {:<aw$}  {}",
+        file,
+        line,
+        keyword,
+        owner,
+        age,
+        text,
+        fw = file_width,
+        lw = line_width,
+        kw = keyword_width,
+        ow = owner_width,
+        aw = age_width
+    );
+    // Whatever the other columns leave over goes to the text
+    let text_width = terminal_width().map(|w| {
+        w.saturating_sub(header.chars().count() - text.chars().count())
+            .max(20)
+    });
+    println!("{}", style::paint(Element::Heading, &header));
+
+    // Padding goes outside the colors, which take no room
+    let pad = |text: &str, width: usize| " ".repeat(width.saturating_sub(text.chars().count()));
+    for row in rows {
+        let owner = row.owner.unwrap_or("-");
+        let age = row.age.as_deref().unwrap_or("-");
+        let line_number = row.line_number.to_string();
+        let text = row.text.trim();
+        let text = match text_width {
+            Some(width) => truncate(text, width),
+            None => text.to_string(),
+        };
+        println!(
+            "{}{}  {}{}  {}{}  {}{}  {}{}  {}",
+            style::paint(Element::Path, row.file),
+            pad(row.file, file_width),
+            pad(&line_number, line_width),
+            style::paint(Element::LineNumber, &line_number),
+            style::paint(Element::Match, row.keyword),
+            pad(row.keyword, keyword_width),
+            owner,
+            pad(owner, owner_width),
+            style::paint(Element::Date, age),
+            pad(age, age_width),
+            text
+        );
+    }
+}
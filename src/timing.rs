@@ -0,0 +1,78 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::i18n::tr;
+
+/// Whether `--timing` was given; phases aren't recorded otherwise
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Wall time per phase, in the order the phases first ran
+static PHASES: Mutex<Vec<(&'static str, Duration)>> = Mutex::new(Vec::new());
+
+/// A running phase; its time is recorded when it is dropped
+pub struct Phase {
+    name: &'static str,
+    started: Instant,
+}
+
+impl Drop for Phase {
+    fn drop(&mut self) {
+        if !ENABLED.load(Ordering::Relaxed) {
+            return;
+        }
+        let elapsed = self.started.elapsed();
+        let Ok(mut phases) = PHASES.lock() else {
+            return;
+        };
+        // A phase that runs more than once adds up
+        match phases.iter_mut().find(|(name, _)| *name == self.name) {
+            Some((_, total)) => *total += elapsed,
+            None => phases.push((self.name, elapsed)),
+        }
+    }
+}
+
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Start timing a phase until the returned guard goes out of scope
+pub fn start(name: &'static str) -> Phase {
+    Phase {
+        name,
+        started: Instant::now(),
+    }
+}
+
+/// Print the time spent per phase on stderr, if timing is enabled
+pub fn report(total: Duration) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    let Ok(phases) = PHASES.lock() else {
+        return;
+    };
+    let width = phases
+        .iter()
+        .map(|(name, _)| name.len())
+        .chain(["total".len()])
+        .max()
+        .unwrap_or(0);
+
+    eprintln!("{}", tr!("\ntiming:"));
+    let mut accounted = Duration::ZERO;
+    for (name, elapsed) in phases.iter() {
+        eprintln!("  {:<width$}  {:>10.1?}", name, elapsed, width = width);
+        accounted += *elapsed;
+    }
+    if total > accounted {
+        eprintln!(
+            "  {:<width$}  {:>10.1?}",
+            "other",
+            total - accounted,
+            width = width
+        );
+    }
+    eprintln!("  {:<width$}  {:>10.1?}", "total", total, width = width);
+}
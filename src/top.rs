@@ -0,0 +1,156 @@
+use anyhow::Result;
+use clap::ValueEnum;
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::dupes::clusters;
+use crate::export::{export_rows, ExportRow};
+use crate::i18n::tr;
+use crate::state::repo_root;
+use crate::style::{self, Element};
+use crate::{collect_current_matches, terminal_width};
+
+/// What `top` ranks TODOs by
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum TopBy {
+    /// Priority score (see the `[score]` config)
+    Score,
+    /// Days since the line last changed
+    Age,
+    /// Number of near-identical copies; one row per group
+    Duplicates,
+}
+
+/// Cut text to `width` characters, marking the cut with "…" ("..." with --plain)
+pub fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    let ellipsis = style::symbol("…", "...");
+    let mut cut: String = text
+        .chars()
+        .take(width.saturating_sub(ellipsis.chars().count()))
+        .collect();
+    cut.push_str(ellipsis);
+    cut
+}
+
+/// Print the top `limit` TODOs as a compact table
+pub fn top(
+    pattern: &str,
+    file_type: Option<&str>,
+    directory: &Path,
+    by: TopBy,
+    limit: usize,
+    threshold: f64,
+) -> Result<()> {
+    let rows = export_rows(pattern, file_type, directory)?;
+
+    // (copies, row); rows arrive highest score first, which breaks ties
+    let mut ranked: Vec<(usize, &ExportRow)> = match by {
+        TopBy::Score => rows.iter().map(|r| (1, r)).collect(),
+        TopBy::Age => {
+            let mut ranked: Vec<(usize, &ExportRow)> = rows.iter().map(|r| (1, r)).collect();
+            ranked.sort_by_key(|(_, r)| std::cmp::Reverse(r.age_days));
+            ranked
+        }
+        TopBy::Duplicates => {
+            let root = repo_root(directory);
+            let by_id: HashMap<&str, &ExportRow> =
+                rows.iter().map(|r| (r.id.as_str(), r)).collect();
+            let matches = collect_current_matches(pattern, file_type, directory)?;
+            // Each group is represented by its highest-scoring copy
+            clusters(&matches, threshold)
+                .into_iter()
+                .filter(|group| group.len() > 1)
+                .filter_map(|group| {
+                    let best = group
+                        .iter()
+                        .filter_map(|m| by_id.get(m.id(&root).as_str()).copied())
+                        .max_by(|a, b| a.score.total_cmp(&b.score))?;
+                    Some((group.len(), best))
+                })
+                .collect()
+        }
+    };
+    ranked.truncate(limit);
+
+    if ranked.is_empty() {
+        match by {
+            TopBy::Duplicates => println!("{}", tr!("No duplicate '{}'s found.", pattern)),
+            _ => println!("{}", tr!("No '{}'s found.", pattern)),
+        }
+        return Ok(());
+    }
+
+    let locations: Vec<String> = ranked
+        .iter()
+        .map(|(_, r)| format!("{}:{}", r.file, r.line_number))
+        .collect();
+    let owners: Vec<&str> = ranked
+        .iter()
+        .map(|(_, r)| r.owner.as_deref().unwrap_or("-"))
+        .collect();
+    let location_width = locations
+        .iter()
+        .map(|l| l.chars().count())
+        .max()
+        .unwrap_or(0);
+    let (owner, location, text) = (tr!("OWNER"), tr!("LOCATION"), tr!("TEXT"));
+    let owner_width = owners
+        .iter()
+        .map(|o| o.chars().count())
+        .chain([owner.chars().count()])
+        .max()
+        .unwrap_or(5);
+    let copies = by == TopBy::Duplicates;
+
+    let mut header = format!(
+        "{:>5}  {:>5}  {:<6}  ",
+        tr!("SCORE"),
+        tr!("AGE"),
+        tr!("SEV")
+    );
+    if copies {
+        header.push_str(&format!("{:>6}  ", tr!("COPIES")));
+    }
+    header.push_str(&format!(
+        "{:<ow$}  {:<lw$}  {}",
+        owner,
+        location,
+        text,
+        ow = owner_width,
+        lw = location_width
+    ));
+    // Whatever the other columns leave over goes to the text
+    let text_width = terminal_width().map(|w| {
+        w.saturating_sub(header.chars().count() - text.chars().count())
+            .max(20)
+    });
+    println!("{}", style::paint(Element::Heading, &header));
+
+    for (((count, row), location), owner) in ranked.iter().zip(&locations).zip(&owners) {
+        let mut line = format!(
+            "{:>5}  {:>4}d  {:<6}  ",
+            row.score, row.age_days, row.severity
+        );
+        if copies {
+            line.push_str(&format!("{:>6}  ", count));
+        }
+        let text = match text_width {
+            Some(width) => truncate(&row.text, width),
+            None => row.text.clone(),
+        };
+        println!(
+            "{}{:<ow$}  {}{}  {}",
+            line,
+            style::paint(Element::Path, owner),
+            location,
+            " ".repeat(location_width - location.chars().count()),
+            text,
+            ow = owner_width
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,100 @@
+use anyhow::Result;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::collect_current_matches;
+use crate::i18n::tr;
+use crate::style::{self, Element};
+
+/// A directory or file with the number of TODOs in and under it
+#[derive(Default)]
+struct Node {
+    count: usize,
+    children: BTreeMap<String, Node>,
+}
+
+impl Node {
+    fn add(&mut self, components: &[String]) {
+        self.count += 1;
+        if let Some((first, rest)) = components.split_first() {
+            self.children.entry(first.clone()).or_default().add(rest);
+        }
+    }
+}
+
+/// Print the repository as a tree with the TODO count of every directory and file under
+/// `directory` and its share of the total, biggest first, down to `depth` levels
+pub fn tree(
+    pattern: &str,
+    file_type: Option<&str>,
+    directory: &Path,
+    depth: Option<usize>,
+) -> Result<()> {
+    let matches = collect_current_matches(pattern, file_type, directory)?;
+    if matches.is_empty() {
+        println!("{}", tr!("No '{}'s found.", pattern));
+        return Ok(());
+    }
+
+    let mut root = Node::default();
+    for m in &matches {
+        // Paths from ripgrep already include the search directory
+        let path = Path::new(&m.file);
+        let relative = path.strip_prefix(directory).unwrap_or(path);
+        let components: Vec<String> = relative
+            .components()
+            .filter(|c| !matches!(c, std::path::Component::CurDir))
+            .map(|c| c.as_os_str().to_string_lossy().into_owned())
+            .collect();
+        root.add(&components);
+    }
+
+    println!(
+        "{} {}",
+        style::paint(Element::Path, directory.display()),
+        style::paint(Element::Heading, root.count)
+    );
+    print_children(&root, root.count, "", 1, depth);
+    Ok(())
+}
+
+fn print_children(node: &Node, total: usize, indent: &str, level: usize, depth: Option<usize>) {
+    if depth.is_some_and(|depth| level > depth) {
+        return;
+    }
+    let mut children: Vec<(&String, &Node)> = node.children.iter().collect();
+    children.sort_by(|(a_name, a), (b_name, b)| b.count.cmp(&a.count).then(a_name.cmp(b_name)));
+
+    let last = children.len().saturating_sub(1);
+    for (idx, (name, child)) in children.into_iter().enumerate() {
+        let (branch, continuation) = if idx == last {
+            (style::symbol("└── ", "`-- "), "    ")
+        } else {
+            (style::symbol("├── ", "|-- "), style::symbol("│   ", "|   "))
+        };
+        let is_dir = !child.children.is_empty();
+        let name = if is_dir {
+            style::paint(Element::Path, format!("{}/", name))
+        } else {
+            name.to_string()
+        };
+        println!(
+            "{}{}{} {} {}",
+            indent,
+            branch,
+            name,
+            style::paint(Element::Heading, child.count),
+            style::paint(
+                Element::Context,
+                format!("({:.0}%)", child.count as f64 * 100.0 / total as f64)
+            )
+        );
+        print_children(
+            child,
+            total,
+            &format!("{}{}", indent, continuation),
+            level + 1,
+            depth,
+        );
+    }
+}
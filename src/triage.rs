@@ -0,0 +1,217 @@
+use anyhow::{Context, Result};
+use chrono::{Local, NaiveDate};
+use std::io::Write;
+use std::path::Path;
+use std::process::Command;
+
+use crate::dates;
+use crate::i18n::tr;
+use crate::state::{Decision, State, TriageEntry};
+use crate::style::{self, Element};
+use crate::{collect_current_matches, read_file_lines, CurrentMatch};
+
+/// Whether a TODO still needs a decision: never triaged, or snoozed until a date that has passed
+fn needs_triage(state: &State, id: &str, today: NaiveDate) -> bool {
+    match state.triage.get(id) {
+        None => true,
+        Some(entry) => entry.decision == Decision::Snoozed && entry.until.is_none_or(|u| u < today),
+    }
+}
+
+/// Print a question and read one trimmed line of input. `None` means stdin was closed.
+fn prompt(question: &str) -> Result<Option<String>> {
+    print!("{}", question);
+    std::io::stdout().flush()?;
+    let mut answer = String::new();
+    if std::io::stdin().read_line(&mut answer)? == 0 {
+        return Ok(None);
+    }
+    Ok(Some(answer.trim().to_string()))
+}
+
+/// Open the file at the match in $VISUAL / $EDITOR (falling back to vi)
+pub fn open_in_editor(file: &str, line_number: usize) -> Result<()> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+
+    // $EDITOR may carry its own arguments, e.g. "code -w"
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().unwrap_or("vi");
+    Command::new(program)
+        .args(parts)
+        .arg(format!("+{}", line_number))
+        .arg(file)
+        .status()
+        .with_context(|| tr!("Failed to run editor '{}'", editor))?;
+    Ok(())
+}
+
+/// Show the match with a few lines around it
+fn print_match(m: &CurrentMatch, id: &str) {
+    println!(
+        "{}:{} [{}]",
+        style::paint(Element::Path, &m.file),
+        style::paint(Element::LineNumber, m.line_number),
+        id
+    );
+    // Paths from ripgrep already include the search directory
+    let Some(lines) = read_file_lines(&m.file, Path::new("")) else {
+        println!("  {}", m.line_content.trim());
+        return;
+    };
+    let start = m.line_number.saturating_sub(2).max(1);
+    let end = (m.line_number + 2).min(lines.len());
+    for i in start..=end {
+        if i == m.line_number {
+            println!(
+                "{}: {}",
+                style::paint(Element::LineNumber, format!("{:>4}", i)),
+                style::paint(Element::Match, &lines[i - 1])
+            );
+        } else {
+            println!(
+                "{}",
+                style::paint(Element::Context, format!("{:>4}: {}", i, lines[i - 1]))
+            );
+        }
+    }
+}
+
+/// List the TODOs that have not been triaged yet, or walk through them one at a time
+pub fn triage(
+    pattern: &str,
+    file_type: Option<&str>,
+    directory: &Path,
+    interactive: bool,
+) -> Result<()> {
+    let today = Local::now().date_naive();
+    let mut state = State::load(directory)?;
+
+    let matches = collect_current_matches(pattern, file_type, directory)?;
+    let pending: Vec<(String, &CurrentMatch)> = matches
+        .iter()
+        .map(|m| (m.id(&state.root), m))
+        .filter(|(id, _)| needs_triage(&state, id, today))
+        .collect();
+
+    if pending.is_empty() {
+        println!("{}", tr!("Nothing to triage."));
+        return Ok(());
+    }
+
+    if !interactive {
+        for (id, m) in &pending {
+            println!(
+                "{}  {}:{}: {}",
+                id,
+                style::paint(Element::Path, &m.file),
+                style::paint(Element::LineNumber, m.line_number),
+                m.line_content.trim()
+            );
+        }
+        println!("{}", tr!("\n{} '{}'(s) to triage.", pending.len(), pattern));
+        return Ok(());
+    }
+
+    let total = pending.len();
+    for (idx, (id, m)) in pending.into_iter().enumerate() {
+        println!("\n({}/{})", idx + 1, total);
+        print_match(m, &id);
+
+        let entry = |decision, issue, until| TriageEntry {
+            decision,
+            decided_on: today,
+            file: m.relative_file(&state.root),
+            text: m.line_content.trim().to_string(),
+            issue,
+            until,
+        };
+
+        loop {
+            let Some(answer) = prompt("[o]pen, [i]ssue, [a]ccept, [s]nooze, s[k]ip, [q]uit: ")?
+            else {
+                return Ok(());
+            };
+
+            match answer.as_str() {
+                "o" => open_in_editor(&m.file, m.line_number)?,
+                "i" => {
+                    let Some(issue) = prompt("Issue key (e.g., #482): ")? else {
+                        return Ok(());
+                    };
+                    if issue.is_empty() {
+                        continue;
+                    }
+                    state
+                        .triage
+                        .insert(id.clone(), entry(Decision::Filed, Some(issue), None));
+                    state.save()?;
+                    break;
+                }
+                "a" => {
+                    state
+                        .triage
+                        .insert(id.clone(), entry(Decision::Accepted, None, None));
+                    state.save()?;
+                    break;
+                }
+                "s" => {
+                    let Some(until) = prompt("Snooze until (YYYY-MM-DD, 2025-W14 or 2025-03): ")?
+                    else {
+                        return Ok(());
+                    };
+                    match dates::parse_end(&until) {
+                        Ok(until) => {
+                            state
+                                .triage
+                                .insert(id.clone(), entry(Decision::Snoozed, None, Some(until)));
+                            state.save()?;
+                            break;
+                        }
+                        Err(e) => println!("{}", e),
+                    }
+                }
+                "k" | "" => break,
+                "q" => return Ok(()),
+                _ => println!("{}", tr!("Unknown action '{}'.", answer)),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Snooze one TODO by ID until a date
+pub fn snooze(id: &str, until: NaiveDate, pattern: &str, directory: &Path) -> Result<()> {
+    let mut state = State::load(directory)?;
+
+    let matches = collect_current_matches(pattern, None, directory)?;
+    let Some(m) = matches.iter().find(|m| m.id(&state.root) == id) else {
+        anyhow::bail!(tr!("No '{}' with ID {} in the current files", pattern, id));
+    };
+    state.triage.insert(
+        id.to_string(),
+        TriageEntry {
+            decision: Decision::Snoozed,
+            decided_on: Local::now().date_naive(),
+            file: m.relative_file(&state.root),
+            text: m.line_content.trim().to_string(),
+            issue: None,
+            until: Some(until),
+        },
+    );
+    state.save()?;
+
+    println!(
+        "{}",
+        tr!(
+            "Snoozed {} ({}:{}) until {}.",
+            id,
+            m.file,
+            m.line_number,
+            until
+        )
+    );
+    Ok(())
+}
@@ -0,0 +1,139 @@
+use std::borrow::Cow;
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::{is_nfc_quick, IsNormalized, UnicodeNormalization};
+
+/// Text in NFC form: composed accents, so "é" reads the same whether typed as one
+/// character or two
+pub fn normalize(text: &str) -> Cow<'_, str> {
+    if text.is_ascii() || is_nfc_quick(text.chars()) == IsNormalized::Yes {
+        Cow::Borrowed(text)
+    } else {
+        Cow::Owned(text.nfc().collect())
+    }
+}
+
+/// Text normalized and case-folded, for telling whether two lines are the same TODO
+pub fn fold(text: &str) -> String {
+    caseless::default_case_fold_str(&normalize(text))
+}
+
+/// The texts a pattern stands for: its lines, so a pattern file joined with newlines finds
+/// any of them
+pub fn alternatives(pattern: &str) -> impl Iterator<Item = &str> {
+    pattern.split('\n')
+}
+
+/// Every alternative of the pattern in every spelling (see `variants`)
+pub fn spellings(pattern: &str) -> Vec<String> {
+    alternatives(pattern)
+        .flat_map(|p| std::iter::once(p.to_string()).chain(variants(p)))
+        .collect()
+}
+
+/// Whether `line` contains `pattern` (any of its alternatives) once both are normalized
+/// and case-folded
+pub fn contains(line: &str, pattern: &str) -> bool {
+    // Not for other text: "e" is in a decomposed "é", but not in the letter it spells
+    if line.is_ascii() && alternatives(pattern).any(|p| line.contains(p)) {
+        return true;
+    }
+    let folded = fold(line);
+    alternatives(pattern).any(|p| folded.contains(&fold(p)))
+}
+
+/// Byte ranges of the pattern in `line`: the texts that are any of its alternatives once
+/// normalized and case-folded, like `contains` finds them
+pub fn spans(line: &str, pattern: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    for p in alternatives(pattern).filter(|p| !p.is_empty()) {
+        if line.is_ascii() && p.is_ascii() {
+            // ASCII folds to lowercase byte for byte, so the offsets carry over
+            let (line, p) = (line.to_ascii_lowercase(), p.to_ascii_lowercase());
+            spans.extend(
+                line.match_indices(p.as_str())
+                    .map(|(start, text)| (start, start + text.len())),
+            );
+            continue;
+        }
+        let target = fold(p);
+        let mut start = 0;
+        while let Some(c) = line[start..].chars().next() {
+            match folded_match(line, start, &target) {
+                Some(end) => {
+                    spans.push((start, end));
+                    start = end;
+                }
+                None => start += c.len_utf8(),
+            }
+        }
+    }
+    spans.sort();
+    spans.dedup();
+    spans
+}
+
+/// End of the text from `start` that folds to `target`, if any. A match can't end inside
+/// a character, so not before a combining mark that belongs to its last letter.
+fn folded_match(line: &str, start: usize, target: &str) -> Option<usize> {
+    let mut end = start;
+    let mut chars = line[start..].chars().peekable();
+    while let Some(c) = chars.next() {
+        end += c.len_utf8();
+        let folded = fold(&line[start..end]);
+        if folded == target && !chars.peek().is_some_and(|&next| is_combining_mark(next)) {
+            return Some(end);
+        }
+        // A longer text never folds shorter, so nothing further can match
+        if folded.len() > target.len() {
+            return None;
+        }
+    }
+    None
+}
+
+/// Whether two lines are the same text apart from normalization and case
+pub fn same(a: &str, b: &str) -> bool {
+    a == b || fold(a) == fold(b)
+}
+
+/// Other spellings of the pattern that normalize to the same text, for searches (ripgrep,
+/// git's pickaxe) that can only compare bytes: composed and decomposed accents. An ASCII
+/// pattern has none, so its searches stay as cheap as they can be.
+pub fn variants(pattern: &str) -> Vec<String> {
+    if pattern.is_ascii() {
+        return Vec::new();
+    }
+    let mut variants: Vec<String> = Vec::new();
+    for form in [pattern.nfc().collect::<String>(), pattern.nfd().collect()] {
+        if form != pattern && !variants.contains(&form) {
+            variants.push(form);
+        }
+    }
+    variants
+}
+
+/// Arguments for git's pickaxe (`-S`) that find the pattern in any of its spellings
+pub fn pickaxe_args(pattern: &str) -> Vec<String> {
+    let spellings = spellings(pattern);
+    if spellings.len() == 1 {
+        return vec!["-S".to_string(), pattern.to_string()];
+    }
+    let alternatives: Vec<String> = spellings.iter().map(|p| escape_ere(p)).collect();
+    vec![
+        "--pickaxe-regex".to_string(),
+        "-S".to_string(),
+        alternatives.join("|"),
+    ]
+}
+
+/// Escape the characters POSIX extended regexes treat specially
+fn escape_ere(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if ".[]()*+?{}|^$\\".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
@@ -0,0 +1,70 @@
+use anyhow::Result;
+use std::path::Path;
+
+use crate::export::severity;
+use crate::hyperlink;
+use crate::syntax;
+use fask::metadata;
+
+/// A match as a problem for VS Code's Problems panel
+pub struct Problem<'a> {
+    pub file: &'a str,
+    pub line_number: usize,
+    pub line: &'a str,
+    /// Byte range of the pattern in `line`
+    pub span: (usize, usize),
+    pub pattern: &'a str,
+}
+
+impl Problem<'_> {
+    /// FIXME, XXX and the like are warnings, the rest info
+    fn severity(&self) -> &'static str {
+        match severity(self.line, &metadata::metadata(self.line, self.pattern)) {
+            "high" => "warning",
+            _ => "info",
+        }
+    }
+
+    /// 1-based, in UTF-16 code units like the editor counts
+    fn column(&self) -> usize {
+        self.line
+            .get(..self.span.0)
+            .map_or(0, |before| before.encode_utf16().count())
+            + 1
+    }
+
+    /// The line from the pattern to the end of the comment or string literal it is in, so
+    /// no `*/` or `";` is left over
+    fn message(&self) -> &str {
+        let end = syntax::text_end(self.file, self.line, self.span.0);
+        self.line.get(self.span.0..end).unwrap_or(self.line).trim()
+    }
+}
+
+/// Print problems as "file:line:column: severity: message" lines for a problem matcher, or
+/// with `json` one JSON object per problem with the file as a file:// URI. Paths are
+/// relative to `base`.
+pub fn print(problems: &[Problem], base: &Path, json: bool) -> Result<()> {
+    for problem in problems {
+        if json {
+            let record = serde_json::json!({
+                "uri": hyperlink::file_url(problem.file, base),
+                "line": problem.line_number,
+                "column": problem.column(),
+                "severity": problem.severity(),
+                "message": problem.message(),
+            });
+            println!("{}", record);
+        } else {
+            println!(
+                "{}:{}:{}: {}: {}",
+                base.join(problem.file).display(),
+                problem.line_number,
+                problem.column(),
+                problem.severity(),
+                problem.message()
+            );
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::pr::git;
+use crate::state::repo_root;
+
+/// A working tree of the repository other than the one fask runs in
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Worktree {
+    pub path: PathBuf,
+    /// Checked-out branch; None with a detached HEAD
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+}
+
+impl Worktree {
+    /// The branch, or the directory name with a detached HEAD
+    pub fn name(&self) -> String {
+        self.branch.clone().unwrap_or_else(|| {
+            self.path.file_name().map_or_else(
+                || self.path.display().to_string(),
+                |n| n.to_string_lossy().into_owned(),
+            )
+        })
+    }
+}
+
+/// The other worktrees of the repository containing `directory`: its linked worktrees, and
+/// the main one when `directory` is in a linked one. Bare entries and worktrees whose
+/// directory is gone are left out.
+pub fn others(directory: &Path) -> Result<Vec<Worktree>> {
+    let list = git(&["worktree", "list", "--porcelain"], directory)?;
+    let current = canonical(&repo_root(directory));
+
+    // Blocks of "worktree <path>", "HEAD <sha>", then "branch <ref>" or "detached", and
+    // "bare", "locked" or "prunable" lines, separated by blank lines
+    let mut worktrees = Vec::new();
+    for block in list.split("\n\n") {
+        let mut path = None;
+        let mut branch = None;
+        let mut usable = true;
+        for line in block.lines() {
+            if let Some(p) = line.strip_prefix("worktree ") {
+                path = Some(PathBuf::from(p));
+            } else if let Some(b) = line.strip_prefix("branch ") {
+                branch = Some(b.strip_prefix("refs/heads/").unwrap_or(b).to_string());
+            } else if line == "bare" || line.starts_with("prunable") {
+                usable = false;
+            }
+        }
+        let Some(path) = path.filter(|p| usable && p.is_dir()) else {
+            continue;
+        };
+        if canonical(&path) != current {
+            worktrees.push(Worktree { path, branch });
+        }
+    }
+    Ok(worktrees)
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
@@ -0,0 +1,368 @@
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use clap::Args;
+use flate2::write::DeflateEncoder;
+use flate2::{Compression, Crc};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::PathBuf;
+
+use crate::export::ExportRow;
+use crate::i18n::tr;
+
+#[derive(Args)]
+pub struct XlsxArgs {
+    /// File to write the spreadsheet to ("-" for stdout)
+    #[arg(long, default_value = "todos.xlsx")]
+    pub xlsx_file: PathBuf,
+}
+
+/// Cell styles, as indexes into `cellXfs` in STYLES
+const HEADER: usize = 1;
+const DATE: usize = 2;
+
+const STYLES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<styleSheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">
+<fonts count="2"><font><sz val="11"/><name val="Calibri"/></font><font><b/><sz val="11"/><name val="Calibri"/></font></fonts>
+<fills count="2"><fill><patternFill patternType="none"/></fill><fill><patternFill patternType="gray125"/></fill></fills>
+<borders count="1"><border><left/><right/><top/><bottom/><diagonal/></border></borders>
+<cellStyleXfs count="1"><xf numFmtId="0" fontId="0" fillId="0" borderId="0"/></cellStyleXfs>
+<cellXfs count="3"><xf numFmtId="0" fontId="0" fillId="0" borderId="0" xfId="0"/><xf numFmtId="0" fontId="1" fillId="0" borderId="0" xfId="0" applyFont="1"/><xf numFmtId="14" fontId="0" fillId="0" borderId="0" xfId="0" applyNumberFormat="1"/></cellXfs>
+</styleSheet>"#;
+
+const CONTENT_TYPES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
+<Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>
+<Default Extension="xml" ContentType="application/xml"/>
+<Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/>
+<Override PartName="/xl/worksheets/sheet1.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/>
+<Override PartName="/xl/worksheets/sheet2.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/>
+<Override PartName="/xl/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.styles+xml"/>
+</Types>"#;
+
+const ROOT_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/>
+</Relationships>"#;
+
+const WORKBOOK: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">
+<sheets><sheet name="TODOs" sheetId="1" r:id="rId1"/><sheet name="Summary" sheetId="2" r:id="rId2"/></sheets>
+</workbook>"#;
+
+const WORKBOOK_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">
+<Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/>
+<Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet2.xml"/>
+<Relationship Id="rId3" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles" Target="styles.xml"/>
+</Relationships>"#;
+
+/// A spreadsheet cell
+enum Cell {
+    Text(String),
+    Number(f64),
+    Date(NaiveDate),
+    Empty,
+}
+
+/// Escape text for XML, dropping the control characters XML 1.0 can't hold
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\t' | '\n' | '\r' => out.push(c),
+            c if (c as u32) < 0x20 => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// "A", "B", ..., "Z", "AA", ... for a column counting from 0
+fn column_name(mut idx: usize) -> String {
+    let mut name = Vec::new();
+    loop {
+        name.push(b'A' + (idx % 26) as u8);
+        if idx < 26 {
+            break;
+        }
+        idx = idx / 26 - 1;
+    }
+    name.reverse();
+    String::from_utf8(name).unwrap_or_default()
+}
+
+/// Excel's serial day number: days since 1899-12-30
+fn serial(date: NaiveDate) -> i64 {
+    let epoch = NaiveDate::from_ymd_opt(1899, 12, 30).expect("valid date");
+    (date - epoch).num_days()
+}
+
+/// A worksheet's XML. The rows at the indexes in `headers` are bold.
+fn sheet(rows: &[Vec<Cell>], headers: &[usize], widths: &[usize]) -> String {
+    let mut xml = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">"#,
+    );
+    xml.push_str("<cols>");
+    for (idx, width) in widths.iter().enumerate() {
+        xml.push_str(&format!(
+            r#"<col min="{0}" max="{0}" width="{1}" customWidth="1"/>"#,
+            idx + 1,
+            width
+        ));
+    }
+    xml.push_str("</cols><sheetData>");
+    for (row_idx, row) in rows.iter().enumerate() {
+        let r = row_idx + 1;
+        let style = if headers.contains(&row_idx) {
+            HEADER
+        } else {
+            0
+        };
+        xml.push_str(&format!(r#"<row r="{}">"#, r));
+        for (col_idx, cell) in row.iter().enumerate() {
+            let at = format!("{}{}", column_name(col_idx), r);
+            match cell {
+                Cell::Text(text) => xml.push_str(&format!(
+                    r#"<c r="{}" s="{}" t="inlineStr"><is><t xml:space="preserve">{}</t></is></c>"#,
+                    at,
+                    style,
+                    escape(text)
+                )),
+                Cell::Number(n) => {
+                    xml.push_str(&format!(r#"<c r="{}" s="{}"><v>{}</v></c>"#, at, style, n))
+                }
+                Cell::Date(d) => xml.push_str(&format!(
+                    r#"<c r="{}" s="{}"><v>{}</v></c>"#,
+                    at,
+                    DATE,
+                    serial(*d)
+                )),
+                Cell::Empty => {}
+            }
+        }
+        xml.push_str("</row>");
+    }
+    xml.push_str("</sheetData></worksheet>");
+    xml
+}
+
+fn text(s: &str) -> Cell {
+    Cell::Text(s.to_string())
+}
+
+/// The raw matches, one row each
+fn matches_sheet(rows: &[ExportRow]) -> String {
+    let mut cells = vec![[
+        "ID",
+        "File",
+        "Line",
+        "Text",
+        "Owner",
+        "Severity",
+        "Age (days)",
+        "Deadline",
+        "Score",
+    ]
+    .map(text)
+    .into()];
+    for row in rows {
+        cells.push(vec![
+            text(&row.id),
+            text(&row.file),
+            Cell::Number(row.line_number as f64),
+            text(&row.text),
+            row.owner.as_deref().map_or(Cell::Empty, text),
+            text(row.severity),
+            Cell::Number(row.age_days as f64),
+            row.deadline.map_or(Cell::Empty, Cell::Date),
+            Cell::Number((row.score * 10.0).round() / 10.0),
+        ]);
+    }
+    sheet(&cells, &[0], &[10, 40, 8, 60, 16, 10, 11, 12, 8])
+}
+
+/// Counts for one group of TODOs in the summary
+#[derive(Default)]
+struct Group {
+    count: usize,
+    high: usize,
+    medium: usize,
+    low: usize,
+    total_age: i64,
+    oldest: i64,
+}
+
+impl Group {
+    fn add(&mut self, row: &ExportRow) {
+        self.count += 1;
+        match row.severity {
+            "high" => self.high += 1,
+            "medium" => self.medium += 1,
+            _ => self.low += 1,
+        }
+        self.total_age += row.age_days;
+        self.oldest = self.oldest.max(row.age_days);
+    }
+
+    fn cells(&self, name: &str) -> Vec<Cell> {
+        vec![
+            text(name),
+            Cell::Number(self.count as f64),
+            Cell::Number(self.high as f64),
+            Cell::Number(self.medium as f64),
+            Cell::Number(self.low as f64),
+            Cell::Number((self.total_age as f64 / self.count.max(1) as f64).round()),
+            Cell::Number(self.oldest as f64),
+        ]
+    }
+}
+
+/// Pivot-style tables: TODOs by directory and by owner, with severities and ages
+fn summary_sheet(rows: &[ExportRow]) -> String {
+    let mut by_directory: BTreeMap<String, Group> = BTreeMap::new();
+    let mut by_owner: BTreeMap<String, Group> = BTreeMap::new();
+    for row in rows {
+        let directory = row.file.rsplit_once('/').map_or(".", |(dir, _)| dir);
+        by_directory
+            .entry(directory.to_string())
+            .or_default()
+            .add(row);
+        let owner = row.owner.as_deref().unwrap_or("(nobody)");
+        by_owner.entry(owner.to_string()).or_default().add(row);
+    }
+
+    let columns = [
+        "TODOs",
+        "High",
+        "Medium",
+        "Low",
+        "Average age (days)",
+        "Oldest (days)",
+    ];
+    let mut cells: Vec<Vec<Cell>> = Vec::new();
+    let mut headers = Vec::new();
+    for (title, groups) in [("Directory", &by_directory), ("Owner", &by_owner)] {
+        if !cells.is_empty() {
+            cells.push(Vec::new());
+        }
+        headers.push(cells.len());
+        cells.push(std::iter::once(title).chain(columns).map(text).collect());
+        let mut total = Group::default();
+        for (name, group) in groups {
+            cells.push(group.cells(name));
+            total.count += group.count;
+            total.high += group.high;
+            total.medium += group.medium;
+            total.low += group.low;
+            total.total_age += group.total_age;
+            total.oldest = total.oldest.max(group.oldest);
+        }
+        headers.push(cells.len());
+        cells.push(total.cells("Total"));
+    }
+    sheet(&cells, &headers, &[40, 8, 8, 8, 8, 18, 14])
+}
+
+/// A zip archive in memory: deflated entries, then the central directory
+struct Zip {
+    data: Vec<u8>,
+    central: Vec<u8>,
+    entries: u16,
+}
+
+impl Zip {
+    fn new() -> Zip {
+        Zip {
+            data: Vec::new(),
+            central: Vec::new(),
+            entries: 0,
+        }
+    }
+
+    fn add(&mut self, name: &str, content: &[u8]) -> Result<()> {
+        let mut crc = Crc::new();
+        crc.update(content);
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(content)?;
+        let compressed = encoder.finish()?;
+        let offset = self.data.len() as u32;
+
+        // Version 2.0, no flags, deflate, time and date of 1980-01-01 00:00
+        let common = |out: &mut Vec<u8>| {
+            out.extend_from_slice(&20u16.to_le_bytes());
+            out.extend_from_slice(&0u16.to_le_bytes());
+            out.extend_from_slice(&8u16.to_le_bytes());
+            out.extend_from_slice(&0u16.to_le_bytes());
+            out.extend_from_slice(&0x21u16.to_le_bytes());
+            out.extend_from_slice(&crc.sum().to_le_bytes());
+            out.extend_from_slice(&(compressed.len() as u32).to_le_bytes());
+            out.extend_from_slice(&(content.len() as u32).to_le_bytes());
+            out.extend_from_slice(&(name.len() as u16).to_le_bytes());
+            out.extend_from_slice(&0u16.to_le_bytes());
+        };
+
+        self.data.extend_from_slice(&0x04034b50u32.to_le_bytes());
+        common(&mut self.data);
+        self.data.extend_from_slice(name.as_bytes());
+        self.data.extend_from_slice(&compressed);
+
+        self.central.extend_from_slice(&0x02014b50u32.to_le_bytes());
+        self.central.extend_from_slice(&20u16.to_le_bytes());
+        common(&mut self.central);
+        // Comment length, disk, internal and external attributes, then the offset
+        self.central.extend_from_slice(&[0; 10]);
+        self.central.extend_from_slice(&offset.to_le_bytes());
+        self.central.extend_from_slice(name.as_bytes());
+        self.entries += 1;
+        Ok(())
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        let central_offset = self.data.len() as u32;
+        let central_size = self.central.len() as u32;
+        self.data.append(&mut self.central);
+        self.data.extend_from_slice(&0x06054b50u32.to_le_bytes());
+        self.data.extend_from_slice(&[0; 4]);
+        self.data.extend_from_slice(&self.entries.to_le_bytes());
+        self.data.extend_from_slice(&self.entries.to_le_bytes());
+        self.data.extend_from_slice(&central_size.to_le_bytes());
+        self.data.extend_from_slice(&central_offset.to_le_bytes());
+        self.data.extend_from_slice(&0u16.to_le_bytes());
+        self.data
+    }
+}
+
+/// Write the TODOs as an Excel workbook: a sheet of the matches and a summary sheet
+pub fn write_workbook(rows: &[ExportRow], args: &XlsxArgs) -> Result<()> {
+    let mut zip = Zip::new();
+    zip.add("[Content_Types].xml", CONTENT_TYPES.as_bytes())?;
+    zip.add("_rels/.rels", ROOT_RELS.as_bytes())?;
+    zip.add("xl/workbook.xml", WORKBOOK.as_bytes())?;
+    zip.add("xl/_rels/workbook.xml.rels", WORKBOOK_RELS.as_bytes())?;
+    zip.add("xl/styles.xml", STYLES.as_bytes())?;
+    zip.add("xl/worksheets/sheet1.xml", matches_sheet(rows).as_bytes())?;
+    zip.add("xl/worksheets/sheet2.xml", summary_sheet(rows).as_bytes())?;
+    let workbook = zip.finish();
+
+    if args.xlsx_file.as_os_str() == "-" {
+        std::io::stdout().write_all(&workbook)?;
+        return Ok(());
+    }
+    std::fs::write(&args.xlsx_file, workbook)
+        .with_context(|| tr!("Failed to write {}", args.xlsx_file.display()))?;
+    println!(
+        "{}",
+        tr!(
+            "Wrote {} TODO(s) to {}.",
+            rows.len(),
+            args.xlsx_file.display()
+        )
+    );
+    Ok(())
+}